@@ -64,6 +64,15 @@ async fn main() -> Result<()> {
 
                     fungible_token: ft_actor_id.into(),
                     non_fungible_token: nft_actor_id.into(),
+                    payment_mode: Default::default(),
+                    currencies: vec![],
+
+                    penalty_schedule: Default::default(),
+                    resale_royalty: Default::default(),
+                    platform_fee: None,
+                    transaction_ttl: None,
+
+                    arbiter: ALICE.into(),
                 },
             )
             .await?;
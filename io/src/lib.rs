@@ -20,7 +20,11 @@ impl Metadata for ContractMetadata {
 /// For more info about fields, see [`Initialize`].
 #[derive(Encode, Decode, TypeInfo, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Clone, Hash)]
 pub struct State {
+    /// Sorted by [`ItemId`].
     pub items: Vec<(ItemId, ItemInfo)>,
+    /// Sorted by [`ItemId`]. Used by
+    /// [`item_history()`](../supply_chain_state/metafns/fn.item_history.html).
+    pub item_histories: Vec<(ItemId, Vec<HistoryRecord>)>,
 
     pub producers: Vec<ActorId>,
     pub distributors: Vec<ActorId>,
@@ -28,10 +32,46 @@ pub struct State {
 
     pub fungible_token: ActorId,
     pub non_fungible_token: ActorId,
+    pub payment_mode: PaymentMode,
+    /// See [`Initialize::currencies`].
+    pub currencies: Vec<ActorId>,
 
-    /// Used by
-    /// [`is_action_cached()`](../supply_chain_state/metafns/fn.is_action_cached.html).
-    /// Also see [`TransactionKind`].
+    pub penalty_schedule: PenaltySchedule,
+    pub resale_royalty: u16,
+
+    pub platform_fee: Option<PlatformFee>,
+    /// Total amounts of fungible tokens collected by
+    /// [`State::platform_fee`]s per FT contract.
+    pub collected_fees: Vec<(ActorId, u128)>,
+
+    /// See [`Initialize::transaction_ttl`].
+    pub transaction_ttl: Option<u64>,
+
+    /// See [`AdminAction`].
+    pub admin: ActorId,
+    pub arbiter: ActorId,
+
+    /// See [`SubscriberAction`].
+    pub subscribers: Vec<(ActorId, SubscriptionFilter)>,
+
+    /// See [`LotAction`].
+    pub lots: Vec<(LotId, LotInfo)>,
+
+    /// Balances of producers' recall pools. See
+    /// [`ProducerAction::FundRecallPool`].
+    pub recall_pools: Vec<(ActorId, u128)>,
+
+    /// Sorted by [`ItemId`]. See [`OfferAction`].
+    pub offers: Vec<(ItemId, Vec<(ActorId, Offer)>)>,
+
+    /// Sorted by [`ItemId`]. See [`AuctionAction`].
+    pub auctions: Vec<(ItemId, Auction)>,
+
+    /// Sorted by [`ActorId`]. Used by
+    /// [`is_action_cached()`](../supply_chain_state/metafns/fn.is_action_cached.html)
+    /// &
+    /// [`cached_actions()`](../supply_chain_state/metafns/fn.cached_actions.html).
+    /// Expired actions aren't included. Also see [`TransactionKind`].
     pub cached_actions: Vec<(ActorId, CachedAction)>,
 }
 
@@ -46,16 +86,91 @@ pub enum CachedAction {
     PutUpForSale(ItemId),
     Approve(ItemId),
     Receive(ItemId),
+    CancelPurchase(ItemId),
+    WithdrawFromSale(ItemId),
+    FailDelivery(ItemId),
+    ExpirePurchase(ItemId),
+    Resolve(ItemId),
+    ProduceBatch,
+    Lot(LotId),
+    FundRecallPool,
+    Recall,
+    Offer(ItemId),
+    Auction(ItemId),
     Other,
 }
 
+impl CachedAction {
+    /// Returns an [`ItemId`] of an item that the action locks, if any.
+    ///
+    /// While such an action is being processed, the item is locked, and all
+    /// other actions for it fail with [`Error::ItemLocked`]. If the action
+    /// fails after some of its steps were executed, the item stays locked
+    /// until the action is retried successfully, aborted, or purged (see
+    /// [`TransactionKind`]). A lock of an action that has expired or has been
+    /// evicted from the cache is ignored.
+    ///
+    /// An action for a lot ([`CachedAction::Lot`]) locks all items of the lot
+    /// in the same way.
+    pub fn item_id(self) -> Option<ItemId> {
+        match self {
+            Self::Purchase(item_id)
+            | Self::PutUpForSale(item_id)
+            | Self::Approve(item_id)
+            | Self::Receive(item_id)
+            | Self::CancelPurchase(item_id)
+            | Self::WithdrawFromSale(item_id)
+            | Self::FailDelivery(item_id)
+            | Self::ExpirePurchase(item_id)
+            | Self::Resolve(item_id)
+            | Self::Offer(item_id)
+            | Self::Auction(item_id) => Some(item_id),
+            Self::ProduceBatch
+            | Self::Lot(_)
+            | Self::FundRecallPool
+            | Self::Recall
+            | Self::Other => None,
+        }
+    }
+}
+
 impl From<InnerAction> for Option<CachedAction> {
     fn from(action: InnerAction) -> Self {
         match action {
             InnerAction::Producer(ProducerAction::Produce { .. }) => Some(CachedAction::Other),
+            InnerAction::Producer(ProducerAction::ProduceBatch { .. }) => {
+                Some(CachedAction::ProduceBatch)
+            }
+            InnerAction::Producer(ProducerAction::FundRecallPool(_)) => {
+                Some(CachedAction::FundRecallPool)
+            }
+            InnerAction::Producer(ProducerAction::Recall { .. }) => Some(CachedAction::Recall),
+            InnerAction::Lot(
+                LotAction::PutUpForSale { lot_id, .. }
+                | LotAction::WithdrawFromSale(lot_id)
+                | LotAction::Purchase { lot_id, .. }
+                | LotAction::Approve { lot_id, .. }
+                | LotAction::Receive(lot_id),
+            )
+            | InnerAction::Deadline(DeadlineAction::FailLotDelivery(lot_id)) => {
+                Some(CachedAction::Lot(lot_id))
+            }
+            InnerAction::Offer(
+                OfferAction::Make { item_id, .. }
+                | OfferAction::Withdraw(item_id)
+                | OfferAction::Accept { item_id, .. }
+                | OfferAction::AcceptCounter(item_id),
+            ) => Some(CachedAction::Offer(item_id)),
+            InnerAction::Auction(
+                AuctionAction::Start { item_id, .. } | AuctionAction::Bid { item_id, .. },
+            )
+            | InnerAction::Deadline(DeadlineAction::SettleAuction(item_id)) => {
+                Some(CachedAction::Auction(item_id))
+            }
             InnerAction::Producer(ProducerAction::PutUpForSale { item_id, .. })
             | InnerAction::Distributor(DistributorAction::PutUpForSale { item_id, .. })
-            | InnerAction::Retailer(RetailerAction::PutUpForSale { item_id, .. }) => {
+            | InnerAction::Retailer(RetailerAction::PutUpForSale { item_id, .. })
+            | InnerAction::Consumer(ConsumerAction::PutUpForSale { item_id, .. }) => {
                 Some(CachedAction::PutUpForSale(item_id))
             }
             InnerAction::Producer(ProducerAction::Approve { item_id, .. })
@@ -71,6 +186,26 @@ impl From<InnerAction> for Option<CachedAction> {
             | InnerAction::Retailer(RetailerAction::Receive(item_id)) => {
                 Some(CachedAction::Receive(item_id))
             }
+            InnerAction::Distributor(DistributorAction::CancelPurchase(item_id))
+            | InnerAction::Retailer(RetailerAction::CancelPurchase(item_id)) => {
+                Some(CachedAction::CancelPurchase(item_id))
+            }
+            InnerAction::Producer(ProducerAction::WithdrawFromSale(item_id))
+            | InnerAction::Distributor(DistributorAction::WithdrawFromSale(item_id))
+            | InnerAction::Retailer(RetailerAction::WithdrawFromSale(item_id))
+            | InnerAction::Consumer(ConsumerAction::WithdrawFromSale(item_id)) => {
+                Some(CachedAction::WithdrawFromSale(item_id))
+            }
+            InnerAction::Deadline(DeadlineAction::FailDelivery(item_id)) => {
+                Some(CachedAction::FailDelivery(item_id))
+            }
+            InnerAction::Deadline(DeadlineAction::ExpirePurchase(item_id)) => {
+                Some(CachedAction::ExpirePurchase(item_id))
+            }
+            InnerAction::Arbiter(ArbiterAction::Resolve { item_id, .. }) => {
+                Some(CachedAction::Resolve(item_id))
+            }
+            InnerAction::Retry(cached_action) => Some(cached_action),
             _ => None,
         }
     }
@@ -83,16 +218,73 @@ impl From<InnerAction> for Option<CachedAction> {
 /// items.
 pub const MAX_NUMBER_OF_ITEMS: usize = 2usize.pow(17);
 
+/// The maximum number of items in [`ProducerAction::ProduceBatch`].
+///
+/// Production of each item takes 2 steps of a transaction (minting and
+/// transferring of its NFT), and one transaction can't have more than 256
+/// steps.
+pub const MAX_BATCH_SIZE: usize = u8::MAX as usize / 2;
+
+/// The maximum number of subscribers to [`Event`]s.
+///
+/// The limited number of subscribers is required because the contract sends a
+/// notification to each of them after every processed [`Action`], and that
+/// costs gas.
+pub const MAX_NUMBER_OF_SUBSCRIBERS: usize = 128;
+
 /// An item ID.
 ///
 /// Should equal [`TokenId`] of an item's NFT.
 pub type ItemId = TokenId;
 
+/// A lot ID.
+pub type LotId = u64;
+
+/// The maximum number of items in a lot.
+///
+/// [`LotAction::Receive`] transfers an NFT of each item of a lot, a royalty to
+/// each of their producers, and up to 3 other fungible token amounts in one
+/// transaction, and one transaction can't have more than 256 steps.
+pub const MAX_LOT_SIZE: usize = (u8::MAX as usize - 3) / 2;
+
+/// The maximum number of open offers for an item.
+///
+/// [`OfferAction::Accept`] & [`OfferAction::AcceptCounter`] refund all other
+/// offers for an item in one transaction, and one transaction can't have more
+/// than 256 steps.
+pub const MAX_NUMBER_OF_OFFERS: usize = 128;
+
+/// The maximum number of records in an item's history.
+///
+/// Histories of all items are stored in the contract's memory & [`State`], so
+/// after reaching this limit, the oldest record is dropped for each new one.
+pub const MAX_HISTORY_LENGTH: usize = 32;
+
+/// 100% in basis points.
+pub const MAX_BASIS_POINTS: u16 = 10_000;
+
+/// The minimal block time in milliseconds.
+///
+/// The contract converts a time of a [`DeadlineAction`] to a delay in blocks
+/// with this value and adds 1 block, so a delayed message never arrives before
+/// its deadline. If blocks are produced slower, the message arrives later, but
+/// anyone can send a [`DeadlineAction`] manually once its deadline has passed.
+pub const BLOCK_TIME: u64 = 1000;
+
+/// Gas that the contract reserves for processing of a [`DeadlineAction`] that
+/// it sends to itself as a delayed message.
+///
+/// This amount is taken from the gas limit of a message that schedules the
+/// [`DeadlineAction`], so such a message must have at least this amount of gas
+/// in addition to gas for its own processing. If the reserved gas isn't enough
+/// to process the [`DeadlineAction`], it can be sent manually.
+pub const DEADLINE_ACTION_GAS: u64 = 20_000_000_000;
+
 /// Initializes the Supply chain contract.
 ///
 /// # Requirements
-/// - Each [`ActorId`] of `producers`, `distributors`, and `retailers` mustn't
-/// equal [`ActorId::zero()`].
+/// - Each [`ActorId`] of `producers`, `distributors`, `retailers`,
+/// `currencies`, and `arbiter` mustn't equal [`ActorId::zero()`].
 #[derive(Encode, Decode, Hash, TypeInfo, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Clone)]
 pub struct Initialize {
     /// IDs of actors that'll have the right to interact with a supply chain on
@@ -105,10 +297,124 @@ pub struct Initialize {
     /// behalf of a retailer.
     pub retailers: Vec<ActorId>,
 
-    /// A FT contract [`ActorId`].
+    /// A FT contract [`ActorId`]. Isn't used and may equal
+    /// [`ActorId::zero()`] if `payment_mode` is [`PaymentMode::Value`].
     pub fungible_token: ActorId,
     /// An NFT contract [`ActorId`].
     pub non_fungible_token: ActorId,
+    /// What payments are made with.
+    pub payment_mode: PaymentMode,
+    /// FT contracts that items can be priced in besides `fungible_token`.
+    /// Aren't used if `payment_mode` is [`PaymentMode::Value`].
+    pub currencies: Vec<ActorId>,
+
+    /// A penalty schedule for late deliveries that's used if a buyer doesn't
+    /// specify its own one on a purchase.
+    pub penalty_schedule: PenaltySchedule,
+    /// A share of an item's price in basis points that its producer receives
+    /// on each resale between consumers (see [`ConsumerAction::Purchase`]).
+    /// Mustn't be greater than [`MAX_BASIS_POINTS`].
+    pub resale_royalty: u16,
+    /// A fee for sales, if any. Can be changed by
+    /// [`AdminAction::SetPlatformFee`].
+    pub platform_fee: Option<PlatformFee>,
+    /// Time in milliseconds after which a cached action expires (see
+    /// [`TransactionKind`]), if any. Can be changed by
+    /// [`AdminAction::SetTransactionTtl`].
+    pub transaction_ttl: Option<u64>,
+
+    /// An [`ActorId`] of an actor that'll resolve disputes between sellers and
+    /// buyers (see [`ArbiterAction`]).
+    pub arbiter: ActorId,
+}
+
+/// A payment mode of a supply chain.
+#[derive(
+    Encode, Decode, TypeInfo, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash,
+)]
+pub enum PaymentMode {
+    /// Payments are made with fungible tokens of the FT contract from
+    /// [`Initialize`].
+    #[default]
+    FungibleToken,
+    /// Payments are made with the native value.
+    ///
+    /// Wherever docs of actions mention fungible tokens, the native value is
+    /// meant instead:
+    /// - An action that transfers fungible tokens from
+    /// [`msg::source()`](gstd::msg::source) must have exactly that amount of
+    /// value attached, otherwise it fails with [`Error::UnexpectedValue`].
+    /// - Transfers from the Supply chain contract are made by value transfers
+    /// after the action is successfully processed.
+    /// - Value attached to a failed action or an action that doesn't transfer
+    /// fungible tokens from [`msg::source()`](gstd::msg::source) is refunded
+    /// with a reply.
+    ///
+    /// **Note:** a retried action (see [`TransactionKind::Retry`]) must have
+    /// value attached again because the value attached to the failed one is
+    /// refunded.
+    Value,
+}
+
+/// A platform fee.
+///
+/// A `fee` share of a price in basis points is transferred to `treasury` each
+/// time a seller receives fungible tokens for an item: on
+/// [`DistributorAction::Receive`], [`RetailerAction::Receive`],
+/// [`LotAction::Receive`], and [`ConsumerAction::Purchase`]. The fee is
+/// deducted from the seller's amount before a producer's royalty.
+///
+/// # Requirements
+/// - `fee` mustn't be greater than [`MAX_BASIS_POINTS`].
+/// - `treasury` mustn't equal [`ActorId::zero()`].
+#[derive(
+    Encode, Decode, TypeInfo, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash,
+)]
+pub struct PlatformFee {
+    pub fee: u16,
+    pub treasury: ActorId,
+}
+
+/// A penalty schedule for late deliveries.
+///
+/// Determines a share of an item's price that a seller receives depending on
+/// how late an item is delivered. If a delivery isn't late, a seller receives
+/// the whole price. Otherwise, a seller receives `seller_share` of the last
+/// [`PenaltyStep`] whose `lateness` is reached, or the whole price if there's
+/// no such step. The rest of the price is refunded to a buyer.
+///
+/// A delivery fails once it takes twice the delivery time (see
+/// [`DeadlineAction::FailDelivery`]), so the whole price is refunded to a
+/// buyer from that moment regardless of the schedule.
+///
+/// # Requirements
+/// - `lateness` of each next step must be greater than of the previous one.
+/// - Each `lateness` must be less than [`MAX_BASIS_POINTS`], i.e. the step
+/// must be reached before the delivery fails.
+/// - Each `seller_share` mustn't be greater than [`MAX_BASIS_POINTS`].
+///
+/// The default schedule transfers a half of the price to a seller if it's
+/// late.
+#[derive(Encode, Decode, Hash, TypeInfo, Debug, PartialEq, Eq, PartialOrd, Ord, Clone)]
+pub struct PenaltySchedule(pub Vec<PenaltyStep>);
+
+impl Default for PenaltySchedule {
+    fn default() -> Self {
+        Self(vec![PenaltyStep {
+            lateness: 0,
+            seller_share: MAX_BASIS_POINTS / 2,
+        }])
+    }
+}
+
+/// A part of [`PenaltySchedule`].
+#[derive(Encode, Decode, Hash, TypeInfo, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Clone)]
+pub struct PenaltyStep {
+    /// Time by which a delivery is late, in basis points of the delivery time.
+    /// E.g., 5000 means the delivery took 1.5 times the delivery time.
+    pub lateness: u64,
+    /// A share of an item's price that a seller receives, in basis points.
+    pub seller_share: u16,
 }
 
 /// Sends the contract info about what it should do.
@@ -132,6 +438,28 @@ impl Action {
             kind: TransactionKind::Retry,
         }
     }
+
+    pub fn to_abort(self) -> Self {
+        Self {
+            action: self.action,
+            kind: TransactionKind::Abort,
+        }
+    }
+
+    /// Creates [`InnerAction::Retry`].
+    pub fn retry(action: CachedAction) -> Self {
+        Self {
+            action: InnerAction::Retry(action),
+            kind: TransactionKind::Retry,
+        }
+    }
+
+    pub fn to_purge(self) -> Self {
+        Self {
+            action: self.action,
+            kind: TransactionKind::Purge,
+        }
+    }
 }
 
 /// A part of [`Action`].
@@ -141,6 +469,27 @@ pub enum InnerAction {
     Distributor(DistributorAction),
     Retailer(RetailerAction),
     Consumer(ConsumerAction),
+    Admin(AdminAction),
+    Arbiter(ArbiterAction),
+    Deadline(DeadlineAction),
+    Subscriber(SubscriberAction),
+    Lot(LotAction),
+    Offer(OfferAction),
+    Auction(AuctionAction),
+    /// Retries a cached action of [`msg::source()`](gstd::msg::source) with
+    /// its original payload, so the payload doesn't have to be sent again.
+    ///
+    /// Is processed as [`TransactionKind::Retry`] regardless of
+    /// [`Action::kind`] unless it's [`TransactionKind::Abort`] or
+    /// [`TransactionKind::Purge`], in which case the cached action is aborted
+    /// or purged respectively.
+    ///
+    /// # Requirements
+    /// - The action must be cached (see [`TransactionKind`]), otherwise fails
+    /// with [`TransactionCacheError::TransactionNotFound`].
+    ///
+    /// On success, replies as the cached action does.
+    Retry(CachedAction),
 }
 
 /// A part of [`Action`].
@@ -152,18 +501,40 @@ pub enum InnerAction {
 /// underprocession is the lack of gas.
 ///
 /// Important notes:
-/// - Only the last sent asynchronous action for
-/// [`msg::source()`](gstd::msg::source) is cached.
+/// - Asynchronous actions are cached per [`msg::source()`](gstd::msg::source)
+/// & [`CachedAction`], so an actor can have several cached actions at once,
+/// but only the last sent one of each [`CachedAction`].
 /// - Non-asynchronous actions are never cached.
+/// - A cached action is removed from the cache once it's successfully
+/// processed, so only failed or still processing actions stay there.
+/// - A new action fails with [`TransactionCacheError::TransactionInProgress`]
+/// while the cached one with the same [`CachedAction`] is still being
+/// processed, e.g. awaits a reply, so it isn't processed twice. An action that
+/// has run out of gas while awaiting a reply is considered to be still
+/// processed until it's retried successfully, aborted, purged, or expired.
+/// - A new action replaces the cached one with the same [`CachedAction`] only
+/// if the cached one has no executed steps, otherwise it fails with
+/// [`TransactionCacheError::UnfinishedTransaction`], and the cached one must
+/// be retried, aborted, or purged first.
+/// - An action for an item locks the item while it's processed and after it
+/// fails partially (see [`CachedAction::item_id()`]).
 /// - There's no guarantee every underprocessed asynchronous action will be
 /// cached. Use
 /// [`is_action_cached()`](../supply_chain_state/metafns/fn.is_action_cached.html)
-/// to check if some action is cached for some [`ActorId`].
-/// - It's possible to send a retry action with a different payload, and it'll
-/// continue with it because, for some action, not all payload is saved in the
-/// cache (see [`CachedAction`]).
+/// to check if some action is cached for some [`ActorId`] or
+/// [`cached_actions()`](../supply_chain_state/metafns/fn.cached_actions.html)
+/// to get all cached actions of some [`ActorId`].
+/// - A retry action must have the same payload as the cached one, otherwise it
+/// fails with [`TransactionCacheError::MismatchedAction`]. Use
+/// [`InnerAction::Retry`] to retry a cached action without sending its payload
+/// again.
 /// - The cache memory has a limit, so when it's reached every oldest cached
 /// action is replaced with a new one.
+/// - If [`Initialize::transaction_ttl`] is set, a cached action expires once
+/// this time has passed since it was sent as [`TransactionKind::New`]. An
+/// expired action is removed from the cache when it's accessed, so it can't be
+/// retried, aborted, or purged anymore. An action that is being aborted never
+/// expires, but can be removed by [`AdminAction::PurgeTransaction`].
 #[derive(
     Default, Encode, Decode, TypeInfo, Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash,
 )]
@@ -171,6 +542,51 @@ pub enum TransactionKind {
     #[default]
     New,
     Retry,
+    /// Aborts a cached action instead of retrying it.
+    ///
+    /// Runs compensating transfers for already executed steps of the action in
+    /// the reverse order: fungible tokens & NFTs are transferred back to their
+    /// senders, and minted NFTs are burned along with their items. Item states
+    /// stay as they were before the action because the contract changes them
+    /// only after all steps are executed.
+    ///
+    /// Once an abort is started, the action can't be retried or sent again
+    /// until the abort is completed, but the abort itself can be retried
+    /// safely, e.g. if a compensating transfer fails because its recipient
+    /// hasn't approved the Supply chain contract to transfer its fungible
+    /// tokens or NFT back.
+    ///
+    /// **Note:** compensating transfers depend on counterparties of the action:
+    /// - a recipient of fungible tokens must still hold them and approve the
+    /// Supply chain contract to transfer them back;
+    /// - a recipient of an NFT, including a minted one, must still hold it and
+    /// approve the Supply chain contract to transfer it back.
+    ///
+    /// Until then, the abort fails. It doesn't expire (see
+    /// [`Initialize::transaction_ttl`]), but the admin can remove it with
+    /// [`AdminAction::PurgeTransaction`].
+    ///
+    /// If [`Initialize::payment_mode`] is [`PaymentMode::Value`], only NFT
+    /// transfers & mints are compensated, since value is sent to recipients
+    /// only after an action is successfully processed, and attached value is
+    /// refunded to [`msg::source()`](gstd::msg::source) on a failure.
+    ///
+    /// An action that is still being processed, e.g. awaits a reply, can't be
+    /// aborted, and the abort fails with
+    /// [`TransactionCacheError::TransactionInProgress`]. If the action has run
+    /// out of gas while awaiting a reply, it can be only retried, or removed by
+    /// the admin with [`AdminAction::PurgeTransaction`].
+    ///
+    /// On success, replies with [`Event::TransactionAborted`].
+    Abort,
+    /// Removes a cached action of [`msg::source()`](gstd::msg::source) from
+    /// the cache without retrying or aborting it.
+    ///
+    /// **Note:** compensating transfers aren't run, so executed steps of the
+    /// action can't be compensated after this (see [`TransactionKind::Abort`]).
+    ///
+    /// On success, replies with [`Event::TransactionPurged`].
+    Purge,
 }
 
 /// Actions for a producer.
@@ -185,12 +601,45 @@ pub enum ProducerAction {
     ///
     /// # Requirements
     /// - [`msg::source()`] must be a producer in a supply chain.
+    /// - `royalty` mustn't be greater than [`MAX_BASIS_POINTS`].
+    ///
+    /// On success, replies with [`Event::Item`] where [`ItemState`] contains
+    /// [`ItemEventState::Produced`] & [`Role::Producer`].
+    ///
+    /// [`msg::source()`]: gstd::msg::source
+    Produce {
+        token_metadata: TokenMetadata,
+        /// A share of an item's price in basis points that the producer
+        /// receives on each downstream sale. See [`ItemInfo`].
+        royalty: u16,
+    },
+
+    /// Produces several items and corresponding NFTs with given `items`
+    /// metadata in one transaction.
+    ///
+    /// Transfers the created NFTs for the items to a producer
+    /// ([`msg::source()`]).
+    ///
+    /// If the action is partially processed, retrying it with the same `items`
+    /// (see [`TransactionKind::Retry`]) continues the batch without producing
+    /// already produced items again.
+    ///
+    /// # Requirements
+    /// - [`msg::source()`] must be a producer in a supply chain.
+    /// - `items` length mustn't exceed [`MAX_BATCH_SIZE`].
+    /// - The number of items in a supply chain together with `items` mustn't
+    /// exceed [`MAX_NUMBER_OF_ITEMS`].
+    /// - `royalty` mustn't be greater than [`MAX_BASIS_POINTS`].
     ///
-    /// On success, replies with [`Event`] where [`ItemState`] contains
+    /// On success, replies with [`Event::Items`] where [`ItemState`] contains
     /// [`ItemEventState::Produced`] & [`Role::Producer`].
     ///
     /// [`msg::source()`]: gstd::msg::source
-    Produce { token_metadata: TokenMetadata },
+    ProduceBatch {
+        items: Vec<TokenMetadata>,
+        /// A royalty for all produced items. See [`ProducerAction::Produce`].
+        royalty: u16,
+    },
 
     /// Puts a produced item up for sale to distributors for given `price` on
     /// behalf of a producer.
@@ -204,9 +653,50 @@ pub enum ProducerAction {
     /// - Item's [`ItemState`] must contain [`ItemEventState::Produced`] &
     /// [`Role::Producer`].
     ///
-    /// On success, replies with [`Event`] where [`ItemState`] contains
+    /// On success, replies with [`Event::Item`] where [`ItemState`] contains
+    /// [`ItemEventState::ForSale`] & [`Role::Producer`].
+    PutUpForSale {
+        item_id: ItemId,
+        price: u128,
+        /// A FT contract that `price` is in, or [`None`] for the default one
+        /// from [`Initialize`]. Must be accepted by a supply chain (see
+        /// [`Initialize::currencies`]), otherwise the action fails with
+        /// [`Error::UnacceptedCurrency`].
+        currency: Option<ActorId>,
+    },
+
+    /// Withdraws an unsold item from sale on behalf of a producer.
+    ///
+    /// Transfers an item's NFT from the Supply chain contract
+    /// ([`exec::program_id()`](gstd::exec::program_id)) back to the producer
+    /// ([`msg::source()`]).
+    ///
+    /// # Requirements
+    /// - The item must exist in a supply chain.
+    /// - [`msg::source()`] must be the producer of the item.
+    /// - Item's [`ItemState`] must contain [`ItemEventState::ForSale`] &
+    /// [`Role::Producer`].
+    ///
+    /// On success, replies with [`Event::Item`] where [`ItemState`] contains
+    /// [`ItemEventState::Produced`] & [`Role::Producer`].
+    ///
+    /// [`msg::source()`]: gstd::msg::source
+    WithdrawFromSale(ItemId),
+
+    /// Changes a price of an item that's up for sale on behalf of a producer.
+    ///
+    /// Unlike [`ProducerAction::WithdrawFromSale`] followed by
+    /// [`ProducerAction::PutUpForSale`], doesn't transfer an item's NFT.
+    ///
+    /// # Requirements
+    /// - The item must exist in a supply chain.
+    /// - [`msg::source()`](gstd::msg::source) must be the producer of the item.
+    /// - Item's [`ItemState`] must contain [`ItemEventState::ForSale`] &
+    /// [`Role::Producer`].
+    ///
+    /// On success, replies with [`Event::Item`] where [`ItemState`] contains
     /// [`ItemEventState::ForSale`] & [`Role::Producer`].
-    PutUpForSale { item_id: ItemId, price: u128 },
+    UpdatePrice { item_id: ItemId, price: u128 },
 
     /// Approves or not a distributor's purchase on behalf of a producer.
     ///
@@ -225,8 +715,11 @@ pub enum ProducerAction {
     /// - [`msg::source()`](gstd::msg::source) must be the producer of the item.
     /// - Item's [`ItemState`] must contain [`ItemEventState::Produced`] &
     /// [`Role::Distributor`].
+    /// - If the purchase is approved, its
+    /// [`approval_deadline`](ItemInfo::approval_deadline), if any, mustn't
+    /// pass.
     ///
-    /// On success, replies with [`Event`] where [`ItemState`] contains
+    /// On success, replies with [`Event::Item`] where [`ItemState`] contains
     /// [`ItemEventState::Approved`]/[`ItemEventState::ForSale`] &
     /// [`Role::Producer`].
     Approve {
@@ -239,7 +732,11 @@ pub enum ProducerAction {
     /// producer.
     ///
     /// Starts the countdown for the delivery time specified for the item in
-    /// [`DistributorAction::Purchase`].
+    /// [`DistributorAction::Purchase`] and schedules
+    /// [`DeadlineAction::FailDelivery`] for the item.
+    ///
+    /// The message must have [`DEADLINE_ACTION_GAS`] in addition to gas for its
+    /// own processing.
     ///
     /// # Requirements
     /// - The item must exist in a supply chain.
@@ -247,15 +744,56 @@ pub enum ProducerAction {
     /// - Item's [`ItemState`] must contain [`ItemEventState::Approved`] &
     /// [`Role::Producer`].
     ///
-    /// On success, replies with [`Event`] where [`ItemState`] contains
+    /// On success, replies with [`Event::Item`] where [`ItemState`] contains
     /// [`ItemEventState::Shipped`] & [`Role::Producer`].
     Ship(ItemId),
+
+    /// Funds a producer's recall pool with given amount of fungible tokens.
+    ///
+    /// Transfers fungible tokens of the default FT contract (see
+    /// [`Initialize`]) from a producer
+    /// ([`msg::source()`](gstd::msg::source)) to the Supply chain contract
+    /// ([`exec::program_id()`](gstd::exec::program_id)). The pool is used for
+    /// refunds in [`ProducerAction::Recall`].
+    ///
+    /// # Requirements
+    /// - [`msg::source()`](gstd::msg::source) must be a producer in a supply
+    /// chain.
+    ///
+    /// On success, replies with [`Event::RecallPoolFunded`].
+    FundRecallPool(u128),
+
+    /// Recalls items with given `reason` on behalf of a producer.
+    ///
+    /// Recalled items can't be put up for sale or purchased anymore (see
+    /// [`Error::ItemRecalled`]), but trades that have already started can be
+    /// completed.
+    ///
+    /// Refunds the price of each recalled item from the producer's recall pool
+    /// (see [`ProducerAction::FundRecallPool`]) to its current holder if the
+    /// pool has enough fungible tokens for that and the item is priced in the
+    /// default currency (see [`ItemInfo::currency`]). The holder is a distributor
+    /// or retailer that has received the item and hasn't put it up for sale,
+    /// or a consumer that has purchased it. Items held by the producer, up for
+    /// sale, or being traded aren't refunded.
+    ///
+    /// # Requirements
+    /// - [`msg::source()`](gstd::msg::source) must be the producer of all
+    /// items.
+    /// - All items must exist in a supply chain and mustn't be recalled.
+    /// - The number of items mustn't exceed [`MAX_BATCH_SIZE`].
+    ///
+    /// On success, replies with [`Event::Recalled`].
+    Recall {
+        item_ids: Vec<ItemId>,
+        reason: String,
+    },
 }
 
 /// Actions for a distributor.
 ///
 /// Should be used inside [`InnerAction::Distributor`].
-#[derive(Encode, Decode, TypeInfo, Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash)]
+#[derive(Encode, Decode, TypeInfo, Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Hash)]
 pub enum DistributorAction {
     /// Purchases an item from a producer on behalf of a distributor.
     ///
@@ -264,30 +802,63 @@ pub enum DistributorAction {
     /// is received (by [`DistributorAction::Receive`]).
     ///
     /// **Note:** the item's producer must approve or not this purchase by
-    /// [`ProducerAction::Approve`].
+    /// [`ProducerAction::Approve`]. Until then, the distributor can cancel it
+    /// by [`DistributorAction::CancelPurchase`].
+    /// If `approval_time` is set and the producer hasn't approved the purchase
+    /// in time, the purchase expires (see [`DeadlineAction::ExpirePurchase`]).
     ///
     /// # Requirements
     /// - The item must exist in a supply chain.
     /// - [`msg::source()`](gstd::msg::source) must be a distributor.
+    /// - `penalty_schedule`, if any, must be valid (see [`PenaltySchedule`]).
     /// - Item's [`ItemState`] must contain [`ItemEventState::ForSale`] &
     /// [`Role::Producer`].
     ///
-    /// On success, replies with [`Event`] where [`ItemState`] contains
+    /// On success, replies with [`Event::Item`] where [`ItemState`] contains
     /// [`ItemEventState::Purchased`] & [`Role::Distributor`].
     Purchase {
         item_id: ItemId,
         /// Milliseconds during which the producer of an item should deliver it.
         /// A countdown starts after [`ProducerAction::Ship`] is executed.
         delivery_time: u64,
+        /// A penalty schedule for a late delivery. If it's [`None`], then the
+        /// contract one is used (see [`Initialize`]).
+        penalty_schedule: Option<PenaltySchedule>,
+        /// Milliseconds during which the producer of an item should approve
+        /// this purchase. If it's [`None`], then the purchase has no deadline.
+        /// Otherwise, it can expire after the deadline by
+        /// [`DeadlineAction::ExpirePurchase`], and the message must have
+        /// [`DEADLINE_ACTION_GAS`] in addition to gas for its own processing.
+        approval_time: Option<u64>,
     },
 
+    /// Cancels a distributor's purchase that the item's producer hasn't
+    /// approved yet.
+    ///
+    /// Refunds fungible tokens for purchasing the item from the Supply chain
+    /// contract ([`exec::program_id()`](gstd::exec::program_id)) to the
+    /// distributor ([`msg::source()`]).
+    ///
+    /// # Requirements
+    /// - The item must exist in a supply chain.
+    /// - [`msg::source()`] must be the distributor of the item.
+    /// - Item's [`ItemState`] must contain [`ItemEventState::Purchased`] &
+    /// [`Role::Distributor`].
+    ///
+    /// On success, replies with [`Event::Item`] where [`ItemState`] contains
+    /// [`ItemEventState::ForSale`] & [`Role::Producer`].
+    ///
+    /// [`msg::source()`]: gstd::msg::source
+    CancelPurchase(ItemId),
+
     /// Receives a shipped item from a producer on behalf of a distributor.
     ///
     /// Depending on the time spent on a delivery, transfers fungible tokens for
     /// purchasing the item from the Supply chain contract
     /// ([`exec::program_id()`](gstd::exec::program_id)) to the item's producer
-    /// or, as a penalty for being late, refunds a half or all of them to the
-    /// item's distributor ([`msg::source()`]).
+    /// or, as a penalty for being late, refunds a part or all of them to the
+    /// item's distributor ([`msg::source()`]) according to the item's
+    /// [`PenaltySchedule`].
     ///
     /// Transfers an item's NFT to the distributor ([`msg::source()`]).
     ///
@@ -297,12 +868,34 @@ pub enum DistributorAction {
     /// - Item's [`ItemState`] must contain [`ItemEventState::Shipped`] &
     /// [`Role::Producer`].
     ///
-    /// On success, replies with [`Event`] where [`ItemState`] contains
+    /// On success, replies with [`Event::Item`] where [`ItemState`] contains
     /// [`ItemEventState::Received`] & [`Role::Distributor`].
     ///
     /// [`msg::source()`]: gstd::msg::source
     Receive(ItemId),
 
+    /// Disputes a shipped item on behalf of a distributor instead of receiving it,
+    /// e.g. if it's damaged or doesn't match its description.
+    ///
+    /// Fungible tokens for purchasing the item stay in the Supply chain
+    /// contract ([`exec::program_id()`](gstd::exec::program_id)) until the
+    /// arbiter resolves the dispute by [`ArbiterAction::Resolve`].
+    ///
+    /// # Requirements
+    /// - The item must exist in a supply chain.
+    /// - [`msg::source()`](gstd::msg::source) must be the distributor of the
+    /// item.
+    /// - Item's [`ItemState`] must contain [`ItemEventState::Shipped`] &
+    /// [`Role::Producer`].
+    ///
+    /// On success, replies with [`Event::Item`] where [`ItemState`] contains
+    /// [`ItemEventState::Disputed`] & [`Role::Distributor`].
+    Dispute {
+        item_id: ItemId,
+        /// A reason of the dispute for the arbiter.
+        reason: String,
+    },
+
     /// Processes a received item on behalf of a distributor.
     ///
     /// # Requirements
@@ -312,7 +905,7 @@ pub enum DistributorAction {
     /// - Item's [`ItemState`] must contain [`ItemEventState::Received`] &
     /// [`Role::Distributor`].
     ///
-    /// On success, replies with [`Event`] where [`ItemState`] contains
+    /// On success, replies with [`Event::Item`] where [`ItemState`] contains
     /// [`ItemEventState::Processed`] & [`Role::Distributor`].
     Process(ItemId),
 
@@ -325,7 +918,7 @@ pub enum DistributorAction {
     /// - Item's [`ItemState`] must contain [`ItemEventState::Processed`] &
     /// [`Role::Distributor`].
     ///
-    /// On success, replies with [`Event`] where [`ItemState`] contains
+    /// On success, replies with [`Event::Item`] where [`ItemState`] contains
     /// [`ItemEventState::Packaged`] & [`Role::Distributor`].
     Package(ItemId),
 
@@ -342,9 +935,47 @@ pub enum DistributorAction {
     /// - Item's [`ItemState`] must contain [`ItemEventState::Packaged`] &
     /// [`Role::Distributor`].
     ///
-    /// On success, replies with [`Event`] where [`ItemState`] contains
+    /// On success, replies with [`Event::Item`] where [`ItemState`] contains
+    /// [`ItemEventState::ForSale`] & [`Role::Distributor`].
+    PutUpForSale {
+        item_id: ItemId,
+        price: u128,
+        /// See [`ProducerAction::PutUpForSale`].
+        currency: Option<ActorId>,
+    },
+
+    /// Withdraws an unsold item from sale on behalf of a distributor.
+    ///
+    /// Transfers an item's NFT from the Supply chain contract
+    /// ([`exec::program_id()`](gstd::exec::program_id)) back to the distributor
+    /// ([`msg::source()`]).
+    ///
+    /// # Requirements
+    /// - The item must exist in a supply chain.
+    /// - [`msg::source()`] must be the distributor of the item.
+    /// - Item's [`ItemState`] must contain [`ItemEventState::ForSale`] &
+    /// [`Role::Distributor`].
+    ///
+    /// On success, replies with [`Event::Item`] where [`ItemState`] contains
+    /// [`ItemEventState::Packaged`] & [`Role::Distributor`].
+    ///
+    /// [`msg::source()`]: gstd::msg::source
+    WithdrawFromSale(ItemId),
+
+    /// Changes a price of an item that's up for sale on behalf of a distributor.
+    ///
+    /// Unlike [`DistributorAction::WithdrawFromSale`] followed by
+    /// [`DistributorAction::PutUpForSale`], doesn't transfer an item's NFT.
+    ///
+    /// # Requirements
+    /// - The item must exist in a supply chain.
+    /// - [`msg::source()`](gstd::msg::source) must be the distributor of the item.
+    /// - Item's [`ItemState`] must contain [`ItemEventState::ForSale`] &
+    /// [`Role::Distributor`].
+    ///
+    /// On success, replies with [`Event::Item`] where [`ItemState`] contains
     /// [`ItemEventState::ForSale`] & [`Role::Distributor`].
-    PutUpForSale { item_id: ItemId, price: u128 },
+    UpdatePrice { item_id: ItemId, price: u128 },
 
     /// Approves or not a retailer's purchase on behalf of a distributor.
     ///
@@ -364,8 +995,11 @@ pub enum DistributorAction {
     /// item.
     /// - Item's [`ItemState`] must contain [`ItemEventState::Purchased`] &
     /// [`Role::Retailer`].
+    /// - If the purchase is approved, its
+    /// [`approval_deadline`](ItemInfo::approval_deadline), if any, mustn't
+    /// pass.
     ///
-    /// On success, replies with [`Event`] where [`ItemState`] contains
+    /// On success, replies with [`Event::Item`] where [`ItemState`] contains
     /// [`ItemEventState::Approved`]/[`ItemEventState::ForSale`] &
     /// [`Role::Distributor`].
     Approve {
@@ -378,7 +1012,11 @@ pub enum DistributorAction {
     /// distributor.
     ///
     /// Starts the countdown for the delivery time specified for the item in
-    /// [`RetailerAction::Purchase`].
+    /// [`RetailerAction::Purchase`] and schedules
+    /// [`DeadlineAction::FailDelivery`] for the item.
+    ///
+    /// The message must have [`DEADLINE_ACTION_GAS`] in addition to gas for its
+    /// own processing.
     ///
     /// # Requirements
     /// - The item must exist in a supply chain.
@@ -387,7 +1025,7 @@ pub enum DistributorAction {
     /// - Item's [`ItemState`] must contain [`ItemEventState::Approved`] &
     /// [`Role::Distributor`].
     ///
-    /// On success, replies with [`Event`] where [`ItemState`] contains
+    /// On success, replies with [`Event::Item`] where [`ItemState`] contains
     /// [`ItemEventState::Shipped`] & [`Role::Distributor`].
     Ship(ItemId),
 }
@@ -395,7 +1033,7 @@ pub enum DistributorAction {
 /// Actions for a retailer.
 ///
 /// Should be used inside [`InnerAction::Retailer`].
-#[derive(Encode, Decode, TypeInfo, Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash)]
+#[derive(Encode, Decode, TypeInfo, Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Hash)]
 pub enum RetailerAction {
     /// Purchases an item from a distributor on behalf of a retailer.
     ///
@@ -404,15 +1042,19 @@ pub enum RetailerAction {
     /// is received (by [`RetailerAction::Receive`]).
     ///
     /// **Note:** the item's distributor must approve or not this purchase by
-    /// [`DistributorAction::Approve`].
+    /// [`DistributorAction::Approve`]. Until then, the retailer can cancel it
+    /// by [`RetailerAction::CancelPurchase`].
+    /// If `approval_time` is set and the distributor hasn't approved the purchase
+    /// in time, the purchase expires (see [`DeadlineAction::ExpirePurchase`]).
     ///
     /// # Requirements
     /// - The item must exist in a supply chain.
     /// - [`msg::source()`](gstd::msg::source) must be a retailer.
+    /// - `penalty_schedule`, if any, must be valid (see [`PenaltySchedule`]).
     /// - Item's [`ItemState`] must contain [`ItemEventState::ForSale`] &
     /// [`Role::Distributor`].
     ///
-    /// On success, replies with [`Event`] where [`ItemState`] contains
+    /// On success, replies with [`Event::Item`] where [`ItemState`] contains
     /// [`ItemEventState::Purchased`] & [`Role::Retailer`].
     Purchase {
         item_id: ItemId,
@@ -420,15 +1062,45 @@ pub enum RetailerAction {
         /// it. A countdown starts after [`DistributorAction::Ship`] is
         /// executed.
         delivery_time: u64,
+        /// A penalty schedule for a late delivery. If it's [`None`], then the
+        /// contract one is used (see [`Initialize`]).
+        penalty_schedule: Option<PenaltySchedule>,
+        /// Milliseconds during which the distributor of an item should approve
+        /// this purchase. If it's [`None`], then the purchase has no deadline.
+        /// Otherwise, it can expire after the deadline by
+        /// [`DeadlineAction::ExpirePurchase`], and the message must have
+        /// [`DEADLINE_ACTION_GAS`] in addition to gas for its own processing.
+        approval_time: Option<u64>,
     },
 
+    /// Cancels a retailer's purchase that the item's distributor hasn't
+    /// approved yet.
+    ///
+    /// Refunds fungible tokens for purchasing the item from the Supply chain
+    /// contract ([`exec::program_id()`](gstd::exec::program_id)) to the
+    /// retailer ([`msg::source()`]).
+    ///
+    /// # Requirements
+    /// - The item must exist in a supply chain.
+    /// - [`msg::source()`] must be the retailer of the item.
+    /// - Item's [`ItemState`] must contain [`ItemEventState::Purchased`] &
+    /// [`Role::Retailer`].
+    ///
+    /// On success, replies with [`Event::Item`] where [`ItemState`] contains
+    /// [`ItemEventState::ForSale`] & [`Role::Distributor`].
+    ///
+    /// [`msg::source()`]: gstd::msg::source
+    CancelPurchase(ItemId),
+
     /// Receives a shipped item from a distributor on behalf of a retailer.
     ///
     /// Depending on the time spent on a delivery, transfers fungible tokens for
     /// purchasing the item from the Supply chain contract
     /// ([`exec::program_id()`](gstd::exec::program_id)) to the item's
-    /// distributor or, as a penalty for being late, refunds a half or all of
-    /// them to the item's retailer ([`msg::source()`]).
+    /// distributor or, as a penalty for being late, refunds a part or all of
+    /// them to the item's retailer ([`msg::source()`]) according to the item's
+    /// [`PenaltySchedule`]. The item's producer receives a royalty from the
+    /// distributor's part (see [`ItemInfo::royalty`]).
     ///
     /// Transfers an item's NFT to the retailer ([`msg::source()`]).
     ///
@@ -438,12 +1110,34 @@ pub enum RetailerAction {
     /// - Item's [`ItemState`] must contain [`ItemEventState::Shipped`] &
     /// [`Role::Distributor`].
     ///
-    /// On success, replies with [`Event`] where [`ItemState`] contains
+    /// On success, replies with [`Event::Item`] where [`ItemState`] contains
     /// [`ItemEventState::Received`] & [`Role::Retailer`].
     ///
     /// [`msg::source()`]: gstd::msg::source
     Receive(ItemId),
 
+    /// Disputes a shipped item on behalf of a retailer instead of receiving it,
+    /// e.g. if it's damaged or doesn't match its description.
+    ///
+    /// Fungible tokens for purchasing the item stay in the Supply chain
+    /// contract ([`exec::program_id()`](gstd::exec::program_id)) until the
+    /// arbiter resolves the dispute by [`ArbiterAction::Resolve`].
+    ///
+    /// # Requirements
+    /// - The item must exist in a supply chain.
+    /// - [`msg::source()`](gstd::msg::source) must be the retailer of the
+    /// item.
+    /// - Item's [`ItemState`] must contain [`ItemEventState::Shipped`] &
+    /// [`Role::Distributor`].
+    ///
+    /// On success, replies with [`Event::Item`] where [`ItemState`] contains
+    /// [`ItemEventState::Disputed`] & [`Role::Retailer`].
+    Dispute {
+        item_id: ItemId,
+        /// A reason of the dispute for the arbiter.
+        reason: String,
+    },
+
     /// Puts a received item up for sale to consumers for given `price` on
     /// behalf of a retailer.
     ///
@@ -456,9 +1150,47 @@ pub enum RetailerAction {
     /// - Item's [`ItemState`] must contain [`ItemEventState::Received`] &
     /// [`Role::Retailer`].
     ///
-    /// On success, replies with [`Event`] where [`ItemState`] contains
+    /// On success, replies with [`Event::Item`] where [`ItemState`] contains
+    /// [`ItemEventState::ForSale`] & [`Role::Retailer`].
+    PutUpForSale {
+        item_id: ItemId,
+        price: u128,
+        /// See [`ProducerAction::PutUpForSale`].
+        currency: Option<ActorId>,
+    },
+
+    /// Withdraws an unsold item from sale on behalf of a retailer.
+    ///
+    /// Transfers an item's NFT from the Supply chain contract
+    /// ([`exec::program_id()`](gstd::exec::program_id)) back to the retailer
+    /// ([`msg::source()`]).
+    ///
+    /// # Requirements
+    /// - The item must exist in a supply chain.
+    /// - [`msg::source()`] must be the retailer of the item.
+    /// - Item's [`ItemState`] must contain [`ItemEventState::ForSale`] &
+    /// [`Role::Retailer`].
+    ///
+    /// On success, replies with [`Event::Item`] where [`ItemState`] contains
+    /// [`ItemEventState::Received`] & [`Role::Retailer`].
+    ///
+    /// [`msg::source()`]: gstd::msg::source
+    WithdrawFromSale(ItemId),
+
+    /// Changes a price of an item that's up for sale on behalf of a retailer.
+    ///
+    /// Unlike [`RetailerAction::WithdrawFromSale`] followed by
+    /// [`RetailerAction::PutUpForSale`], doesn't transfer an item's NFT.
+    ///
+    /// # Requirements
+    /// - The item must exist in a supply chain.
+    /// - [`msg::source()`](gstd::msg::source) must be the retailer of the item.
+    /// - Item's [`ItemState`] must contain [`ItemEventState::ForSale`] &
+    /// [`Role::Retailer`].
+    ///
+    /// On success, replies with [`Event::Item`] where [`ItemState`] contains
     /// [`ItemEventState::ForSale`] & [`Role::Retailer`].
-    PutUpForSale { item_id: ItemId, price: u128 },
+    UpdatePrice { item_id: ItemId, price: u128 },
 }
 
 /// Actions for a consumer.
@@ -466,9 +1198,11 @@ pub enum RetailerAction {
 /// Should be used inside [`InnerAction::Consumer`].
 #[derive(Encode, Decode, TypeInfo, Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash)]
 pub enum ConsumerAction {
-    /// Purchases an item from a retailer.
+    /// Purchases an item from a retailer or resells it from another consumer.
     ///
-    /// Transfers fungible tokens for purchasing the item to its retailer.
+    /// Transfers fungible tokens for purchasing the item to its seller. The
+    /// item's producer receives a royalty from its price (see
+    /// [`ItemInfo::royalty`], or [`Initialize`] on a resale).
     ///
     /// Transfers an item's NFT to the consumer
     /// ([`msg::source()`](gstd::msg::source)).
@@ -476,22 +1210,814 @@ pub enum ConsumerAction {
     /// # Requirements
     /// - The item must exist in a supply chain.
     /// - Item's [`ItemState`] must contain [`ItemEventState::ForSale`] &
-    /// [`Role::Retailer`].
+    /// [`Role::Retailer`]/[`Role::Consumer`].
     ///
-    /// On success, replies with [`Event`] where [`ItemState`] contains
+    /// On success, replies with [`Event::Item`] where [`ItemState`] contains
     /// [`ItemEventState::Purchased`] & [`Role::Consumer`].
     Purchase(ItemId),
-}
-
-/// A result of successfully processed [`Action`].
-#[derive(Encode, Decode, TypeInfo, Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash)]
-pub struct Event {
-    pub item_id: ItemId,
-    pub item_state: ItemState,
-}
 
-/// A result of **un**successfully processed [`Action`].
-#[derive(Debug, Encode, Decode, PartialEq, Eq, PartialOrd, Ord, Clone, TypeInfo, Hash)]
+    /// Puts a purchased item up for sale to other consumers for given `price`
+    /// on behalf of a consumer.
+    ///
+    /// Transfers an item's NFT to the Supply chain contract
+    /// ([`exec::program_id()`](gstd::exec::program_id)).
+    ///
+    /// # Requirements
+    /// - The item must exist in a supply chain.
+    /// - [`msg::source()`](gstd::msg::source) must be the consumer of the
+    /// item.
+    /// - Item's [`ItemState`] must contain [`ItemEventState::Purchased`] &
+    /// [`Role::Consumer`].
+    ///
+    /// On success, replies with [`Event::Item`] where [`ItemState`] contains
+    /// [`ItemEventState::ForSale`] & [`Role::Consumer`].
+    PutUpForSale {
+        item_id: ItemId,
+        price: u128,
+        /// See [`ProducerAction::PutUpForSale`].
+        currency: Option<ActorId>,
+    },
+
+    /// Withdraws an unsold item from sale on behalf of a consumer.
+    ///
+    /// Transfers an item's NFT from the Supply chain contract
+    /// ([`exec::program_id()`](gstd::exec::program_id)) back to the consumer
+    /// ([`msg::source()`]).
+    ///
+    /// # Requirements
+    /// - The item must exist in a supply chain.
+    /// - [`msg::source()`] must be the consumer of the item.
+    /// - Item's [`ItemState`] must contain [`ItemEventState::ForSale`] &
+    /// [`Role::Consumer`].
+    ///
+    /// On success, replies with [`Event::Item`] where [`ItemState`] contains
+    /// [`ItemEventState::Purchased`] & [`Role::Consumer`].
+    ///
+    /// [`msg::source()`]: gstd::msg::source
+    WithdrawFromSale(ItemId),
+
+    /// Changes a price of an item that's up for sale on behalf of a consumer.
+    ///
+    /// # Requirements
+    /// - The item must exist in a supply chain.
+    /// - [`msg::source()`](gstd::msg::source) must be the consumer of the
+    /// item.
+    /// - Item's [`ItemState`] must contain [`ItemEventState::ForSale`] &
+    /// [`Role::Consumer`].
+    ///
+    /// On success, replies with [`Event::Item`] where [`ItemState`] contains
+    /// [`ItemEventState::ForSale`] & [`Role::Consumer`].
+    UpdatePrice { item_id: ItemId, price: u128 },
+}
+
+/// Actions for an admin.
+///
+/// Should be used inside [`InnerAction::Admin`].
+///
+/// The admin is an actor that initialized the contract
+/// ([`msg::source()`](gstd::msg::source) of [`Initialize`]) or an actor that
+/// received admin rights by [`AdminAction::ChangeAdmin`].
+#[derive(Encode, Decode, TypeInfo, Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash)]
+pub enum AdminAction {
+    /// Adds a new participant with given `role` to a supply chain.
+    ///
+    /// # Requirements
+    /// - [`msg::source()`](gstd::msg::source) must be the admin.
+    /// - `participant` mustn't equal [`ActorId::zero()`].
+    /// - `role` mustn't be [`Role::Consumer`].
+    /// - `participant` mustn't already have given `role`.
+    ///
+    /// On success, replies with [`Event::ParticipantAdded`].
+    AddParticipant { role: Role, participant: ActorId },
+
+    /// Removes a participant with given `role` from a supply chain.
+    ///
+    /// **Note:** the removed participant loses access to all actions of its
+    /// `role`, including those for items it's already involved in. If it has
+    /// no roles left, it's also unsubscribed from [`Event`]s (see
+    /// [`SubscriberAction::Subscribe`]).
+    ///
+    /// # Requirements
+    /// - [`msg::source()`](gstd::msg::source) must be the admin.
+    /// - `role` mustn't be [`Role::Consumer`].
+    /// - `participant` must have given `role`.
+    ///
+    /// On success, replies with [`Event::ParticipantRemoved`].
+    RemoveParticipant { role: Role, participant: ActorId },
+
+    /// Transfers admin rights to given [`ActorId`].
+    ///
+    /// # Requirements
+    /// - [`msg::source()`](gstd::msg::source) must be the admin.
+    /// - A new admin mustn't equal [`ActorId::zero()`].
+    ///
+    /// On success, replies with [`Event::AdminChanged`].
+    ChangeAdmin(ActorId),
+
+    /// Sets or removes ([`None`]) a platform fee.
+    ///
+    /// # Requirements
+    /// - [`msg::source()`](gstd::msg::source) must be the admin.
+    /// - The fee, if any, must be valid (see [`PlatformFee`]).
+    ///
+    /// On success, replies with [`Event::PlatformFeeChanged`].
+    SetPlatformFee(Option<PlatformFee>),
+
+    /// Sets or removes ([`None`]) a time after which cached actions expire.
+    /// See [`Initialize::transaction_ttl`].
+    ///
+    /// Applies to already cached actions too.
+    ///
+    /// # Requirements
+    /// - [`msg::source()`](gstd::msg::source) must be the admin.
+    ///
+    /// On success, replies with [`Event::TransactionTtlChanged`].
+    SetTransactionTtl(Option<u64>),
+
+    /// Removes a cached action of given `actor` from the cache like
+    /// [`TransactionKind::Purge`] does, and releases its item lock, if any.
+    ///
+    /// Unlike [`TransactionKind::Purge`], also removes an action that is being
+    /// aborted, so an abort that can't be completed (see
+    /// [`TransactionKind::Abort`]) doesn't block the action & its item forever.
+    ///
+    /// # Requirements
+    /// - [`msg::source()`](gstd::msg::source) must be the admin.
+    /// - `actor` must have given `action` cached.
+    ///
+    /// On success, replies with [`Event::TransactionPurged`].
+    PurgeTransaction {
+        actor: ActorId,
+        action: CachedAction,
+    },
+}
+
+/// Actions for an arbiter.
+///
+/// Should be used inside [`InnerAction::Arbiter`].
+///
+/// The arbiter is an actor specified in [`Initialize`].
+#[derive(Encode, Decode, TypeInfo, Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash)]
+pub enum ArbiterAction {
+    /// Resolves a dispute over an item opened by
+    /// [`DistributorAction::Dispute`] or [`RetailerAction::Dispute`].
+    ///
+    /// Transfers `seller_share` of fungible tokens for purchasing the item
+    /// from the Supply chain contract
+    /// ([`exec::program_id()`](gstd::exec::program_id)) to the item's seller
+    /// and refunds the rest of them to the item's buyer. The seller's share is
+    /// split into the platform fee & the producer royalty in the same way as in
+    /// [`DistributorAction::Receive`] & [`RetailerAction::Receive`].
+    ///
+    /// If the buyer is refunded in full (`seller_share` is 0), then transfers
+    /// an item's NFT back to the seller, and the item returns to the state it
+    /// had before it was put up for sale. Otherwise, transfers an item's NFT to
+    /// the buyer as if the item was received.
+    ///
+    /// # Requirements
+    /// - [`msg::source()`](gstd::msg::source) must be the arbiter.
+    /// - `seller_share` mustn't be greater than [`MAX_BASIS_POINTS`].
+    /// - The item must exist in a supply chain.
+    /// - Item's [`ItemState`] must contain [`ItemEventState::Disputed`].
+    ///
+    /// On success, replies with [`Event::Item`] where [`ItemState`] contains
+    /// [`ItemEventState::Produced`]/[`ItemEventState::Packaged`] & [`Role`] of
+    /// the item's seller or [`ItemEventState::Received`] & [`Role`] of the
+    /// item's buyer.
+    Resolve {
+        item_id: ItemId,
+        /// A share of an item's price that the seller receives, in basis
+        /// points.
+        seller_share: u16,
+    },
+}
+
+/// Actions that enforce deadlines of supply chain participants.
+///
+/// Should be used inside [`InnerAction::Deadline`].
+///
+/// The contract sends these actions to itself as delayed messages, but anyone
+/// can send them as well, e.g. if a delayed message wasn't processed because of
+/// the lack of gas or arrived later than a deadline due to a longer block time.
+#[derive(Encode, Decode, TypeInfo, Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash)]
+pub enum DeadlineAction {
+    /// Fails a delivery of a shipped item that hasn't been received in twice
+    /// the delivery time.
+    ///
+    /// Refunds all fungible tokens for purchasing the item from the Supply
+    /// chain contract ([`exec::program_id()`](gstd::exec::program_id)) to the
+    /// item's buyer and transfers an item's NFT back to the item's seller.
+    /// The item returns to the seller's unsold state (e.g.
+    /// [`ItemEventState::Produced`] for a producer), so it can be put up for
+    /// sale again. [`ItemEventState::DeliveryFailed`] is recorded only in the
+    /// event & the item's history.
+    ///
+    /// # Requirements
+    /// - The item must exist in a supply chain.
+    /// - Item's [`ItemState`] must contain [`ItemEventState::Shipped`].
+    /// - Twice the delivery time must elapse since the item was shipped.
+    ///
+    /// On success, replies with [`Event::Item`] where [`ItemState`] contains
+    /// [`ItemEventState::DeliveryFailed`] & [`Role`] of the item's seller.
+    FailDelivery(ItemId),
+
+    /// Settles an auction for an item after its end time.
+    ///
+    /// If there's the highest bid, the item is purchased by its bidder for the
+    /// bid's price, and its fungible tokens stay in the Supply chain contract
+    /// ([`exec::program_id()`](gstd::exec::program_id)) like after a
+    /// [`DistributorAction::Purchase`] or [`RetailerAction::Purchase`], so then
+    /// the seller should approve the purchase. Otherwise, an item's NFT is
+    /// transferred back to the seller.
+    ///
+    /// # Requirements
+    /// - The item must exist in a supply chain.
+    /// - Item's [`ItemState`] must contain [`ItemEventState::Auctioned`].
+    /// - [`Auction::end_time`] must pass.
+    ///
+    /// On success, replies with [`Event::Item`] where [`ItemState`] contains
+    /// [`ItemEventState::Purchased`] & [`Role`] of the buyer, or
+    /// [`ItemState`] the item had before [`AuctionAction::Start`].
+    SettleAuction(ItemId),
+
+    /// Expires an item's purchase that its seller hasn't approved before
+    /// [`ItemInfo::approval_deadline`].
+    ///
+    /// Refunds fungible tokens for purchasing the item from the Supply chain
+    /// contract ([`exec::program_id()`](gstd::exec::program_id)) to the item's
+    /// buyer and puts the item back up for sale.
+    ///
+    /// # Requirements
+    /// - The item must exist in a supply chain.
+    /// - Item's [`ItemState`] must contain [`ItemEventState::Purchased`].
+    /// - [`ItemInfo::approval_deadline`] must be set & pass.
+    ///
+    /// On success, replies with [`Event::Item`] where [`ItemState`] contains
+    /// [`ItemEventState::ForSale`] & [`Role`] of the item's seller.
+    ExpirePurchase(ItemId),
+
+    /// Fails a delivery of a shipped lot that hasn't been received in twice
+    /// the delivery time.
+    ///
+    /// Works like [`DeadlineAction::FailDelivery`] for all items of the lot at
+    /// once: refunds the lot's price to its buyer, transfers NFTs of its items
+    /// back to its seller, and returns the lot to the seller's unsold state.
+    ///
+    /// # Requirements
+    /// - The lot must exist in a supply chain.
+    /// - Lot's [`ItemState`] must contain [`ItemEventState::Shipped`].
+    /// - Twice the delivery time must elapse since the lot was shipped.
+    ///
+    /// On success, replies with [`Event::Lot`] where [`ItemState`] contains
+    /// [`ItemEventState::DeliveryFailed`] & [`Role`] of the lot's seller.
+    FailLotDelivery(LotId),
+}
+
+/// Actions with lots.
+///
+/// Should be used inside [`InnerAction::Lot`].
+///
+/// A lot groups several items that are traded together in one transition
+/// instead of one by one, e.g. a pallet of items. Items of a lot always have
+/// the same [`ItemState`] as their lot, and, until the lot is split, they can't
+/// be used in other actions. Prices of a lot's items are summed up for
+/// purchasing of the lot.
+///
+/// **Note:** [`DistributorAction::Dispute`] & [`RetailerAction::Dispute`]
+/// aren't available for lots.
+#[derive(Encode, Decode, TypeInfo, Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Hash)]
+pub enum LotAction {
+    /// Creates a lot from given items.
+    ///
+    /// # Requirements
+    /// - Given items must exist in a supply chain and mustn't be in other lots.
+    /// - The number of given items must be from 1 to [`MAX_LOT_SIZE`], and they
+    /// mustn't repeat.
+    /// - [`ItemState`] of each item must be the same and must contain
+    /// [`ItemEventState::Produced`] & [`Role::Producer`] or
+    /// [`ItemEventState::Packaged`] & [`Role::Distributor`].
+    /// - [`msg::source()`](gstd::msg::source) must be the producer or the
+    /// distributor of each item respectively.
+    ///
+    /// On success, replies with [`Event::Lot`] where [`ItemState`] is the one
+    /// of given items.
+    Create(Vec<ItemId>),
+
+    /// Splits a lot back into its items.
+    ///
+    /// # Requirements
+    /// - The lot must exist in a supply chain.
+    /// - The lot mustn't be traded, i.e. its [`ItemState`] mustn't contain
+    /// [`ItemEventState::ForSale`], [`ItemEventState::Purchased`],
+    /// [`ItemEventState::Approved`], or [`ItemEventState::Shipped`].
+    /// - [`msg::source()`](gstd::msg::source) must be the participant of the
+    /// lot's items with [`Role`] from the lot's [`ItemState`].
+    ///
+    /// On success, replies with [`Event::LotSplit`].
+    Split(LotId),
+
+    /// Puts a lot up for sale for given `price` of each of its items.
+    ///
+    /// Transfers NFTs of the lot's items to the Supply chain contract
+    /// ([`exec::program_id()`](gstd::exec::program_id)).
+    ///
+    /// # Requirements
+    /// - The lot must exist in a supply chain.
+    /// - Lot's [`ItemState`] must be the one required by
+    /// [`LotAction::Create`].
+    /// - [`msg::source()`](gstd::msg::source) must be the producer or the
+    /// distributor of the lot's items respectively.
+    ///
+    /// On success, replies with [`Event::Lot`] where [`ItemState`] contains
+    /// [`ItemEventState::ForSale`] & [`Role`] of the seller.
+    PutUpForSale {
+        lot_id: LotId,
+        price: u128,
+        /// A currency of all the lot's items. See
+        /// [`ProducerAction::PutUpForSale`].
+        currency: Option<ActorId>,
+    },
+
+    /// Withdraws an unsold lot from sale.
+    ///
+    /// Transfers NFTs of the lot's items from the Supply chain contract
+    /// ([`exec::program_id()`](gstd::exec::program_id)) back to the seller
+    /// ([`msg::source()`](gstd::msg::source)).
+    ///
+    /// # Requirements
+    /// - The lot must exist in a supply chain.
+    /// - Lot's [`ItemState`] must contain [`ItemEventState::ForSale`].
+    /// - [`msg::source()`](gstd::msg::source) must be the seller of the lot.
+    ///
+    /// On success, replies with [`Event::Lot`] where [`ItemState`] is the one
+    /// the lot had before [`LotAction::PutUpForSale`].
+    WithdrawFromSale(LotId),
+
+    /// Purchases a lot on behalf of a distributor from a producer or on behalf
+    /// of a retailer from a distributor.
+    ///
+    /// Transfers fungible tokens for purchasing all the lot's items to the
+    /// Supply chain contract ([`exec::program_id()`](gstd::exec::program_id))
+    /// until the lot is received (by [`LotAction::Receive`]).
+    ///
+    /// # Requirements
+    /// - The lot must exist in a supply chain.
+    /// - Lot's [`ItemState`] must contain [`ItemEventState::ForSale`].
+    /// - [`msg::source()`](gstd::msg::source) must be a distributor if the
+    /// seller is a producer, or a retailer if the seller is a distributor.
+    /// - `penalty_schedule`, if any, must be valid (see [`PenaltySchedule`]).
+    ///
+    /// On success, replies with [`Event::Lot`] where [`ItemState`] contains
+    /// [`ItemEventState::Purchased`] & [`Role`] of the buyer.
+    Purchase {
+        lot_id: LotId,
+        /// Milliseconds during which the seller should deliver the lot.
+        delivery_time: u64,
+        /// A penalty schedule for a late delivery. If it's [`None`], then the
+        /// contract one is used (see [`Initialize`]).
+        penalty_schedule: Option<PenaltySchedule>,
+    },
+
+    /// Approves or not a purchase of a lot.
+    ///
+    /// If the purchase is **not** approved, then fungible tokens for it are
+    /// refunded from the Supply chain contract
+    /// ([`exec::program_id()`](gstd::exec::program_id)) to the buyer.
+    ///
+    /// # Requirements
+    /// - The lot must exist in a supply chain.
+    /// - Lot's [`ItemState`] must contain [`ItemEventState::Purchased`].
+    /// - [`msg::source()`](gstd::msg::source) must be the seller of the lot.
+    ///
+    /// On success, replies with [`Event::Lot`] where [`ItemState`] contains
+    /// [`ItemEventState::Approved`]/[`ItemEventState::ForSale`] & [`Role`] of
+    /// the seller.
+    Approve {
+        lot_id: LotId,
+        /// Yes ([`true`]) or no ([`false`]).
+        approve: bool,
+    },
+
+    /// Starts a shipping of a purchased lot.
+    ///
+    /// Starts the countdown for the delivery time specified for the lot in
+    /// [`LotAction::Purchase`] and schedules [`DeadlineAction::FailLotDelivery`]
+    /// for the lot. The message must have [`DEADLINE_ACTION_GAS`] in addition
+    /// to gas for its own processing.
+    ///
+    /// # Requirements
+    /// - The lot must exist in a supply chain.
+    /// - Lot's [`ItemState`] must contain [`ItemEventState::Approved`].
+    /// - [`msg::source()`](gstd::msg::source) must be the seller of the lot.
+    ///
+    /// On success, replies with [`Event::Lot`] where [`ItemState`] contains
+    /// [`ItemEventState::Shipped`] & [`Role`] of the seller.
+    Ship(LotId),
+
+    /// Receives a shipped lot.
+    ///
+    /// Like [`DistributorAction::Receive`] & [`RetailerAction::Receive`],
+    /// transfers fungible tokens for purchasing the lot from the Supply chain
+    /// contract ([`exec::program_id()`](gstd::exec::program_id)) to the seller
+    /// and the buyer according to the lot's [`PenaltySchedule`].
+    ///
+    /// Transfers NFTs of the lot's items to the buyer
+    /// ([`msg::source()`](gstd::msg::source)).
+    ///
+    /// # Requirements
+    /// - The lot must exist in a supply chain.
+    /// - Lot's [`ItemState`] must contain [`ItemEventState::Shipped`].
+    /// - [`msg::source()`](gstd::msg::source) must be the buyer of the lot.
+    ///
+    /// On success, replies with [`Event::Lot`] where [`ItemState`] contains
+    /// [`ItemEventState::Received`] & [`Role`] of the buyer.
+    Receive(LotId),
+}
+
+/// Actions with offers.
+///
+/// Should be used inside [`InnerAction::Offer`].
+///
+/// Besides purchasing an item for its fixed price, a distributor or retailer
+/// can offer its own price for an item that's up for sale by a producer or
+/// distributor respectively. Fungible tokens for an offer are kept in the
+/// Supply chain contract ([`exec::program_id()`](gstd::exec::program_id))
+/// until it's withdrawn or accepted. The seller can accept one of offers or
+/// propose a counter price to a buyer. Offers for an item are independent of
+/// its state, so they aren't refunded automatically if the item is purchased
+/// for its fixed price or withdrawn from sale.
+///
+/// Also see [`MAX_NUMBER_OF_OFFERS`].
+#[derive(Encode, Decode, TypeInfo, Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash)]
+pub enum OfferAction {
+    /// Makes an offer for an item.
+    ///
+    /// Transfers fungible tokens for the offer in the item's currency (see
+    /// [`ItemInfo::currency`]) to the Supply chain contract
+    /// ([`exec::program_id()`](gstd::exec::program_id)).
+    ///
+    /// # Requirements
+    /// - The item must exist in a supply chain, mustn't be in a lot, and
+    /// mustn't be recalled.
+    /// - Item's [`ItemState`] must contain [`ItemEventState::ForSale`] &
+    /// [`Role::Producer`] or [`Role::Distributor`].
+    /// - [`msg::source()`](gstd::msg::source) must be a distributor if the
+    /// seller is a producer, or a retailer if the seller is a distributor, and
+    /// mustn't have another offer for the item.
+    /// - The number of offers for the item mustn't exceed
+    /// [`MAX_NUMBER_OF_OFFERS`].
+    ///
+    /// On success, replies with [`Event::OfferMade`].
+    Make {
+        item_id: ItemId,
+        price: u128,
+        /// Milliseconds during which the seller should deliver the item if the
+        /// offer is accepted.
+        delivery_time: u64,
+    },
+
+    /// Withdraws an offer for an item.
+    ///
+    /// Refunds fungible tokens for the offer from the Supply chain contract
+    /// ([`exec::program_id()`](gstd::exec::program_id)) to the buyer
+    /// ([`msg::source()`](gstd::msg::source)).
+    ///
+    /// # Requirements
+    /// - [`msg::source()`](gstd::msg::source) must have an offer for the item.
+    ///
+    /// On success, replies with [`Event::OfferWithdrawn`].
+    Withdraw(ItemId),
+
+    /// Accepts an offer of `buyer` for an item on behalf of its seller.
+    ///
+    /// Refunds fungible tokens of all other offers for the item to their
+    /// buyers. The item is sold for the offer's price, and its purchase is
+    /// approved at once, so it can be shipped right away.
+    ///
+    /// # Requirements
+    /// - The item must meet the requirements of [`OfferAction::Make`].
+    /// - [`msg::source()`](gstd::msg::source) must be the seller of the item.
+    /// - `buyer` must have an offer for the item.
+    ///
+    /// On success, replies with [`Event::Item`] where [`ItemState`] contains
+    /// [`ItemEventState::Approved`] & [`Role`] of the seller.
+    Accept { item_id: ItemId, buyer: ActorId },
+
+    /// Proposes a counter `price` to an offer of `buyer` for an item on behalf
+    /// of its seller.
+    ///
+    /// A counter price replaces the previous one, if any.
+    ///
+    /// # Requirements
+    /// - The item must meet the requirements of [`OfferAction::Make`].
+    /// - [`msg::source()`](gstd::msg::source) must be the seller of the item.
+    /// - `buyer` must have an offer for the item.
+    ///
+    /// On success, replies with [`Event::OfferCountered`].
+    Counter {
+        item_id: ItemId,
+        buyer: ActorId,
+        price: u128,
+    },
+
+    /// Accepts a counter price to an offer for an item on behalf of the buyer.
+    ///
+    /// Transfers the difference between the counter price and the offer's
+    /// price from the buyer ([`msg::source()`](gstd::msg::source)) to the
+    /// Supply chain contract ([`exec::program_id()`](gstd::exec::program_id))
+    /// or back. Then, acts like [`OfferAction::Accept`].
+    ///
+    /// # Requirements
+    /// - The item must meet the requirements of [`OfferAction::Make`].
+    /// - [`msg::source()`](gstd::msg::source) must have an offer for the item
+    /// with a counter price.
+    ///
+    /// On success, replies with [`Event::Item`] where [`ItemState`] contains
+    /// [`ItemEventState::Approved`] & [`Role`] of the seller.
+    AcceptCounter(ItemId),
+}
+
+/// Actions with auctions.
+///
+/// Should be used inside [`InnerAction::Auction`].
+///
+/// An item can be sold by an English auction instead of a fixed price. Bidders
+/// are distributors if the seller is a producer, or retailers if the seller is
+/// a distributor. Fungible tokens of the highest bid are kept in the Supply
+/// chain contract ([`exec::program_id()`](gstd::exec::program_id)), and an
+/// auction is settled by [`DeadlineAction::SettleAuction`].
+#[derive(Encode, Decode, TypeInfo, Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash)]
+pub enum AuctionAction {
+    /// Starts an auction for an item.
+    ///
+    /// Transfers an item's NFT to the Supply chain contract
+    /// ([`exec::program_id()`](gstd::exec::program_id)) and sends
+    /// [`DeadlineAction::SettleAuction`] to it as a delayed message, so the
+    /// message must have [`DEADLINE_ACTION_GAS`] in addition to gas for its own
+    /// processing.
+    ///
+    /// # Requirements
+    /// - The item must exist in a supply chain, mustn't be in a lot, and
+    /// mustn't be recalled.
+    /// - Item's [`ItemState`] must contain [`ItemEventState::Produced`] &
+    /// [`Role::Producer`] or [`ItemEventState::Packaged`] &
+    /// [`Role::Distributor`].
+    /// - [`msg::source()`](gstd::msg::source) must be the producer or the
+    /// distributor of the item respectively.
+    /// - `currency` must be accepted (see [`ProducerAction::PutUpForSale`]).
+    ///
+    /// On success, replies with [`Event::Item`] where [`ItemState`] contains
+    /// [`ItemEventState::Auctioned`] & [`Role`] of the seller.
+    Start {
+        item_id: ItemId,
+        /// The minimum price of a bid. Also becomes the item's price until
+        /// the auction is settled.
+        reserve_price: u128,
+        /// Milliseconds during which bids are accepted.
+        duration: u64,
+        /// See [`ProducerAction::PutUpForSale`].
+        currency: Option<ActorId>,
+    },
+
+    /// Places a bid for an auctioned item.
+    ///
+    /// Transfers fungible tokens for the bid from a bidder
+    /// ([`msg::source()`](gstd::msg::source)) to the Supply chain contract
+    /// ([`exec::program_id()`](gstd::exec::program_id)), and refunds the
+    /// previous highest bid to its bidder.
+    ///
+    /// # Requirements
+    /// - The item must exist in a supply chain and mustn't be recalled.
+    /// - Item's [`ItemState`] must contain [`ItemEventState::Auctioned`].
+    /// - [`msg::source()`](gstd::msg::source) must be a distributor if the
+    /// seller is a producer, or a retailer if the seller is a distributor.
+    /// - [`Auction::end_time`] mustn't pass.
+    /// - `price` mustn't be less than [`Auction::reserve_price`] and must be
+    /// greater than the highest bid.
+    ///
+    /// On success, replies with [`Event::AuctionBid`].
+    Bid {
+        item_id: ItemId,
+        price: u128,
+        /// Milliseconds during which the seller should deliver the item if the
+        /// bid wins.
+        delivery_time: u64,
+    },
+}
+
+/// An auction for an item. See [`AuctionAction`].
+#[derive(
+    Encode, Decode, TypeInfo, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash,
+)]
+pub struct Auction {
+    pub reserve_price: u128,
+    /// A timestamp in milliseconds since which bids aren't accepted.
+    pub end_time: u64,
+    pub highest_bid: Option<Bid>,
+}
+
+/// A bid in an [`Auction`].
+#[derive(
+    Encode, Decode, TypeInfo, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash,
+)]
+pub struct Bid {
+    pub bidder: ActorId,
+    pub price: u128,
+    /// See [`AuctionAction::Bid`].
+    pub delivery_time: u64,
+}
+
+/// An offer for an item. See [`OfferAction`].
+#[derive(
+    Encode, Decode, TypeInfo, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash,
+)]
+pub struct Offer {
+    pub price: u128,
+    /// A FT contract that `price` is in.
+    pub currency: ActorId,
+    /// See [`OfferAction::Make`].
+    pub delivery_time: u64,
+    /// A counter price proposed by the seller, if any. See
+    /// [`OfferAction::Counter`].
+    pub counter_price: Option<u128>,
+}
+
+/// Actions for subscribers to [`Event`]s.
+///
+/// Should be used inside [`InnerAction::Subscriber`].
+///
+/// After each successfully processed [`Action`], the contract sends its
+/// [`Event`] by [`msg::send()`](gstd::msg::send) to each subscriber whose
+/// [`SubscriptionFilter`] matches it. Subscribers can be programs or users,
+/// notifications for the latter end up in their mailbox. Notifications are
+/// sent on a best-effort basis, so a failed one doesn't fail an action.
+#[derive(Encode, Decode, TypeInfo, Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash)]
+pub enum SubscriberAction {
+    /// Subscribes [`msg::source()`](gstd::msg::source) to [`Event`]s that
+    /// match given filter, or replaces its filter if it's already subscribed.
+    ///
+    /// The number of subscribers is limited, so only actors chosen by the admin
+    /// can subscribe. A participant removed by
+    /// [`AdminAction::RemoveParticipant`] is unsubscribed once it has no roles
+    /// left.
+    ///
+    /// # Requirements
+    /// - [`msg::source()`](gstd::msg::source) must be the admin, the arbiter, or
+    /// a producer, distributor, or retailer.
+    /// - The number of subscribers mustn't exceed
+    /// [`MAX_NUMBER_OF_SUBSCRIBERS`].
+    ///
+    /// On success, replies with [`Event::Subscribed`].
+    Subscribe(SubscriptionFilter),
+
+    /// Unsubscribes [`msg::source()`](gstd::msg::source) from [`Event`]s.
+    ///
+    /// # Requirements
+    /// - [`msg::source()`](gstd::msg::source) must be a subscriber.
+    ///
+    /// On success, replies with [`Event::Unsubscribed`].
+    Unsubscribe,
+}
+
+/// A filter of [`Event`]s for [`SubscriberAction::Subscribe`].
+///
+/// An event matches the filter if it matches each of its fields that isn't
+/// [`None`], so the default filter matches all events. Events that aren't
+/// related to any item or role match only the filter without such fields.
+#[derive(
+    Encode, Decode, TypeInfo, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash,
+)]
+pub struct SubscriptionFilter {
+    /// Matches [`Event::Item`] with given [`ItemId`].
+    pub item_id: Option<ItemId>,
+    /// Matches [`Event::Item`] where [`ItemState`] contains given [`Role`], and
+    /// [`Event::ParticipantAdded`] & [`Event::ParticipantRemoved`] with given
+    /// [`Role`].
+    pub role: Option<Role>,
+}
+
+impl SubscriptionFilter {
+    pub fn matches(&self, event: &Event) -> bool {
+        match event {
+            Event::Item {
+                item_id,
+                item_state,
+            } => {
+                self.item_id
+                    .map_or(true, |some_item_id| some_item_id == *item_id)
+                    && self.role.map_or(true, |role| role == item_state.by)
+            }
+            Event::Items {
+                item_ids,
+                item_state,
+            }
+            | Event::Lot {
+                item_ids,
+                item_state,
+                ..
+            } => {
+                self.item_id
+                    .map_or(true, |item_id| item_ids.contains(&item_id))
+                    && self.role.map_or(true, |role| role == item_state.by)
+            }
+            Event::LotSplit { item_ids, .. } | Event::Recalled { item_ids, .. } => {
+                self.item_id
+                    .map_or(true, |item_id| item_ids.contains(&item_id))
+                    && self.role.is_none()
+            }
+            Event::OfferMade { item_id, .. }
+            | Event::OfferWithdrawn { item_id, .. }
+            | Event::OfferCountered { item_id, .. }
+            | Event::AuctionBid { item_id, .. } => {
+                self.item_id
+                    .map_or(true, |some_item_id| some_item_id == *item_id)
+                    && self.role.is_none()
+            }
+            Event::ParticipantAdded { role, .. } | Event::ParticipantRemoved { role, .. } => {
+                self.item_id.is_none() && self.role.map_or(true, |some_role| some_role == *role)
+            }
+            _ => self.item_id.is_none() && self.role.is_none(),
+        }
+    }
+}
+
+/// A result of successfully processed [`Action`].
+#[derive(Encode, Decode, TypeInfo, Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Hash)]
+pub enum Event {
+    /// An item's state has been changed.
+    Item {
+        item_id: ItemId,
+        item_state: ItemState,
+    },
+    /// States of several items have been changed to the same one.
+    Items {
+        item_ids: Vec<ItemId>,
+        item_state: ItemState,
+    },
+    /// A lot has been created or its state has been changed.
+    Lot {
+        lot_id: LotId,
+        item_ids: Vec<ItemId>,
+        item_state: ItemState,
+    },
+    LotSplit {
+        lot_id: LotId,
+        item_ids: Vec<ItemId>,
+    },
+    ParticipantAdded {
+        role: Role,
+        participant: ActorId,
+    },
+    ParticipantRemoved {
+        role: Role,
+        participant: ActorId,
+    },
+    AdminChanged(ActorId),
+    PlatformFeeChanged(Option<PlatformFee>),
+    Subscribed {
+        subscriber: ActorId,
+        filter: SubscriptionFilter,
+    },
+    Unsubscribed(ActorId),
+    RecallPoolFunded {
+        producer: ActorId,
+        amount: u128,
+    },
+    /// Items have been recalled, some of them have been refunded to their
+    /// holders.
+    Recalled {
+        item_ids: Vec<ItemId>,
+        refunded_item_ids: Vec<ItemId>,
+    },
+    OfferMade {
+        item_id: ItemId,
+        buyer: ActorId,
+        price: u128,
+    },
+    OfferWithdrawn {
+        item_id: ItemId,
+        buyer: ActorId,
+    },
+    OfferCountered {
+        item_id: ItemId,
+        buyer: ActorId,
+        price: u128,
+    },
+    AuctionBid {
+        item_id: ItemId,
+        bidder: ActorId,
+        price: u128,
+    },
+    /// A cached action has been aborted. See [`TransactionKind::Abort`].
+    TransactionAborted(CachedAction),
+    TransactionTtlChanged(Option<u64>),
+    /// A cached action has been purged. See [`TransactionKind::Purge`] &
+    /// [`AdminAction::PurgeTransaction`].
+    TransactionPurged(CachedAction),
+}
+
+/// A result of **un**successfully processed [`Action`].
+#[derive(Debug, Encode, Decode, PartialEq, Eq, PartialOrd, Ord, Clone, TypeInfo, Hash)]
 pub enum Error {
     /// [`ActorId::zero()`] was found where it's forbidden.
     ZeroActorId,
@@ -514,6 +2040,57 @@ pub enum Error {
     NFTMintingFailed,
     /// The contract reached a limit of protection against the memory overflow.
     MemoryLimitExceeded,
+    /// An actor already has a role given in [`AdminAction::AddParticipant`].
+    ParticipantExists,
+    /// An actor doesn't have a role given in
+    /// [`AdminAction::RemoveParticipant`].
+    ParticipantNotFound,
+    /// A given [`Role`] can't be used for a requested action.
+    UnexpectedRole,
+    /// A deadline for an item with given [`ItemId`] hasn't passed yet.
+    DeadlineNotReached,
+    /// A given [`PenaltySchedule`] doesn't meet its requirements.
+    InvalidPenaltySchedule,
+    /// A given value in basis points is greater than [`MAX_BASIS_POINTS`].
+    InvalidBasisPoints,
+    /// A batch is larger than [`MAX_BATCH_SIZE`].
+    BatchSizeExceeded,
+    /// A lot with given [`LotId`] doesn't exist in a supply chain.
+    LotNotFound,
+    /// An item with given [`ItemId`] is in a lot, so it can be used only in
+    /// [`LotAction`]s.
+    ItemInLot,
+    /// The number of given items or their uniqueness doesn't meet
+    /// requirements of [`LotAction::Create`].
+    InvalidLot,
+    /// [`msg::source()`](gstd::msg::source) isn't subscribed to [`Event`]s.
+    SubscriberNotFound,
+    /// An item with given [`ItemId`] is recalled. See
+    /// [`ProducerAction::Recall`].
+    ItemRecalled,
+    /// An attached value doesn't equal a required amount. See
+    /// [`PaymentMode::Value`].
+    UnexpectedValue,
+    /// A currency isn't accepted by a supply chain. See
+    /// [`Initialize::currencies`].
+    UnacceptedCurrency,
+    /// [`msg::source()`](gstd::msg::source) already has an offer for an item
+    /// with given [`ItemId`].
+    OfferExists,
+    /// An offer (or its counter price) for an item with given [`ItemId`]
+    /// doesn't exist.
+    OfferNotFound,
+    /// An auction for an item with given [`ItemId`] has already ended.
+    AuctionEnded,
+    /// A bid doesn't meet the requirements of [`AuctionAction::Bid`].
+    BidTooLow,
+    /// The approval deadline of a purchase of an item with given [`ItemId`]
+    /// has passed.
+    ApprovalExpired,
+    /// An item with given [`ItemId`] is locked by another asynchronous action
+    /// that is being processed or has been partially processed. See
+    /// [`CachedAction::item_id()`].
+    ItemLocked,
     /// See [`GstdError`].
     ContractError(String),
     TxCacheError(TransactionCacheError),
@@ -527,13 +2104,25 @@ pub enum TransactionCacheError {
     /// There's no cached transaction for
     /// [`msg::source()`](gstd::msg::source()). The reason may be a
     /// transaction's action wasn't asynchronous or just wasn't cached, or a
-    /// cached transaction has been removed because it was completed or too old.
+    /// cached transaction has been removed because it was completed, purged,
+    /// expired, or too old.
     TransactionNotFound,
-    /// An action for retrying doesn't match its cached counterpart.
+    /// A payload of an action for retrying doesn't match the one of its cached
+    /// counterpart. See [`TransactionKind`].
     MismatchedAction,
     /// Too many transaction IDs were acquired in one action. The maximum amount
     /// is 256.
     StepOverflow,
+    /// A cached transaction is being aborted. See [`TransactionKind::Abort`].
+    AbortInProgress,
+    /// A cached transaction with the same [`CachedAction`] is still being
+    /// processed, so it can't be sent again or aborted, only retried. See
+    /// [`TransactionKind`].
+    TransactionInProgress,
+    /// A cached transaction with the same [`CachedAction`] has executed some
+    /// of its steps, so it must be retried, aborted, or purged before the
+    /// action can be sent again. See [`TransactionKind`].
+    UnfinishedTransaction,
 }
 
 impl From<TransactionCacheError> for Error {
@@ -562,18 +2151,18 @@ pub enum Role {
 
 /// Supply chain patricipants.
 ///
-/// For more info about fields, see [`Initialize`].
+/// For more info about fields, see [`Initialize`] & [`AdminAction`].
 #[derive(Encode, Decode, TypeInfo, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Clone, Hash)]
 pub struct Participants {
     pub producers: Vec<ActorId>,
     pub distributors: Vec<ActorId>,
     pub retailers: Vec<ActorId>,
+    pub admin: ActorId,
+    pub arbiter: ActorId,
 }
 
 /// Item info.
-#[derive(
-    Encode, Decode, TypeInfo, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash,
-)]
+#[derive(Encode, Decode, TypeInfo, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Clone, Hash)]
 pub struct ItemInfo {
     /// Item’s producer [`ActorId`].
     pub producer: ActorId,
@@ -585,13 +2174,49 @@ pub struct ItemInfo {
     /// `state`). If it equals [`ActorId::zero()`], then it means that an item
     /// has never had a retailer.
     pub retailer: ActorId,
+    /// [`ActorId`] of an item’s current or past consumer (depends on item’s
+    /// `state`). If it equals [`ActorId::zero()`], then it means that an item
+    /// has never had a consumer.
+    pub consumer: ActorId,
 
     pub state: ItemState,
     /// An item’s price. If it equals 0, then, depending on item’s `state`, an
     /// item is sold for free or has never been put up for sale.
     pub price: u128,
+    /// A FT contract that an item's `price` is in. See
+    /// [`ProducerAction::PutUpForSale`].
+    pub currency: ActorId,
     /// Milliseconds during which a current seller should deliver an item.
     pub delivery_time: u64,
+    /// A penalty schedule for a late delivery of an item by a current seller.
+    pub penalty_schedule: PenaltySchedule,
+    /// A timestamp until which a current seller should approve a purchase of
+    /// an item, if its buyer has set one. See [`DistributorAction::Purchase`]
+    /// & [`RetailerAction::Purchase`].
+    pub approval_deadline: Option<u64>,
+    /// A share of an item's price in basis points that its producer receives
+    /// from a seller on each sale to a retailer or consumer. It's taken from
+    /// the amount that the seller receives, so the buyer pays only the price.
+    ///
+    /// **Note:** resales between consumers use the royalty from
+    /// [`Initialize`] instead.
+    pub royalty: u16,
+    /// A reason of the last dispute over an item, if any. See
+    /// [`DistributorAction::Dispute`] & [`RetailerAction::Dispute`].
+    pub dispute_reason: Option<String>,
+    /// A lot that an item is in, if any. See [`LotAction`].
+    pub lot_id: Option<LotId>,
+    /// A reason of an item's recall, if it's recalled. See
+    /// [`ProducerAction::Recall`].
+    pub recall_reason: Option<String>,
+}
+
+/// Lot info.
+#[derive(Encode, Decode, TypeInfo, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Clone, Hash)]
+pub struct LotInfo {
+    pub item_ids: Vec<ItemId>,
+    /// The state of the lot and all its items.
+    pub state: ItemState,
 }
 
 /// An item’s state.
@@ -610,6 +2235,26 @@ impl Default for ItemState {
     }
 }
 
+/// A record of an item's history.
+///
+/// The contract appends a new record to an item's history on each change of
+/// item's [`ItemState`], keeping at most [`MAX_HISTORY_LENGTH`] latest records.
+#[derive(
+    Encode, Decode, TypeInfo, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash,
+)]
+pub struct HistoryRecord {
+    /// An item's state after an action.
+    pub state: ItemState,
+    /// [`ActorId`] of an actor that performed an action.
+    pub actor: ActorId,
+    /// A timestamp of a block in which an action was performed.
+    pub timestamp: u64,
+    /// A height of a block in which an action was performed.
+    pub block_height: u32,
+    /// An item's price at the moment of an action.
+    pub price: u128,
+}
+
 /// A part of [`ItemState`].
 #[derive(
     Encode, Decode, TypeInfo, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash,
@@ -624,4 +2269,63 @@ pub enum ItemEventState {
     ForSale,
     Approved,
     Shipped,
+    DeliveryFailed,
+    Disputed,
+    /// See [`AuctionAction`].
+    Auctioned,
+}
+
+/// A filter for
+/// [`items_page()`](../supply_chain_state/metafns/fn.items_page.html).
+///
+/// An item matches the filter if it matches each of its fields that isn't
+/// [`None`], so the default filter matches all items.
+#[derive(
+    Encode, Decode, TypeInfo, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash,
+)]
+pub struct ItemFilter {
+    pub state: Option<ItemEventState>,
+    pub by: Option<Role>,
+
+    pub producer: Option<ActorId>,
+    pub distributor: Option<ActorId>,
+    pub retailer: Option<ActorId>,
+
+    /// The minimum item price, inclusive.
+    pub min_price: Option<u128>,
+    /// The maximum item price, inclusive.
+    pub max_price: Option<u128>,
+}
+
+impl ItemFilter {
+    pub fn matches(&self, item_info: &ItemInfo) -> bool {
+        self.state
+            .map_or(true, |state| item_info.state.state == state)
+            && self.by.map_or(true, |by| item_info.state.by == by)
+            && self
+                .producer
+                .map_or(true, |producer| item_info.producer == producer)
+            && self
+                .distributor
+                .map_or(true, |distributor| item_info.distributor == distributor)
+            && self
+                .retailer
+                .map_or(true, |retailer| item_info.retailer == retailer)
+            && self
+                .min_price
+                .map_or(true, |min_price| item_info.price >= min_price)
+            && self
+                .max_price
+                .map_or(true, |max_price| item_info.price <= max_price)
+    }
+}
+
+/// A page of items returned by
+/// [`items_page()`](../supply_chain_state/metafns/fn.items_page.html).
+#[derive(Encode, Decode, TypeInfo, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Clone, Hash)]
+pub struct ItemsPage {
+    /// Items of the page sorted by [`ItemId`].
+    pub items: Vec<(ItemId, ItemInfo)>,
+    /// The total number of items that match a filter.
+    pub total: u32,
 }
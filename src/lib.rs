@@ -4,7 +4,7 @@ use gear_lib::non_fungible_token::token::TokenMetadata;
 use gstd::{errors::Result as GstdResult, exec, msg, prelude::*, ActorId, MessageId};
 use hashbrown::{HashMap, HashSet};
 use supply_chain_io::*;
-use tx_manager::{TransactionGuard, TransactionManager};
+use tx_manager::{Step, TransactionGuard, TransactionManager};
 
 mod tx_manager;
 mod utils;
@@ -12,6 +12,20 @@ mod utils;
 #[cfg(feature = "binary-vendor")]
 include!(concat!(env!("OUT_DIR"), "/wasm_binary.rs"));
 
+fn send_deadline_action(action: DeadlineAction, time: u64) -> Result<(), Error> {
+    let delay = (time / BLOCK_TIME).saturating_add(1);
+
+    msg::send_with_gas_delayed(
+        exec::program_id(),
+        Action::new(InnerAction::Deadline(action)),
+        DEADLINE_ACTION_GAS,
+        0,
+        delay.try_into().unwrap_or(u32::MAX),
+    )?;
+
+    Ok(())
+}
+
 fn get_mut_item(
     items: &mut HashMap<ItemId, Item>,
     item_id: ItemId,
@@ -19,6 +33,10 @@ fn get_mut_item(
 ) -> Result<&mut Item, Error> {
     let item = items.get_mut(&item_id).ok_or(Error::ItemNotFound)?;
 
+    if item.info.lot_id.is_some() {
+        return Err(Error::ItemInLot);
+    }
+
     if item.info.state != expected_item_state {
         return Err(Error::UnexpectedItemState);
     }
@@ -26,6 +44,35 @@ fn get_mut_item(
     Ok(item)
 }
 
+/// Returns an item that meets the requirements of [`OfferAction::Make`].
+fn get_mut_item_for_offer(
+    items: &mut HashMap<ItemId, Item>,
+    item_id: ItemId,
+) -> Result<&mut Item, Error> {
+    let by = items
+        .get(&item_id)
+        .ok_or(Error::ItemNotFound)?
+        .info
+        .state
+        .by;
+
+    if !matches!(by, Role::Producer | Role::Distributor) {
+        return Err(Error::UnexpectedItemState);
+    }
+
+    let item = get_mut_item(
+        items,
+        item_id,
+        ItemState {
+            state: ItemEventState::ForSale,
+            by,
+        },
+    )?;
+    item.is_not_recalled()?;
+
+    Ok(item)
+}
+
 fn role_to_set_item_dr(role: Role) -> fn(&mut Item, ActorId) {
     match role {
         Role::Distributor => Item::set_distributor,
@@ -34,6 +81,170 @@ fn role_to_set_item_dr(role: Role) -> fn(&mut Item, ActorId) {
     }
 }
 
+fn seller_to_buyer_role(role: Role) -> Role {
+    match role {
+        Role::Producer => Role::Distributor,
+        Role::Distributor => Role::Retailer,
+        _ => unreachable!(),
+    }
+}
+
+fn buyer_to_seller_role(role: Role) -> Role {
+    match role {
+        Role::Distributor => Role::Producer,
+        Role::Retailer => Role::Distributor,
+        _ => unreachable!(),
+    }
+}
+
+fn role_to_unsold_state(role: Role) -> ItemEventState {
+    match role {
+        Role::Producer => ItemEventState::Produced,
+        Role::Distributor => ItemEventState::Packaged,
+        Role::Retailer => ItemEventState::Received,
+        Role::Consumer => ItemEventState::Purchased,
+    }
+}
+
+fn is_lot_unsold(item_state: ItemState) -> bool {
+    matches!(item_state.by, Role::Producer | Role::Distributor)
+        && item_state.state == role_to_unsold_state(item_state.by)
+}
+
+fn get_lot(
+    lots: &HashMap<LotId, LotInfo>,
+    lot_id: LotId,
+    expected_item_event_state: Option<ItemEventState>,
+) -> Result<&LotInfo, Error> {
+    let lot = lots.get(&lot_id).ok_or(Error::LotNotFound)?;
+
+    if expected_item_event_state.map_or(false, |state| state != lot.state.state) {
+        return Err(Error::UnexpectedItemState);
+    }
+
+    Ok(lot)
+}
+
+fn lot_item(items: &HashMap<ItemId, Item>, item_id: ItemId) -> &Item {
+    items.get(&item_id).expect("items of a lot must exist")
+}
+
+fn lot_item_mut(items: &mut HashMap<ItemId, Item>, item_id: ItemId) -> &mut Item {
+    items.get_mut(&item_id).expect("items of a lot must exist")
+}
+
+fn lot_price(items: &HashMap<ItemId, Item>, item_ids: &[ItemId]) -> u128 {
+    item_ids
+        .iter()
+        .map(|item_id| lot_item(items, *item_id).info.price)
+        .sum()
+}
+
+fn lot_currency(items: &HashMap<ItemId, Item>, item_ids: &[ItemId]) -> ActorId {
+    lot_item(items, item_ids[0]).info.currency
+}
+
+fn is_lot_pdr(
+    items: &HashMap<ItemId, Item>,
+    item_ids: &[ItemId],
+    role: Role,
+    actor: ActorId,
+) -> Result<(), Error> {
+    let is_pdr = role_to_is_pdr(role);
+
+    item_ids
+        .iter()
+        .try_for_each(|item_id| is_pdr(lot_item(items, *item_id), actor))
+}
+
+fn is_lot_not_recalled(items: &HashMap<ItemId, Item>, item_ids: &[ItemId]) -> Result<(), Error> {
+    item_ids
+        .iter()
+        .try_for_each(|item_id| lot_item(items, *item_id).is_not_recalled())
+}
+
+fn is_lot_not_locked(
+    items: &HashMap<ItemId, Item>,
+    item_ids: &[ItemId],
+    tx_manager: &TransactionManager<CachedAction>,
+) -> Result<(), Error> {
+    item_ids
+        .iter()
+        .try_for_each(|item_id| lot_item(items, *item_id).is_not_locked(tx_manager))
+}
+
+fn is_penalty_schedule_valid(penalty_schedule: &PenaltySchedule) -> bool {
+    penalty_schedule
+        .0
+        .iter()
+        .all(|step| step.seller_share <= MAX_BASIS_POINTS)
+        && penalty_schedule
+            .0
+            .windows(2)
+            .all(|steps| steps[0].lateness < steps[1].lateness)
+        // A step at or beyond the delivery deadline (see `delivery_deadline()`)
+        // would never be reached.
+        && penalty_schedule
+            .0
+            .last()
+            .map_or(true, |step| step.lateness < MAX_BASIS_POINTS.into())
+}
+
+fn share_of(amount: u128, basis_points: u16) -> u128 {
+    let (basis_points, max_basis_points): (u128, u128) =
+        (basis_points.into(), MAX_BASIS_POINTS.into());
+
+    amount / max_basis_points * basis_points
+        + amount % max_basis_points * basis_points / max_basis_points
+}
+
+fn is_platform_fee_valid(platform_fee: Option<PlatformFee>) -> Result<(), Error> {
+    match platform_fee {
+        Some(PlatformFee { fee, .. }) if fee > MAX_BASIS_POINTS => Err(Error::InvalidBasisPoints),
+        Some(PlatformFee { treasury, .. }) if treasury.is_zero() => Err(Error::ZeroActorId),
+        _ => Ok(()),
+    }
+}
+
+/// Returns a treasury & a fee from given `amount`, or a zero fee if there's no
+/// platform fee.
+fn platform_fee_of(platform_fee: Option<PlatformFee>, amount: u128) -> (ActorId, u128) {
+    platform_fee.map_or((ActorId::zero(), 0), |PlatformFee { fee, treasury }| {
+        (treasury, share_of(amount, fee))
+    })
+}
+
+/// Returns milliseconds since shipping of an item after which its delivery
+/// fails (see [`DeadlineAction::FailDelivery`]).
+fn delivery_deadline(delivery_time: u64) -> u64 {
+    delivery_time.saturating_mul(2)
+}
+
+fn seller_amount(
+    penalty_schedule: &PenaltySchedule,
+    price: u128,
+    delivery_time: u64,
+    elapsed_time: u64,
+) -> u128 {
+    if elapsed_time <= delivery_time {
+        return price;
+    }
+
+    // The whole price is refunded at the deadline as on a failed delivery.
+    if elapsed_time >= delivery_deadline(delivery_time) {
+        return 0;
+    }
+
+    let lateness = u128::from(elapsed_time - delivery_time) * u128::from(MAX_BASIS_POINTS);
+
+    penalty_schedule
+        .0
+        .iter()
+        .take_while(|step| u128::from(step.lateness) * u128::from(delivery_time) <= lateness)
+        .last()
+        .map_or(price, |step| share_of(price, step.seller_share))
+}
+
 type IsPdr = fn(&Item, ActorId) -> Result<(), Error>;
 
 fn role_to_is_pdr(role: Role) -> IsPdr {
@@ -41,7 +252,7 @@ fn role_to_is_pdr(role: Role) -> IsPdr {
         Role::Producer => Item::is_producer,
         Role::Distributor => Item::is_distributor,
         Role::Retailer => Item::is_retailer,
-        _ => unreachable!(),
+        Role::Consumer => Item::is_consumer,
     }
 }
 
@@ -50,14 +261,25 @@ fn role_to_item_pdr(role: Role) -> fn(&Item) -> ActorId {
         Role::Producer => Item::producer,
         Role::Distributor => Item::distributor,
         Role::Retailer => Item::retailer,
-        _ => unreachable!(),
+        Role::Consumer => Item::consumer,
     }
 }
 
+/// Terms of [`DistributorAction::Purchase`] & [`RetailerAction::Purchase`].
+struct PurchaseTerms {
+    delivery_time: u64,
+    penalty_schedule: Option<PenaltySchedule>,
+    approval_time: Option<u64>,
+}
+
 #[derive(Default)]
 struct Item {
     info: ItemInfo,
     shipping_time: u64,
+    history: Vec<HistoryRecord>,
+    /// A cached transaction that locks the item. See
+    /// [`CachedAction::item_id()`].
+    lock: Option<(ActorId, CachedAction)>,
 }
 
 impl Item {
@@ -69,10 +291,27 @@ impl Item {
         self.info.distributor = distributor
     }
 
-    fn set_state_and_get_event(&mut self, item_id: ItemId, item_state: ItemState) -> Event {
+    fn set_state_and_get_event(
+        &mut self,
+        actor: ActorId,
+        item_id: ItemId,
+        item_state: ItemState,
+    ) -> Event {
         self.info.state = item_state;
 
-        Event {
+        if self.history.len() >= MAX_HISTORY_LENGTH {
+            self.history.remove(0);
+        }
+
+        self.history.push(HistoryRecord {
+            state: item_state,
+            actor,
+            timestamp: exec::block_timestamp(),
+            block_height: exec::block_height(),
+            price: self.info.price,
+        });
+
+        Event::Item {
             item_id,
             item_state,
         }
@@ -86,275 +325,2170 @@ impl Item {
         }
     }
 
-    fn is_producer(&self, actor_id: ActorId) -> Result<(), Error> {
-        Self::is_pdr(self.info.producer, actor_id)
-    }
+    fn is_producer(&self, actor_id: ActorId) -> Result<(), Error> {
+        Self::is_pdr(self.info.producer, actor_id)
+    }
+
+    fn is_distributor(&self, actor_id: ActorId) -> Result<(), Error> {
+        Self::is_pdr(self.info.distributor, actor_id)
+    }
+
+    fn is_retailer(&self, actor_id: ActorId) -> Result<(), Error> {
+        Self::is_pdr(self.info.retailer, actor_id)
+    }
+
+    fn is_consumer(&self, actor_id: ActorId) -> Result<(), Error> {
+        Self::is_pdr(self.info.consumer, actor_id)
+    }
+
+    fn producer(&self) -> ActorId {
+        self.info.producer
+    }
+
+    fn retailer(&self) -> ActorId {
+        self.info.retailer
+    }
+
+    fn distributor(&self) -> ActorId {
+        self.info.distributor
+    }
+
+    fn consumer(&self) -> ActorId {
+        self.info.consumer
+    }
+
+    fn is_not_recalled(&self) -> Result<(), Error> {
+        if self.info.recall_reason.is_some() {
+            Err(Error::ItemRecalled)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Checks that the item isn't locked by a cached transaction. A lock of an
+    /// expired or evicted transaction is ignored.
+    fn is_not_locked(&self, tx_manager: &TransactionManager<CachedAction>) -> Result<(), Error> {
+        match self.lock {
+            Some((actor, action)) if tx_manager.is_cached(actor, action) => Err(Error::ItemLocked),
+            _ => Ok(()),
+        }
+    }
+
+    /// Returns an actor that holds an item after buying it, if any.
+    fn holder(&self) -> Option<ActorId> {
+        match self.info.state {
+            ItemState {
+                state:
+                    ItemEventState::Received | ItemEventState::Processed | ItemEventState::Packaged,
+                by: Role::Distributor,
+            } => Some(self.info.distributor),
+            ItemState {
+                state: ItemEventState::Received,
+                by: Role::Retailer,
+            } => Some(self.info.retailer),
+            ItemState {
+                state: ItemEventState::Purchased,
+                by: Role::Consumer,
+            } => Some(self.info.consumer),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Default)]
+struct Contract {
+    items: HashMap<ItemId, Item>,
+
+    producers: HashSet<ActorId>,
+    distributors: HashSet<ActorId>,
+    retailers: HashSet<ActorId>,
+
+    fungible_token: ActorId,
+    non_fungible_token: ActorId,
+    payment_mode: PaymentMode,
+    currencies: HashSet<ActorId>,
+
+    penalty_schedule: PenaltySchedule,
+    resale_royalty: u16,
+
+    platform_fee: Option<PlatformFee>,
+    collected_fees: HashMap<ActorId, u128>,
+
+    admin: ActorId,
+    arbiter: ActorId,
+
+    subscribers: HashMap<ActorId, SubscriptionFilter>,
+
+    lots: HashMap<LotId, LotInfo>,
+    lot_id_nonce: LotId,
+
+    recall_pools: HashMap<ActorId, u128>,
+
+    offers: HashMap<ItemId, HashMap<ActorId, Offer>>,
+
+    auctions: HashMap<ItemId, Auction>,
+}
+
+impl Contract {
+    fn participants_mut(&mut self, role: Role) -> Result<&mut HashSet<ActorId>, Error> {
+        match role {
+            Role::Producer => Ok(&mut self.producers),
+            Role::Distributor => Ok(&mut self.distributors),
+            Role::Retailer => Ok(&mut self.retailers),
+            Role::Consumer => Err(Error::UnexpectedRole),
+        }
+    }
+
+    fn currency(&self, currency: Option<ActorId>) -> Result<ActorId, Error> {
+        match currency {
+            None => Ok(self.fungible_token),
+            Some(currency)
+                if self.payment_mode == PaymentMode::FungibleToken
+                    && (currency == self.fungible_token || self.currencies.contains(&currency)) =>
+            {
+                Ok(currency)
+            }
+            _ => Err(Error::UnacceptedCurrency),
+        }
+    }
+
+    fn add_participant(&mut self, role: Role, participant: ActorId) -> Result<Event, Error> {
+        if participant.is_zero() {
+            return Err(Error::ZeroActorId);
+        }
+
+        if !self.participants_mut(role)?.insert(participant) {
+            return Err(Error::ParticipantExists);
+        }
+
+        Ok(Event::ParticipantAdded { role, participant })
+    }
+
+    fn remove_participant(&mut self, role: Role, participant: ActorId) -> Result<Event, Error> {
+        if !self.participants_mut(role)?.remove(&participant) {
+            return Err(Error::ParticipantNotFound);
+        }
+
+        // Only the admin & participants can take the limited subscriber slots.
+        if !self.is_admin_or_participant(participant) {
+            self.subscribers.remove(&participant);
+        }
+
+        Ok(Event::ParticipantRemoved { role, participant })
+    }
+
+    fn change_admin(&mut self, admin: ActorId) -> Result<Event, Error> {
+        if admin.is_zero() {
+            return Err(Error::ZeroActorId);
+        }
+
+        self.admin = admin;
+
+        Ok(Event::AdminChanged(admin))
+    }
+
+    fn set_platform_fee(&mut self, platform_fee: Option<PlatformFee>) -> Result<Event, Error> {
+        is_platform_fee_valid(platform_fee)?;
+
+        self.platform_fee = platform_fee;
+
+        Ok(Event::PlatformFeeChanged(platform_fee))
+    }
+
+    fn set_transaction_ttl(
+        &self,
+        tx_manager: &mut TransactionManager<CachedAction>,
+        transaction_ttl: Option<u64>,
+    ) -> Result<Event, Error> {
+        tx_manager.ttl = transaction_ttl;
+
+        Ok(Event::TransactionTtlChanged(transaction_ttl))
+    }
+
+    fn purge_transaction(
+        &mut self,
+        tx_manager: &mut TransactionManager<CachedAction>,
+        actor: ActorId,
+        action: CachedAction,
+    ) -> Result<Event, Error> {
+        if !tx_manager.remove_transaction(actor, action) {
+            return Err(TransactionCacheError::TransactionNotFound.into());
+        }
+
+        self.unlock_items(&self.locked_item_ids(action), (actor, action));
+
+        Ok(Event::TransactionPurged(action))
+    }
+
+    /// Returns items that a transaction for given `action` locks: an item of
+    /// an action for it, or all items of a lot.
+    fn locked_item_ids(&self, action: CachedAction) -> Vec<ItemId> {
+        match action {
+            CachedAction::Lot(lot_id) => self
+                .lots
+                .get(&lot_id)
+                .map(|lot| lot.item_ids.clone())
+                .unwrap_or_default(),
+            action => action.item_id().into_iter().collect(),
+        }
+    }
+
+    /// Locks items for a transaction with given `key` unless any of them is
+    /// locked by another cached transaction.
+    fn lock_items(
+        &mut self,
+        tx_manager: &TransactionManager<CachedAction>,
+        item_ids: &[ItemId],
+        key: (ActorId, CachedAction),
+    ) -> Result<(), Error> {
+        for item_id in item_ids {
+            if let Some((actor, action)) = self.items.get(item_id).and_then(|item| item.lock) {
+                if (actor, action) != key && tx_manager.is_cached(actor, action) {
+                    return Err(Error::ItemLocked);
+                }
+            }
+        }
+
+        for item_id in item_ids {
+            if let Some(item) = self.items.get_mut(item_id) {
+                item.lock = Some(key);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn unlock_items(&mut self, item_ids: &[ItemId], key: (ActorId, CachedAction)) {
+        for item_id in item_ids {
+            if let Some(item) = self.items.get_mut(item_id) {
+                if item.lock == Some(key) {
+                    item.lock = None;
+                }
+            }
+        }
+    }
+
+    fn is_admin_or_participant(&self, actor_id: ActorId) -> bool {
+        actor_id == self.admin
+            || actor_id == self.arbiter
+            || self.producers.contains(&actor_id)
+            || self.distributors.contains(&actor_id)
+            || self.retailers.contains(&actor_id)
+    }
+
+    fn subscribe(
+        &mut self,
+        msg_source: ActorId,
+        filter: SubscriptionFilter,
+    ) -> Result<Event, Error> {
+        if !self.is_admin_or_participant(msg_source) {
+            return Err(Error::AccessRestricted);
+        }
+
+        if self.subscribers.len() >= MAX_NUMBER_OF_SUBSCRIBERS
+            && !self.subscribers.contains_key(&msg_source)
+        {
+            return Err(Error::MemoryLimitExceeded);
+        }
+
+        self.subscribers.insert(msg_source, filter);
+
+        Ok(Event::Subscribed {
+            subscriber: msg_source,
+            filter,
+        })
+    }
+
+    fn unsubscribe(&mut self, msg_source: ActorId) -> Result<Event, Error> {
+        self.subscribers
+            .remove(&msg_source)
+            .ok_or(Error::SubscriberNotFound)?;
+
+        Ok(Event::Unsubscribed(msg_source))
+    }
+
+    fn notify(&self, event: &Event) {
+        for (subscriber, filter) in &self.subscribers {
+            if filter.matches(event) {
+                // A failed notification mustn't fail an already processed
+                // action.
+                let _ = msg::send(*subscriber, event, 0);
+            }
+        }
+    }
+
+    async fn produce_item(
+        &mut self,
+        tx_guard: &mut TransactionGuard<'_, CachedAction>,
+        msg_source: ActorId,
+        token_metadata: TokenMetadata,
+        royalty: u16,
+    ) -> Result<ItemId, Error> {
+        let item_id = utils::mint_nft(tx_guard, self.non_fungible_token, token_metadata).await?;
+
+        utils::transfer_nft(tx_guard, self.non_fungible_token, msg_source, item_id).await?;
+
+        // The item may already exist if the transaction is retried.
+        self.items.entry(item_id).or_insert_with(|| {
+            let mut item = Item {
+                info: ItemInfo {
+                    producer: msg_source,
+                    royalty,
+                    ..Default::default()
+                },
+                ..Default::default()
+            };
+
+            item.set_state_and_get_event(msg_source, item_id, Default::default());
+
+            item
+        });
+
+        Ok(item_id)
+    }
+
+    async fn produce(
+        &mut self,
+        tx_guard: &mut TransactionGuard<'_, CachedAction>,
+        msg_source: ActorId,
+        token_metadata: TokenMetadata,
+        royalty: u16,
+    ) -> Result<Event, Error> {
+        if self.items.len() == MAX_NUMBER_OF_ITEMS {
+            return Err(Error::MemoryLimitExceeded);
+        }
+
+        if royalty > MAX_BASIS_POINTS {
+            return Err(Error::InvalidBasisPoints);
+        }
+
+        let item_id = self
+            .produce_item(tx_guard, msg_source, token_metadata, royalty)
+            .await?;
+
+        Ok(Event::Item {
+            item_id,
+            item_state: Default::default(),
+        })
+    }
+
+    async fn produce_batch(
+        &mut self,
+        tx_guard: &mut TransactionGuard<'_, CachedAction>,
+        msg_source: ActorId,
+        items: Vec<TokenMetadata>,
+        royalty: u16,
+    ) -> Result<Event, Error> {
+        if items.len() > MAX_BATCH_SIZE {
+            return Err(Error::BatchSizeExceeded);
+        }
+
+        if self.items.len() + items.len() > MAX_NUMBER_OF_ITEMS {
+            return Err(Error::MemoryLimitExceeded);
+        }
+
+        if royalty > MAX_BASIS_POINTS {
+            return Err(Error::InvalidBasisPoints);
+        }
+
+        let mut item_ids = Vec::with_capacity(items.len());
+
+        for token_metadata in items {
+            item_ids.push(
+                self.produce_item(tx_guard, msg_source, token_metadata, royalty)
+                    .await?,
+            );
+        }
+
+        Ok(Event::Items {
+            item_ids,
+            item_state: Default::default(),
+        })
+    }
+
+    async fn purchase(
+        &mut self,
+        tx_guard: &mut TransactionGuard<'_, CachedAction>,
+        msg_source: ActorId,
+        item_id: ItemId,
+        by: Role,
+        terms: PurchaseTerms,
+    ) -> Result<Event, Error> {
+        let item = get_mut_item(
+            &mut self.items,
+            item_id,
+            ItemState {
+                state: ItemEventState::ForSale,
+                by: buyer_to_seller_role(by),
+            },
+        )?;
+        item.is_not_recalled()?;
+
+        let penalty_schedule = terms
+            .penalty_schedule
+            .unwrap_or_else(|| self.penalty_schedule.clone());
+
+        if !is_penalty_schedule_valid(&penalty_schedule) {
+            return Err(Error::InvalidPenaltySchedule);
+        }
+
+        utils::receive_value(self.payment_mode, item.info.price)?;
+        utils::transfer_funds(
+            tx_guard,
+            self.payment_mode,
+            item.info.currency,
+            msg_source,
+            exec::program_id(),
+            item.info.price,
+        )
+        .await?;
+
+        if let Some(approval_time) = terms.approval_time {
+            send_deadline_action(DeadlineAction::ExpirePurchase(item_id), approval_time)?;
+        }
+
+        role_to_set_item_dr(by)(item, msg_source);
+        item.info.delivery_time = terms.delivery_time;
+        item.info.penalty_schedule = penalty_schedule;
+        item.info.approval_deadline = terms
+            .approval_time
+            .map(|approval_time| exec::block_timestamp().saturating_add(approval_time));
+
+        Ok(item.set_state_and_get_event(
+            msg_source,
+            item_id,
+            ItemState {
+                state: ItemEventState::Purchased,
+                by,
+            },
+        ))
+    }
+
+    async fn purchase_by_consumer(
+        &mut self,
+        tx_guard: &mut TransactionGuard<'_, CachedAction>,
+        msg_source: ActorId,
+        item_id: ItemId,
+    ) -> Result<Event, Error> {
+        let seller_by = match self
+            .items
+            .get(&item_id)
+            .ok_or(Error::ItemNotFound)?
+            .info
+            .state
+            .by
+        {
+            Role::Consumer => Role::Consumer,
+            _ => Role::Retailer,
+        };
+        let item = get_mut_item(
+            &mut self.items,
+            item_id,
+            ItemState {
+                state: ItemEventState::ForSale,
+                by: seller_by,
+            },
+        )?;
+        item.is_not_recalled()?;
+
+        let (treasury, fee) = platform_fee_of(self.platform_fee, item.info.price);
+        let royalty = share_of(
+            item.info.price - fee,
+            if seller_by == Role::Consumer {
+                self.resale_royalty
+            } else {
+                item.info.royalty
+            },
+        );
+
+        utils::receive_value(self.payment_mode, item.info.price)?;
+        utils::transfer_funds(
+            tx_guard,
+            self.payment_mode,
+            item.info.currency,
+            msg_source,
+            role_to_item_pdr(seller_by)(item),
+            item.info.price - fee - royalty,
+        )
+        .await?;
+
+        if royalty != 0 {
+            utils::transfer_funds(
+                tx_guard,
+                self.payment_mode,
+                item.info.currency,
+                msg_source,
+                item.info.producer,
+                royalty,
+            )
+            .await?;
+        }
+
+        if fee != 0 {
+            utils::transfer_funds(
+                tx_guard,
+                self.payment_mode,
+                item.info.currency,
+                msg_source,
+                treasury,
+                fee,
+            )
+            .await?;
+        }
+
+        utils::transfer_nft(tx_guard, self.non_fungible_token, msg_source, item_id).await?;
+        item.info.consumer = msg_source;
+        *self.collected_fees.entry(item.info.currency).or_default() += fee;
+
+        Ok(item.set_state_and_get_event(
+            msg_source,
+            item_id,
+            ItemState {
+                state: ItemEventState::Purchased,
+                by: Role::Consumer,
+            },
+        ))
+    }
+
+    async fn put_up_for_sale(
+        &mut self,
+        tx_guard: &mut TransactionGuard<'_, CachedAction>,
+        msg_source: ActorId,
+        item_id: ItemId,
+        by: Role,
+        price: u128,
+        currency: Option<ActorId>,
+    ) -> Result<Event, Error> {
+        let currency = self.currency(currency)?;
+        let item = get_mut_item(
+            &mut self.items,
+            item_id,
+            ItemState {
+                state: role_to_unsold_state(by),
+                by,
+            },
+        )?;
+        role_to_is_pdr(by)(item, msg_source)?;
+        item.is_not_recalled()?;
+
+        utils::transfer_nft(
+            tx_guard,
+            self.non_fungible_token,
+            exec::program_id(),
+            item_id,
+        )
+        .await?;
+        item.info.price = price;
+        item.info.currency = currency;
+
+        Ok(item.set_state_and_get_event(
+            msg_source,
+            item_id,
+            ItemState {
+                state: ItemEventState::ForSale,
+                by,
+            },
+        ))
+    }
+
+    async fn withdraw_from_sale(
+        &mut self,
+        tx_guard: &mut TransactionGuard<'_, CachedAction>,
+        msg_source: ActorId,
+        item_id: ItemId,
+        state: ItemEventState,
+        by: Role,
+    ) -> Result<Event, Error> {
+        let item = get_mut_item(
+            &mut self.items,
+            item_id,
+            ItemState {
+                state: ItemEventState::ForSale,
+                by,
+            },
+        )?;
+        role_to_is_pdr(by)(item, msg_source)?;
+
+        utils::transfer_nft(tx_guard, self.non_fungible_token, msg_source, item_id).await?;
+
+        Ok(item.set_state_and_get_event(msg_source, item_id, ItemState { state, by }))
+    }
+
+    fn update_price(
+        &mut self,
+        tx_manager: &TransactionManager<CachedAction>,
+        msg_source: ActorId,
+        item_id: ItemId,
+        by: Role,
+        price: u128,
+    ) -> Result<Event, Error> {
+        let item_state = ItemState {
+            state: ItemEventState::ForSale,
+            by,
+        };
+        let item = get_mut_item(&mut self.items, item_id, item_state)?;
+        role_to_is_pdr(by)(item, msg_source)?;
+        item.is_not_locked(tx_manager)?;
+
+        item.info.price = price;
+
+        // The item's state doesn't change, so nothing is added to its history.
+        Ok(Event::Item {
+            item_id,
+            item_state,
+        })
+    }
+
+    async fn approve(
+        &mut self,
+        tx_guard: &mut TransactionGuard<'_, CachedAction>,
+        msg_source: ActorId,
+        item_id: ItemId,
+        expected_by: Role,
+        by: Role,
+        approve: bool,
+    ) -> Result<Event, Error> {
+        let item = get_mut_item(
+            &mut self.items,
+            item_id,
+            ItemState {
+                state: ItemEventState::Purchased,
+                by: expected_by,
+            },
+        )?;
+        role_to_is_pdr(by)(item, msg_source)?;
+
+        let item_state = if approve {
+            if item
+                .info
+                .approval_deadline
+                .map_or(false, |deadline| exec::block_timestamp() >= deadline)
+            {
+                return Err(Error::ApprovalExpired);
+            }
+
+            ItemState {
+                state: ItemEventState::Approved,
+                by,
+            }
+        } else {
+            utils::transfer_funds(
+                tx_guard,
+                self.payment_mode,
+                item.info.currency,
+                exec::program_id(),
+                role_to_item_pdr(expected_by)(item),
+                item.info.price,
+            )
+            .await?;
+
+            ItemState {
+                state: ItemEventState::ForSale,
+                by,
+            }
+        };
+
+        item.info.approval_deadline = None;
+
+        Ok(item.set_state_and_get_event(msg_source, item_id, item_state))
+    }
+
+    async fn cancel_purchase(
+        &mut self,
+        tx_guard: &mut TransactionGuard<'_, CachedAction>,
+        msg_source: ActorId,
+        item_id: ItemId,
+        expected_by: Role,
+        by: Role,
+    ) -> Result<Event, Error> {
+        let item = get_mut_item(
+            &mut self.items,
+            item_id,
+            ItemState {
+                state: ItemEventState::Purchased,
+                by,
+            },
+        )?;
+        role_to_is_pdr(by)(item, msg_source)?;
+
+        utils::transfer_funds(
+            tx_guard,
+            self.payment_mode,
+            item.info.currency,
+            exec::program_id(),
+            msg_source,
+            item.info.price,
+        )
+        .await?;
+
+        item.info.approval_deadline = None;
+
+        Ok(item.set_state_and_get_event(
+            msg_source,
+            item_id,
+            ItemState {
+                state: ItemEventState::ForSale,
+                by: expected_by,
+            },
+        ))
+    }
+
+    async fn expire_purchase(
+        &mut self,
+        tx_guard: &mut TransactionGuard<'_, CachedAction>,
+        msg_source: ActorId,
+        item_id: ItemId,
+    ) -> Result<Event, Error> {
+        let by = self
+            .items
+            .get(&item_id)
+            .ok_or(Error::ItemNotFound)?
+            .info
+            .state
+            .by;
+        let item = get_mut_item(
+            &mut self.items,
+            item_id,
+            ItemState {
+                state: ItemEventState::Purchased,
+                by,
+            },
+        )?;
+
+        if item
+            .info
+            .approval_deadline
+            .map_or(true, |deadline| tx_guard.timestamp < deadline)
+        {
+            return Err(Error::DeadlineNotReached);
+        }
+
+        utils::transfer_funds(
+            tx_guard,
+            self.payment_mode,
+            item.info.currency,
+            exec::program_id(),
+            role_to_item_pdr(by)(item),
+            item.info.price,
+        )
+        .await?;
+
+        item.info.approval_deadline = None;
+
+        Ok(item.set_state_and_get_event(
+            msg_source,
+            item_id,
+            ItemState {
+                state: ItemEventState::ForSale,
+                by: buyer_to_seller_role(by),
+            },
+        ))
+    }
+
+    fn ship(
+        &mut self,
+        tx_manager: &TransactionManager<CachedAction>,
+        msg_source: ActorId,
+        item_id: ItemId,
+        by: Role,
+    ) -> Result<Event, Error> {
+        let item = get_mut_item(
+            &mut self.items,
+            item_id,
+            ItemState {
+                state: ItemEventState::Approved,
+                by,
+            },
+        )?;
+        role_to_is_pdr(by)(item, msg_source)?;
+        item.is_not_locked(tx_manager)?;
+
+        send_deadline_action(
+            DeadlineAction::FailDelivery(item_id),
+            delivery_deadline(item.info.delivery_time),
+        )?;
+
+        item.shipping_time = exec::block_timestamp();
+
+        Ok(item.set_state_and_get_event(
+            msg_source,
+            item_id,
+            ItemState {
+                state: ItemEventState::Shipped,
+                by,
+            },
+        ))
+    }
+
+    async fn receive(
+        &mut self,
+        tx_guard: &mut TransactionGuard<'_, CachedAction>,
+        msg_source: ActorId,
+        item_id: ItemId,
+        expected_by: Role,
+        by: Role,
+    ) -> Result<Event, Error> {
+        let item = get_mut_item(
+            &mut self.items,
+            item_id,
+            ItemState {
+                state: ItemEventState::Shipped,
+                by: expected_by,
+            },
+        )?;
+        role_to_is_pdr(by)(item, msg_source)?;
+
+        let program_id = exec::program_id();
+        let seller_amount = seller_amount(
+            &item.info.penalty_schedule,
+            item.info.price,
+            item.info.delivery_time,
+            tx_guard.timestamp.saturating_sub(item.shipping_time),
+        );
+        let buyer_amount = item.info.price - seller_amount;
+        let (treasury, fee) = platform_fee_of(self.platform_fee, seller_amount);
+        let royalty = if expected_by == Role::Producer {
+            0
+        } else {
+            share_of(seller_amount - fee, item.info.royalty)
+        };
+
+        if buyer_amount != 0 {
+            utils::transfer_funds(
+                tx_guard,
+                self.payment_mode,
+                item.info.currency,
+                program_id,
+                msg_source,
+                buyer_amount,
+            )
+            .await?;
+        }
+
+        if seller_amount != fee + royalty {
+            utils::transfer_funds(
+                tx_guard,
+                self.payment_mode,
+                item.info.currency,
+                program_id,
+                role_to_item_pdr(expected_by)(item),
+                seller_amount - fee - royalty,
+            )
+            .await?;
+        }
+
+        if royalty != 0 {
+            utils::transfer_funds(
+                tx_guard,
+                self.payment_mode,
+                item.info.currency,
+                program_id,
+                item.info.producer,
+                royalty,
+            )
+            .await?;
+        }
+
+        if fee != 0 {
+            utils::transfer_funds(
+                tx_guard,
+                self.payment_mode,
+                item.info.currency,
+                program_id,
+                treasury,
+                fee,
+            )
+            .await?;
+        }
+
+        utils::transfer_nft(tx_guard, self.non_fungible_token, msg_source, item_id).await?;
+        *self.collected_fees.entry(item.info.currency).or_default() += fee;
+
+        Ok(item.set_state_and_get_event(
+            msg_source,
+            item_id,
+            ItemState {
+                state: ItemEventState::Received,
+                by,
+            },
+        ))
+    }
+
+    async fn fail_delivery(
+        &mut self,
+        tx_guard: &mut TransactionGuard<'_, CachedAction>,
+        msg_source: ActorId,
+        item_id: ItemId,
+    ) -> Result<Event, Error> {
+        let by = self
+            .items
+            .get(&item_id)
+            .ok_or(Error::ItemNotFound)?
+            .info
+            .state
+            .by;
+        let item = get_mut_item(
+            &mut self.items,
+            item_id,
+            ItemState {
+                state: ItemEventState::Shipped,
+                by,
+            },
+        )?;
+
+        if tx_guard.timestamp.saturating_sub(item.shipping_time)
+            < delivery_deadline(item.info.delivery_time)
+        {
+            return Err(Error::DeadlineNotReached);
+        }
+
+        utils::transfer_funds(
+            tx_guard,
+            self.payment_mode,
+            item.info.currency,
+            exec::program_id(),
+            role_to_item_pdr(seller_to_buyer_role(by))(item),
+            item.info.price,
+        )
+        .await?;
+        utils::transfer_nft(
+            tx_guard,
+            self.non_fungible_token,
+            role_to_item_pdr(by)(item),
+            item_id,
+        )
+        .await?;
+
+        let event = item.set_state_and_get_event(
+            msg_source,
+            item_id,
+            ItemState {
+                state: ItemEventState::DeliveryFailed,
+                by,
+            },
+        );
+
+        // The failed delivery stays in the history, but the item returns to
+        // the seller to be put up for sale again.
+        item.info.state = ItemState {
+            state: role_to_unsold_state(by),
+            by,
+        };
+
+        Ok(event)
+    }
+
+    fn dispute(
+        &mut self,
+        tx_manager: &TransactionManager<CachedAction>,
+        msg_source: ActorId,
+        item_id: ItemId,
+        by: Role,
+        reason: String,
+    ) -> Result<Event, Error> {
+        let item = get_mut_item(
+            &mut self.items,
+            item_id,
+            ItemState {
+                state: ItemEventState::Shipped,
+                by: buyer_to_seller_role(by),
+            },
+        )?;
+        role_to_is_pdr(by)(item, msg_source)?;
+        item.is_not_locked(tx_manager)?;
+
+        item.info.dispute_reason = Some(reason);
+
+        Ok(item.set_state_and_get_event(
+            msg_source,
+            item_id,
+            ItemState {
+                state: ItemEventState::Disputed,
+                by,
+            },
+        ))
+    }
+
+    async fn resolve(
+        &mut self,
+        tx_guard: &mut TransactionGuard<'_, CachedAction>,
+        msg_source: ActorId,
+        item_id: ItemId,
+        seller_share: u16,
+    ) -> Result<Event, Error> {
+        if seller_share > MAX_BASIS_POINTS {
+            return Err(Error::InvalidBasisPoints);
+        }
+
+        let by = self
+            .items
+            .get(&item_id)
+            .ok_or(Error::ItemNotFound)?
+            .info
+            .state
+            .by;
+        let item = get_mut_item(
+            &mut self.items,
+            item_id,
+            ItemState {
+                state: ItemEventState::Disputed,
+                by,
+            },
+        )?;
+
+        let seller_by = buyer_to_seller_role(by);
+        let (seller, buyer) = (
+            role_to_item_pdr(seller_by)(item),
+            role_to_item_pdr(by)(item),
+        );
+        let program_id = exec::program_id();
+        let seller_amount = share_of(item.info.price, seller_share);
+        let buyer_amount = item.info.price - seller_amount;
+        let (treasury, fee) = platform_fee_of(self.platform_fee, seller_amount);
+        let royalty = if seller_by == Role::Producer {
+            0
+        } else {
+            share_of(seller_amount - fee, item.info.royalty)
+        };
+
+        if buyer_amount != 0 {
+            utils::transfer_funds(
+                tx_guard,
+                self.payment_mode,
+                item.info.currency,
+                program_id,
+                buyer,
+                buyer_amount,
+            )
+            .await?;
+        }
+
+        if seller_amount != fee + royalty {
+            utils::transfer_funds(
+                tx_guard,
+                self.payment_mode,
+                item.info.currency,
+                program_id,
+                seller,
+                seller_amount - fee - royalty,
+            )
+            .await?;
+        }
+
+        if royalty != 0 {
+            utils::transfer_funds(
+                tx_guard,
+                self.payment_mode,
+                item.info.currency,
+                program_id,
+                item.info.producer,
+                royalty,
+            )
+            .await?;
+        }
+
+        if fee != 0 {
+            utils::transfer_funds(
+                tx_guard,
+                self.payment_mode,
+                item.info.currency,
+                program_id,
+                treasury,
+                fee,
+            )
+            .await?;
+        }
+
+        let item_state = if seller_amount == 0 {
+            utils::transfer_nft(tx_guard, self.non_fungible_token, seller, item_id).await?;
+
+            ItemState {
+                state: role_to_unsold_state(seller_by),
+                by: seller_by,
+            }
+        } else {
+            utils::transfer_nft(tx_guard, self.non_fungible_token, buyer, item_id).await?;
+
+            ItemState {
+                state: ItemEventState::Received,
+                by,
+            }
+        };
+
+        *self.collected_fees.entry(item.info.currency).or_default() += fee;
+
+        Ok(item.set_state_and_get_event(msg_source, item_id, item_state))
+    }
+
+    async fn fund_recall_pool(
+        &mut self,
+        tx_guard: &mut TransactionGuard<'_, CachedAction>,
+        msg_source: ActorId,
+        amount: u128,
+    ) -> Result<Event, Error> {
+        utils::receive_value(self.payment_mode, amount)?;
+        utils::transfer_funds(
+            tx_guard,
+            self.payment_mode,
+            self.fungible_token,
+            msg_source,
+            exec::program_id(),
+            amount,
+        )
+        .await?;
+
+        *self.recall_pools.entry(msg_source).or_default() += amount;
+
+        Ok(Event::RecallPoolFunded {
+            producer: msg_source,
+            amount,
+        })
+    }
+
+    async fn recall(
+        &mut self,
+        tx_guard: &mut TransactionGuard<'_, CachedAction>,
+        msg_source: ActorId,
+        mut item_ids: Vec<ItemId>,
+        reason: String,
+    ) -> Result<Event, Error> {
+        if item_ids.len() > MAX_BATCH_SIZE {
+            return Err(Error::BatchSizeExceeded);
+        }
+
+        item_ids.sort_unstable();
+        item_ids.dedup();
+
+        let mut pool = self
+            .recall_pools
+            .get(&msg_source)
+            .copied()
+            .unwrap_or_default();
+        let mut refunds = Vec::new();
+
+        for item_id in &item_ids {
+            // Items in a lot can be recalled too, so `get_mut_item()` isn't
+            // used here.
+            let item = self.items.get(item_id).ok_or(Error::ItemNotFound)?;
+
+            item.is_producer(msg_source)?;
+            item.is_not_recalled()?;
+            item.is_not_locked(tx_guard.manager())?;
+
+            if let Some(holder) = item.holder() {
+                // Recall pools are in the default currency, so items in other
+                // ones aren't refunded.
+                if item.info.currency == self.fungible_token && item.info.price <= pool {
+                    pool -= item.info.price;
+                    refunds.push((*item_id, holder, item.info.price));
+                }
+            }
+        }
+
+        let program_id = exec::program_id();
+
+        for (_, holder, amount) in &refunds {
+            if *amount != 0 {
+                utils::transfer_funds(
+                    tx_guard,
+                    self.payment_mode,
+                    self.fungible_token,
+                    program_id,
+                    *holder,
+                    *amount,
+                )
+                .await?;
+            }
+        }
+
+        if !refunds.is_empty() {
+            self.recall_pools.insert(msg_source, pool);
+        }
+
+        for item_id in &item_ids {
+            if let Some(item) = self.items.get_mut(item_id) {
+                item.info.recall_reason = Some(reason.clone());
+            }
+        }
+
+        Ok(Event::Recalled {
+            item_ids,
+            refunded_item_ids: refunds.into_iter().map(|(item_id, ..)| item_id).collect(),
+        })
+    }
+
+    fn set_lot_state_and_get_event(
+        &mut self,
+        msg_source: ActorId,
+        lot_id: LotId,
+        item_ids: Vec<ItemId>,
+        item_state: ItemState,
+    ) -> Event {
+        if let Some(lot) = self.lots.get_mut(&lot_id) {
+            lot.state = item_state;
+        }
+
+        for item_id in &item_ids {
+            lot_item_mut(&mut self.items, *item_id)
+                .set_state_and_get_event(msg_source, *item_id, item_state);
+        }
+
+        Event::Lot {
+            lot_id,
+            item_ids,
+            item_state,
+        }
+    }
+
+    fn create_lot(
+        &mut self,
+        tx_manager: &TransactionManager<CachedAction>,
+        msg_source: ActorId,
+        item_ids: Vec<ItemId>,
+    ) -> Result<Event, Error> {
+        if item_ids.is_empty()
+            || item_ids.len() > MAX_LOT_SIZE
+            || item_ids.iter().collect::<HashSet<_>>().len() != item_ids.len()
+        {
+            return Err(Error::InvalidLot);
+        }
+
+        let item_state = self
+            .items
+            .get(&item_ids[0])
+            .ok_or(Error::ItemNotFound)?
+            .info
+            .state;
+
+        if !is_lot_unsold(item_state) {
+            return Err(Error::UnexpectedItemState);
+        }
+
+        for item_id in &item_ids {
+            let item = get_mut_item(&mut self.items, *item_id, item_state)?;
+            role_to_is_pdr(item_state.by)(item, msg_source)?;
+            item.is_not_locked(tx_manager)?;
+        }
+
+        let lot_id = self.lot_id_nonce;
+
+        self.lot_id_nonce += 1;
+
+        for item_id in &item_ids {
+            lot_item_mut(&mut self.items, *item_id).info.lot_id = Some(lot_id);
+        }
+
+        self.lots.insert(
+            lot_id,
+            LotInfo {
+                item_ids: item_ids.clone(),
+                state: item_state,
+            },
+        );
+
+        Ok(Event::Lot {
+            lot_id,
+            item_ids,
+            item_state,
+        })
+    }
+
+    fn split_lot(
+        &mut self,
+        tx_manager: &TransactionManager<CachedAction>,
+        msg_source: ActorId,
+        lot_id: LotId,
+    ) -> Result<Event, Error> {
+        let lot = get_lot(&self.lots, lot_id, None)?;
+
+        if matches!(
+            lot.state.state,
+            ItemEventState::ForSale
+                | ItemEventState::Purchased
+                | ItemEventState::Approved
+                | ItemEventState::Shipped
+        ) {
+            return Err(Error::UnexpectedItemState);
+        }
+
+        is_lot_pdr(&self.items, &lot.item_ids, lot.state.by, msg_source)?;
+        is_lot_not_locked(&self.items, &lot.item_ids, tx_manager)?;
+
+        let item_ids = lot.item_ids.clone();
+
+        self.lots.remove(&lot_id);
+
+        for item_id in &item_ids {
+            lot_item_mut(&mut self.items, *item_id).info.lot_id = None;
+        }
+
+        Ok(Event::LotSplit { lot_id, item_ids })
+    }
+
+    async fn put_up_lot_for_sale(
+        &mut self,
+        tx_guard: &mut TransactionGuard<'_, CachedAction>,
+        msg_source: ActorId,
+        lot_id: LotId,
+        price: u128,
+        currency: Option<ActorId>,
+    ) -> Result<Event, Error> {
+        let currency = self.currency(currency)?;
+        let lot = get_lot(&self.lots, lot_id, None)?;
+        let by = lot.state.by;
+
+        if !is_lot_unsold(lot.state) {
+            return Err(Error::UnexpectedItemState);
+        }
+
+        is_lot_pdr(&self.items, &lot.item_ids, by, msg_source)?;
+        is_lot_not_recalled(&self.items, &lot.item_ids)?;
+
+        let item_ids = lot.item_ids.clone();
+        let program_id = exec::program_id();
+
+        for item_id in &item_ids {
+            utils::transfer_nft(tx_guard, self.non_fungible_token, program_id, *item_id).await?;
+        }
+
+        for item_id in &item_ids {
+            let item = lot_item_mut(&mut self.items, *item_id);
+
+            item.info.price = price;
+            item.info.currency = currency;
+        }
+
+        Ok(self.set_lot_state_and_get_event(
+            msg_source,
+            lot_id,
+            item_ids,
+            ItemState {
+                state: ItemEventState::ForSale,
+                by,
+            },
+        ))
+    }
+
+    async fn withdraw_lot_from_sale(
+        &mut self,
+        tx_guard: &mut TransactionGuard<'_, CachedAction>,
+        msg_source: ActorId,
+        lot_id: LotId,
+    ) -> Result<Event, Error> {
+        let lot = get_lot(&self.lots, lot_id, Some(ItemEventState::ForSale))?;
+        let by = lot.state.by;
+
+        is_lot_pdr(&self.items, &lot.item_ids, by, msg_source)?;
+
+        let item_ids = lot.item_ids.clone();
+
+        for item_id in &item_ids {
+            utils::transfer_nft(tx_guard, self.non_fungible_token, msg_source, *item_id).await?;
+        }
+
+        Ok(self.set_lot_state_and_get_event(
+            msg_source,
+            lot_id,
+            item_ids,
+            ItemState {
+                state: role_to_unsold_state(by),
+                by,
+            },
+        ))
+    }
+
+    async fn purchase_lot(
+        &mut self,
+        tx_guard: &mut TransactionGuard<'_, CachedAction>,
+        msg_source: ActorId,
+        lot_id: LotId,
+        delivery_time: u64,
+        penalty_schedule: Option<PenaltySchedule>,
+    ) -> Result<Event, Error> {
+        let lot = get_lot(&self.lots, lot_id, Some(ItemEventState::ForSale))?;
+        let by = seller_to_buyer_role(lot.state.by);
+        let item_ids = lot.item_ids.clone();
+
+        is_lot_not_recalled(&self.items, &item_ids)?;
+
+        if !self.participants_mut(by)?.contains(&msg_source) {
+            return Err(Error::AccessRestricted);
+        }
+
+        let penalty_schedule = penalty_schedule.unwrap_or_else(|| self.penalty_schedule.clone());
+
+        if !is_penalty_schedule_valid(&penalty_schedule) {
+            return Err(Error::InvalidPenaltySchedule);
+        }
+
+        let lot_price = lot_price(&self.items, &item_ids);
+
+        utils::receive_value(self.payment_mode, lot_price)?;
+        utils::transfer_funds(
+            tx_guard,
+            self.payment_mode,
+            lot_currency(&self.items, &item_ids),
+            msg_source,
+            exec::program_id(),
+            lot_price,
+        )
+        .await?;
+
+        for item_id in &item_ids {
+            let item = lot_item_mut(&mut self.items, *item_id);
+
+            role_to_set_item_dr(by)(item, msg_source);
+            item.info.delivery_time = delivery_time;
+            item.info.penalty_schedule = penalty_schedule.clone();
+        }
+
+        Ok(self.set_lot_state_and_get_event(
+            msg_source,
+            lot_id,
+            item_ids,
+            ItemState {
+                state: ItemEventState::Purchased,
+                by,
+            },
+        ))
+    }
+
+    async fn approve_lot(
+        &mut self,
+        tx_guard: &mut TransactionGuard<'_, CachedAction>,
+        msg_source: ActorId,
+        lot_id: LotId,
+        approve: bool,
+    ) -> Result<Event, Error> {
+        let lot = get_lot(&self.lots, lot_id, Some(ItemEventState::Purchased))?;
+        let expected_by = lot.state.by;
+        let by = buyer_to_seller_role(expected_by);
+
+        is_lot_pdr(&self.items, &lot.item_ids, by, msg_source)?;
+
+        let item_ids = lot.item_ids.clone();
+        let item_state = if approve {
+            ItemState {
+                state: ItemEventState::Approved,
+                by,
+            }
+        } else {
+            utils::transfer_funds(
+                tx_guard,
+                self.payment_mode,
+                lot_currency(&self.items, &item_ids),
+                exec::program_id(),
+                role_to_item_pdr(expected_by)(lot_item(&self.items, item_ids[0])),
+                lot_price(&self.items, &item_ids),
+            )
+            .await?;
+
+            ItemState {
+                state: ItemEventState::ForSale,
+                by,
+            }
+        };
+
+        Ok(self.set_lot_state_and_get_event(msg_source, lot_id, item_ids, item_state))
+    }
+
+    fn ship_lot(
+        &mut self,
+        tx_manager: &TransactionManager<CachedAction>,
+        msg_source: ActorId,
+        lot_id: LotId,
+    ) -> Result<Event, Error> {
+        let lot = get_lot(&self.lots, lot_id, Some(ItemEventState::Approved))?;
+        let by = lot.state.by;
+
+        is_lot_pdr(&self.items, &lot.item_ids, by, msg_source)?;
+        is_lot_not_locked(&self.items, &lot.item_ids, tx_manager)?;
+
+        let item_ids = lot.item_ids.clone();
+
+        send_deadline_action(
+            DeadlineAction::FailLotDelivery(lot_id),
+            delivery_deadline(lot_item(&self.items, item_ids[0]).info.delivery_time),
+        )?;
+
+        let shipping_time = exec::block_timestamp();
+
+        for item_id in &item_ids {
+            lot_item_mut(&mut self.items, *item_id).shipping_time = shipping_time;
+        }
+
+        Ok(self.set_lot_state_and_get_event(
+            msg_source,
+            lot_id,
+            item_ids,
+            ItemState {
+                state: ItemEventState::Shipped,
+                by,
+            },
+        ))
+    }
+
+    async fn receive_lot(
+        &mut self,
+        tx_guard: &mut TransactionGuard<'_, CachedAction>,
+        msg_source: ActorId,
+        lot_id: LotId,
+    ) -> Result<Event, Error> {
+        let lot = get_lot(&self.lots, lot_id, Some(ItemEventState::Shipped))?;
+        let expected_by = lot.state.by;
+        let by = seller_to_buyer_role(expected_by);
+
+        is_lot_pdr(&self.items, &lot.item_ids, by, msg_source)?;
+
+        let item_ids = lot.item_ids.clone();
+        let (mut buyer_amount, mut seller_amount, mut fee) = (0, 0, 0);
+        let mut royalties: Vec<(ActorId, u128)> = Vec::new();
+
+        for item_id in &item_ids {
+            let item = lot_item(&self.items, *item_id);
+            let item_seller_amount = crate::seller_amount(
+                &item.info.penalty_schedule,
+                item.info.price,
+                item.info.delivery_time,
+                tx_guard.timestamp.saturating_sub(item.shipping_time),
+            );
+            let item_fee = platform_fee_of(self.platform_fee, item_seller_amount).1;
+            let royalty = if expected_by == Role::Producer {
+                0
+            } else {
+                share_of(item_seller_amount - item_fee, item.info.royalty)
+            };
+
+            buyer_amount += item.info.price - item_seller_amount;
+            seller_amount += item_seller_amount - item_fee - royalty;
+            fee += item_fee;
+
+            if royalty != 0 {
+                match royalties
+                    .iter_mut()
+                    .find(|(producer, _)| *producer == item.info.producer)
+                {
+                    Some((_, amount)) => *amount += royalty,
+                    None => royalties.push((item.info.producer, royalty)),
+                }
+            }
+        }
+
+        let seller = role_to_item_pdr(expected_by)(lot_item(&self.items, item_ids[0]));
+        let treasury = self
+            .platform_fee
+            .map_or(ActorId::zero(), |platform_fee| platform_fee.treasury);
+        let currency = lot_currency(&self.items, &item_ids);
+        let program_id = exec::program_id();
+
+        if buyer_amount != 0 {
+            utils::transfer_funds(
+                tx_guard,
+                self.payment_mode,
+                currency,
+                program_id,
+                msg_source,
+                buyer_amount,
+            )
+            .await?;
+        }
+
+        if seller_amount != 0 {
+            utils::transfer_funds(
+                tx_guard,
+                self.payment_mode,
+                currency,
+                program_id,
+                seller,
+                seller_amount,
+            )
+            .await?;
+        }
+
+        for (producer, royalty) in royalties {
+            utils::transfer_funds(
+                tx_guard,
+                self.payment_mode,
+                currency,
+                program_id,
+                producer,
+                royalty,
+            )
+            .await?;
+        }
+
+        if fee != 0 {
+            utils::transfer_funds(
+                tx_guard,
+                self.payment_mode,
+                currency,
+                program_id,
+                treasury,
+                fee,
+            )
+            .await?;
+        }
+
+        for item_id in &item_ids {
+            utils::transfer_nft(tx_guard, self.non_fungible_token, msg_source, *item_id).await?;
+        }
+
+        *self.collected_fees.entry(currency).or_default() += fee;
+
+        Ok(self.set_lot_state_and_get_event(
+            msg_source,
+            lot_id,
+            item_ids,
+            ItemState {
+                state: ItemEventState::Received,
+                by,
+            },
+        ))
+    }
+
+    async fn fail_lot_delivery(
+        &mut self,
+        tx_guard: &mut TransactionGuard<'_, CachedAction>,
+        msg_source: ActorId,
+        lot_id: LotId,
+    ) -> Result<Event, Error> {
+        let lot = get_lot(&self.lots, lot_id, Some(ItemEventState::Shipped))?;
+        let by = lot.state.by;
+        let item_ids = lot.item_ids.clone();
+        let item = lot_item(&self.items, item_ids[0]);
+
+        if tx_guard.timestamp.saturating_sub(item.shipping_time)
+            < delivery_deadline(item.info.delivery_time)
+        {
+            return Err(Error::DeadlineNotReached);
+        }
+
+        let (seller, buyer) = (
+            role_to_item_pdr(by)(item),
+            role_to_item_pdr(seller_to_buyer_role(by))(item),
+        );
+
+        utils::transfer_funds(
+            tx_guard,
+            self.payment_mode,
+            lot_currency(&self.items, &item_ids),
+            exec::program_id(),
+            buyer,
+            lot_price(&self.items, &item_ids),
+        )
+        .await?;
+
+        for item_id in &item_ids {
+            utils::transfer_nft(tx_guard, self.non_fungible_token, seller, *item_id).await?;
+        }
+
+        let event = self.set_lot_state_and_get_event(
+            msg_source,
+            lot_id,
+            item_ids.clone(),
+            ItemState {
+                state: ItemEventState::DeliveryFailed,
+                by,
+            },
+        );
+
+        // As with a single item, the lot returns to the seller to be put up for
+        // sale again.
+        let item_state = ItemState {
+            state: role_to_unsold_state(by),
+            by,
+        };
+
+        if let Some(lot) = self.lots.get_mut(&lot_id) {
+            lot.state = item_state;
+        }
+
+        for item_id in &item_ids {
+            lot_item_mut(&mut self.items, *item_id).info.state = item_state;
+        }
+
+        Ok(event)
+    }
+
+    fn offer(&self, item_id: ItemId, buyer: ActorId) -> Result<Offer, Error> {
+        self.offers
+            .get(&item_id)
+            .and_then(|offers| offers.get(&buyer))
+            .copied()
+            .ok_or(Error::OfferNotFound)
+    }
+
+    async fn make_offer(
+        &mut self,
+        tx_guard: &mut TransactionGuard<'_, CachedAction>,
+        msg_source: ActorId,
+        item_id: ItemId,
+        price: u128,
+        delivery_time: u64,
+    ) -> Result<Event, Error> {
+        let item = get_mut_item_for_offer(&mut self.items, item_id)?;
+        let by = seller_to_buyer_role(item.info.state.by);
+        let currency = item.info.currency;
+
+        if !self.participants_mut(by)?.contains(&msg_source) {
+            return Err(Error::AccessRestricted);
+        }
+
+        if let Some(offers) = self.offers.get(&item_id) {
+            if offers.contains_key(&msg_source) {
+                return Err(Error::OfferExists);
+            }
+
+            if offers.len() >= MAX_NUMBER_OF_OFFERS {
+                return Err(Error::MemoryLimitExceeded);
+            }
+        }
+
+        utils::receive_value(self.payment_mode, price)?;
+        utils::transfer_funds(
+            tx_guard,
+            self.payment_mode,
+            currency,
+            msg_source,
+            exec::program_id(),
+            price,
+        )
+        .await?;
+
+        self.offers.entry(item_id).or_default().insert(
+            msg_source,
+            Offer {
+                price,
+                currency,
+                delivery_time,
+                counter_price: None,
+            },
+        );
+
+        Ok(Event::OfferMade {
+            item_id,
+            buyer: msg_source,
+            price,
+        })
+    }
+
+    async fn withdraw_offer(
+        &mut self,
+        tx_guard: &mut TransactionGuard<'_, CachedAction>,
+        msg_source: ActorId,
+        item_id: ItemId,
+    ) -> Result<Event, Error> {
+        let offer = self.offer(item_id, msg_source)?;
+
+        utils::transfer_funds(
+            tx_guard,
+            self.payment_mode,
+            offer.currency,
+            exec::program_id(),
+            msg_source,
+            offer.price,
+        )
+        .await?;
+
+        let offers = self
+            .offers
+            .get_mut(&item_id)
+            .expect("offers must exist for the offer");
+
+        offers.remove(&msg_source);
+
+        if offers.is_empty() {
+            self.offers.remove(&item_id);
+        }
+
+        Ok(Event::OfferWithdrawn {
+            item_id,
+            buyer: msg_source,
+        })
+    }
+
+    fn counter_offer(
+        &mut self,
+        tx_manager: &TransactionManager<CachedAction>,
+        msg_source: ActorId,
+        item_id: ItemId,
+        buyer: ActorId,
+        price: u128,
+    ) -> Result<Event, Error> {
+        let item = get_mut_item_for_offer(&mut self.items, item_id)?;
+        role_to_is_pdr(item.info.state.by)(item, msg_source)?;
+        item.is_not_locked(tx_manager)?;
+
+        self.offers
+            .get_mut(&item_id)
+            .and_then(|offers| offers.get_mut(&buyer))
+            .ok_or(Error::OfferNotFound)?
+            .counter_price = Some(price);
 
-    fn is_distributor(&self, actor_id: ActorId) -> Result<(), Error> {
-        Self::is_pdr(self.info.distributor, actor_id)
+        Ok(Event::OfferCountered {
+            item_id,
+            buyer,
+            price,
+        })
     }
 
-    fn is_retailer(&self, actor_id: ActorId) -> Result<(), Error> {
-        Self::is_pdr(self.info.retailer, actor_id)
-    }
+    async fn accept_offer(
+        &mut self,
+        tx_guard: &mut TransactionGuard<'_, CachedAction>,
+        msg_source: ActorId,
+        item_id: ItemId,
+        buyer: ActorId,
+    ) -> Result<Event, Error> {
+        let item = get_mut_item_for_offer(&mut self.items, item_id)?;
+        role_to_is_pdr(item.info.state.by)(item, msg_source)?;
 
-    fn producer(&self) -> ActorId {
-        self.info.producer
-    }
+        let offer = self.offer(item_id, buyer)?;
 
-    fn retailer(&self) -> ActorId {
-        self.info.retailer
+        self.take_offer(tx_guard, item_id, buyer, offer, offer.price)
+            .await
     }
 
-    fn distributor(&self) -> ActorId {
-        self.info.distributor
-    }
-}
+    async fn accept_counter_offer(
+        &mut self,
+        tx_guard: &mut TransactionGuard<'_, CachedAction>,
+        msg_source: ActorId,
+        item_id: ItemId,
+    ) -> Result<Event, Error> {
+        get_mut_item_for_offer(&mut self.items, item_id)?;
 
-#[derive(Default)]
-struct Contract {
-    items: HashMap<ItemId, Item>,
+        let offer = self.offer(item_id, msg_source)?;
+        let price = offer.counter_price.ok_or(Error::OfferNotFound)?;
+        let program_id = exec::program_id();
 
-    producers: HashSet<ActorId>,
-    distributors: HashSet<ActorId>,
-    retailers: HashSet<ActorId>,
+        if price > offer.price {
+            utils::receive_value(self.payment_mode, price - offer.price)?;
+            utils::transfer_funds(
+                tx_guard,
+                self.payment_mode,
+                offer.currency,
+                msg_source,
+                program_id,
+                price - offer.price,
+            )
+            .await?;
+        } else if price < offer.price {
+            utils::transfer_funds(
+                tx_guard,
+                self.payment_mode,
+                offer.currency,
+                program_id,
+                msg_source,
+                offer.price - price,
+            )
+            .await?;
+        }
 
-    fungible_token: ActorId,
-    non_fungible_token: ActorId,
-}
+        self.take_offer(tx_guard, item_id, msg_source, offer, price)
+            .await
+    }
 
-impl Contract {
-    async fn produce(
+    /// Sells an item to `buyer` for `price` of its `offer` and refunds all
+    /// other offers for the item.
+    async fn take_offer(
         &mut self,
         tx_guard: &mut TransactionGuard<'_, CachedAction>,
-        msg_source: ActorId,
-        token_metadata: TokenMetadata,
+        item_id: ItemId,
+        buyer: ActorId,
+        offer: Offer,
+        price: u128,
     ) -> Result<Event, Error> {
-        if self.items.len() == MAX_NUMBER_OF_ITEMS {
-            return Err(Error::MemoryLimitExceeded);
+        let mut other_offers: Vec<_> = self.offers[&item_id]
+            .iter()
+            .filter(|(other_buyer, _)| **other_buyer != buyer)
+            .map(|(other_buyer, other_offer)| (*other_buyer, *other_offer))
+            .collect();
+        let program_id = exec::program_id();
+
+        // The order must be the same on a retry of the transaction.
+        other_offers.sort_unstable_by_key(|(other_buyer, _)| *other_buyer);
+
+        for (other_buyer, other_offer) in other_offers {
+            utils::transfer_funds(
+                tx_guard,
+                self.payment_mode,
+                other_offer.currency,
+                program_id,
+                other_buyer,
+                other_offer.price,
+            )
+            .await?;
         }
 
-        let item_id = utils::mint_nft(tx_guard, self.non_fungible_token, token_metadata).await?;
+        self.offers.remove(&item_id);
 
-        utils::transfer_nft(tx_guard, self.non_fungible_token, msg_source, item_id).await?;
+        let item = self.items.get_mut(&item_id).ok_or(Error::ItemNotFound)?;
+        let seller_by = item.info.state.by;
+        let by = seller_to_buyer_role(seller_by);
 
-        self.items.insert(
+        role_to_set_item_dr(by)(item, buyer);
+        item.info.price = price;
+        item.info.currency = offer.currency;
+        item.info.delivery_time = offer.delivery_time;
+        item.info.penalty_schedule = self.penalty_schedule.clone();
+        item.set_state_and_get_event(
+            buyer,
             item_id,
-            Item {
-                info: ItemInfo {
-                    producer: msg_source,
-                    ..Default::default()
-                },
-                ..Default::default()
+            ItemState {
+                state: ItemEventState::Purchased,
+                by,
             },
         );
 
-        Ok(Event {
+        Ok(item.set_state_and_get_event(
+            role_to_item_pdr(seller_by)(item),
             item_id,
-            item_state: Default::default(),
-        })
+            ItemState {
+                state: ItemEventState::Approved,
+                by: seller_by,
+            },
+        ))
     }
 
-    async fn purchase(
+    async fn start_auction(
         &mut self,
         tx_guard: &mut TransactionGuard<'_, CachedAction>,
         msg_source: ActorId,
         item_id: ItemId,
-        expected_by: Role,
-        by: Role,
-        delivery_time: u64,
+        reserve_price: u128,
+        duration: u64,
+        currency: Option<ActorId>,
     ) -> Result<Event, Error> {
+        let currency = self.currency(currency)?;
+        let by = self
+            .items
+            .get(&item_id)
+            .ok_or(Error::ItemNotFound)?
+            .info
+            .state
+            .by;
+
+        if !matches!(by, Role::Producer | Role::Distributor) {
+            return Err(Error::UnexpectedItemState);
+        }
+
         let item = get_mut_item(
             &mut self.items,
             item_id,
             ItemState {
-                state: ItemEventState::ForSale,
-                by: expected_by,
+                state: role_to_unsold_state(by),
+                by,
             },
         )?;
+        role_to_is_pdr(by)(item, msg_source)?;
+        item.is_not_recalled()?;
 
-        utils::transfer_ftokens(
+        utils::transfer_nft(
             tx_guard,
-            self.fungible_token,
-            msg_source,
+            self.non_fungible_token,
             exec::program_id(),
-            item.info.price,
+            item_id,
         )
         .await?;
+        send_deadline_action(DeadlineAction::SettleAuction(item_id), duration)?;
 
-        role_to_set_item_dr(by)(item, msg_source);
-        item.info.delivery_time = delivery_time;
+        item.info.price = reserve_price;
+        item.info.currency = currency;
+        self.auctions.insert(
+            item_id,
+            Auction {
+                reserve_price,
+                end_time: exec::block_timestamp().saturating_add(duration),
+                highest_bid: None,
+            },
+        );
 
         Ok(item.set_state_and_get_event(
+            msg_source,
             item_id,
             ItemState {
-                state: ItemEventState::Purchased,
+                state: ItemEventState::Auctioned,
                 by,
             },
         ))
     }
 
-    async fn put_up_for_sale(
+    async fn bid(
         &mut self,
         tx_guard: &mut TransactionGuard<'_, CachedAction>,
         msg_source: ActorId,
         item_id: ItemId,
-        expected_item_event_state: ItemEventState,
-        by: Role,
         price: u128,
+        delivery_time: u64,
     ) -> Result<Event, Error> {
+        let by = self
+            .items
+            .get(&item_id)
+            .ok_or(Error::ItemNotFound)?
+            .info
+            .state
+            .by;
         let item = get_mut_item(
             &mut self.items,
             item_id,
             ItemState {
-                state: expected_item_event_state,
+                state: ItemEventState::Auctioned,
                 by,
             },
         )?;
-        role_to_is_pdr(by)(item, msg_source)?;
+        item.is_not_recalled()?;
 
-        utils::transfer_nft(
+        let currency = item.info.currency;
+
+        if !self
+            .participants_mut(seller_to_buyer_role(by))?
+            .contains(&msg_source)
+        {
+            return Err(Error::AccessRestricted);
+        }
+
+        let auction = self.auctions[&item_id];
+
+        if tx_guard.timestamp >= auction.end_time {
+            return Err(Error::AuctionEnded);
+        }
+
+        if price < auction.reserve_price
+            || auction
+                .highest_bid
+                .map_or(false, |highest_bid| price <= highest_bid.price)
+        {
+            return Err(Error::BidTooLow);
+        }
+
+        let program_id = exec::program_id();
+
+        utils::receive_value(self.payment_mode, price)?;
+        utils::transfer_funds(
             tx_guard,
-            self.non_fungible_token,
-            exec::program_id(),
-            item_id,
+            self.payment_mode,
+            currency,
+            msg_source,
+            program_id,
+            price,
         )
         .await?;
-        item.info.price = price;
 
-        Ok(item.set_state_and_get_event(
+        if let Some(highest_bid) = auction.highest_bid {
+            utils::transfer_funds(
+                tx_guard,
+                self.payment_mode,
+                currency,
+                program_id,
+                highest_bid.bidder,
+                highest_bid.price,
+            )
+            .await?;
+        }
+
+        self.auctions
+            .get_mut(&item_id)
+            .expect("auction must exist for the auctioned item")
+            .highest_bid = Some(Bid {
+            bidder: msg_source,
+            price,
+            delivery_time,
+        });
+
+        Ok(Event::AuctionBid {
             item_id,
-            ItemState {
-                state: ItemEventState::ForSale,
-                by,
-            },
-        ))
+            bidder: msg_source,
+            price,
+        })
     }
 
-    async fn approve(
+    async fn settle_auction(
         &mut self,
         tx_guard: &mut TransactionGuard<'_, CachedAction>,
         msg_source: ActorId,
         item_id: ItemId,
-        expected_by: Role,
-        by: Role,
-        approve: bool,
     ) -> Result<Event, Error> {
+        let by = self
+            .items
+            .get(&item_id)
+            .ok_or(Error::ItemNotFound)?
+            .info
+            .state
+            .by;
         let item = get_mut_item(
             &mut self.items,
             item_id,
             ItemState {
-                state: ItemEventState::Purchased,
-                by: expected_by,
+                state: ItemEventState::Auctioned,
+                by,
             },
         )?;
-        role_to_is_pdr(by)(item, msg_source)?;
+        let auction = self.auctions[&item_id];
+
+        if tx_guard.timestamp < auction.end_time {
+            return Err(Error::DeadlineNotReached);
+        }
+
+        let item_state = if let Some(highest_bid) = auction.highest_bid {
+            let buyer_by = seller_to_buyer_role(by);
+
+            role_to_set_item_dr(buyer_by)(item, highest_bid.bidder);
+            item.info.price = highest_bid.price;
+            item.info.delivery_time = highest_bid.delivery_time;
+            item.info.penalty_schedule = self.penalty_schedule.clone();
 
-        let item_state = if approve {
             ItemState {
-                state: ItemEventState::Approved,
-                by,
+                state: ItemEventState::Purchased,
+                by: buyer_by,
             }
         } else {
-            utils::transfer_ftokens(
+            utils::transfer_nft(
                 tx_guard,
-                self.fungible_token,
-                exec::program_id(),
-                role_to_item_pdr(expected_by)(item),
-                item.info.price,
+                self.non_fungible_token,
+                role_to_item_pdr(by)(item),
+                item_id,
             )
             .await?;
 
             ItemState {
-                state: ItemEventState::ForSale,
+                state: role_to_unsold_state(by),
                 by,
             }
         };
 
-        Ok(item.set_state_and_get_event(item_id, item_state))
-    }
-
-    fn ship(&mut self, msg_source: ActorId, item_id: ItemId, by: Role) -> Result<Event, Error> {
-        let item = get_mut_item(
-            &mut self.items,
-            item_id,
-            ItemState {
-                state: ItemEventState::Approved,
-                by,
-            },
-        )?;
-        role_to_is_pdr(by)(item, msg_source)?;
-
-        item.shipping_time = exec::block_timestamp();
+        self.auctions.remove(&item_id);
 
-        Ok(item.set_state_and_get_event(
-            item_id,
-            ItemState {
-                state: ItemEventState::Shipped,
-                by,
-            },
-        ))
+        Ok(item.set_state_and_get_event(msg_source, item_id, item_state))
     }
 
-    async fn receive(
+    async fn abort(
         &mut self,
-        tx_guard: &mut TransactionGuard<'_, CachedAction>,
+        tx_manager: &mut TransactionManager<CachedAction>,
         msg_source: ActorId,
-        item_id: ItemId,
-        expected_by: Role,
-        by: Role,
+        action: CachedAction,
     ) -> Result<Event, Error> {
-        let item = get_mut_item(
-            &mut self.items,
-            item_id,
-            ItemState {
-                state: ItemEventState::Shipped,
-                by: expected_by,
-            },
-        )?;
-        role_to_is_pdr(by)(item, msg_source)?;
+        let steps = tx_manager.asquire_abort(msg_source, action)?;
 
-        let program_id = exec::program_id();
-        let elapsed_time = tx_guard.timestamp - item.shipping_time;
-        // By default, all fungible tokens are transferred to a seller,
-        let (mut to, mut amount) = (role_to_item_pdr(expected_by)(item), item.info.price);
-
-        // but if the seller spends more time than was agreed...
-        if elapsed_time > item.info.delivery_time {
-            // ...and is extremely late (more than or exactly 2 times in this example),
-            if elapsed_time >= item.info.delivery_time * 2 {
-                // then all fungible tokens are refunded to a buyer...
-                to = msg_source;
-            } else {
-                // ...or another half is transferred to the seller...
-                amount /= 2;
+        for (transaction_id, step) in &steps {
+            utils::compensate(*transaction_id, *step).await?;
+        }
 
-                // ...and a half of tokens is refunded to the buyer.
-                utils::transfer_ftokens(
-                    tx_guard,
-                    self.fungible_token,
-                    program_id,
-                    msg_source,
-                    item.info.price - amount,
-                )
-                .await?;
+        // Items are created only after their NFTs are minted, so burned NFTs
+        // mustn't have items.
+        for (_, step) in steps {
+            if let Step::MintNFT { token_id, .. } = step {
+                self.items.remove(&token_id);
             }
         }
 
-        utils::transfer_ftokens(tx_guard, self.fungible_token, program_id, to, amount).await?;
-        utils::transfer_nft(tx_guard, self.non_fungible_token, msg_source, item_id).await?;
+        tx_manager.remove_transaction(msg_source, action);
+        self.unlock_items(&self.locked_item_ids(action), (msg_source, action));
 
-        Ok(item.set_state_and_get_event(
-            item_id,
-            ItemState {
-                state: ItemEventState::Received,
-                by,
-            },
-        ))
+        Ok(Event::TransactionAborted(action))
     }
 
     fn process_or_package(
         &mut self,
+        tx_manager: &TransactionManager<CachedAction>,
         msg_source: ActorId,
         item_id: ItemId,
         expected_item_event_state: ItemEventState,
@@ -369,8 +2503,10 @@ impl Contract {
             },
         )?;
         item.is_distributor(msg_source)?;
+        item.is_not_locked(tx_manager)?;
 
         Ok(item.set_state_and_get_event(
+            msg_source,
             item_id,
             ItemState {
                 state,
@@ -413,20 +2549,42 @@ fn process_init() -> Result<(), Error> {
         retailers,
         fungible_token,
         non_fungible_token,
+        payment_mode,
+        currencies,
+        penalty_schedule,
+        resale_royalty,
+        platform_fee,
+        transaction_ttl,
+        arbiter,
     } = msg::load()?;
 
     if producers
         .iter()
         .chain(&distributors)
         .chain(&retailers)
-        .chain(&[fungible_token, non_fungible_token])
+        .chain(&currencies)
+        .chain(&[non_fungible_token, arbiter])
+        .chain((payment_mode == PaymentMode::FungibleToken).then_some(&fungible_token))
         .any(|actor| actor.is_zero())
     {
         return Err(Error::ZeroActorId);
     }
 
+    if !is_penalty_schedule_valid(&penalty_schedule) {
+        return Err(Error::InvalidPenaltySchedule);
+    }
+
+    if resale_royalty > MAX_BASIS_POINTS {
+        return Err(Error::InvalidBasisPoints);
+    }
+
+    is_platform_fee_valid(platform_fee)?;
+
     let [producers, distributors, retailers] =
         [producers, distributors, retailers].map(|actors| actors.into_iter().collect());
+    let mut tx_manager = TransactionManager::default();
+
+    tx_manager.ttl = transaction_ttl;
 
     unsafe {
         STATE = Some((
@@ -436,9 +2594,16 @@ fn process_init() -> Result<(), Error> {
                 retailers,
                 fungible_token,
                 non_fungible_token,
+                payment_mode,
+                currencies: currencies.into_iter().collect(),
+                penalty_schedule,
+                resale_royalty,
+                platform_fee,
+                admin: msg::source(),
+                arbiter,
                 ..Default::default()
             },
-            Default::default(),
+            tx_manager,
         ));
     }
 
@@ -447,7 +2612,19 @@ fn process_init() -> Result<(), Error> {
 
 #[gstd::async_main]
 async fn main() {
-    reply(process_handle().await).expect("failed to encode or reply `handle()`");
+    let result = process_handle().await;
+    let contract = &state_mut().0;
+    let value = if contract.payment_mode == PaymentMode::Value {
+        utils::settle_value(result.is_ok())
+    } else {
+        0
+    };
+
+    if let Ok(event) = &result {
+        contract.notify(event);
+    }
+
+    msg::reply(result, value).expect("failed to encode or reply `handle()`");
 }
 
 async fn process_handle() -> Result<Event, Error> {
@@ -458,6 +2635,76 @@ async fn process_handle() -> Result<Event, Error> {
 
     let msg_source = msg::source();
     let (contract, tx_manager) = state_mut();
+    let cached_action: Option<CachedAction> = action.clone().into();
+
+    if tx_kind == TransactionKind::Abort {
+        let cached_action = cached_action.ok_or(TransactionCacheError::TransactionNotFound)?;
+
+        return contract.abort(tx_manager, msg_source, cached_action).await;
+    }
+
+    if tx_kind == TransactionKind::Purge {
+        let cached_action = cached_action.ok_or(TransactionCacheError::TransactionNotFound)?;
+
+        let result = tx_manager.purge_transaction(msg_source, cached_action);
+
+        // An expired action is already removed from the cache, but its lock
+        // still has to be released.
+        contract.unlock_items(
+            &contract.locked_item_ids(cached_action),
+            (msg_source, cached_action),
+        );
+
+        result?;
+
+        return Ok(Event::TransactionPurged(cached_action));
+    }
+
+    let (action, tx_kind) = if let InnerAction::Retry(cached_action) = action {
+        (
+            tx_manager.cached_action(msg_source, cached_action)?,
+            TransactionKind::Retry,
+        )
+    } else {
+        (action, tx_kind)
+    };
+
+    let lock =
+        cached_action.map(|cached_action| (contract.locked_item_ids(cached_action), cached_action));
+
+    if let Some((item_ids, cached_action)) = &lock {
+        contract.lock_items(tx_manager, item_ids, (msg_source, *cached_action))?;
+    }
+
+    let result = process_action(contract, tx_manager, msg_source, action, tx_kind).await;
+
+    // A completed transaction can't be retried or aborted anymore.
+    if let (Ok(_), Some(cached_action)) = (&result, cached_action) {
+        tx_manager.remove_transaction(msg_source, cached_action);
+    }
+
+    // An item stays locked only by a failed transaction that has something to
+    // continue or compensate, or by the same transaction that is still being
+    // processed by another message.
+    if let Some((item_ids, cached_action)) = lock {
+        if !tx_manager.is_in_flight(msg_source, cached_action)
+            && !tx_manager.has_executed_steps(msg_source, cached_action)
+        {
+            contract.unlock_items(&item_ids, (msg_source, cached_action));
+        }
+    }
+
+    result
+}
+
+async fn process_action(
+    contract: &mut Contract,
+    tx_manager: &mut TransactionManager<CachedAction>,
+    msg_source: ActorId,
+    action: InnerAction,
+    tx_kind: TransactionKind,
+) -> Result<Event, Error> {
+    let payload = action.clone();
 
     match action {
         InnerAction::Consumer(action) => match action {
@@ -466,40 +2713,56 @@ async fn process_handle() -> Result<Event, Error> {
                     tx_kind,
                     msg_source,
                     CachedAction::Purchase(item_id),
+                    &payload,
                 )?;
 
-                let item = get_mut_item(
-                    &mut contract.items,
-                    item_id,
-                    ItemState {
-                        state: ItemEventState::ForSale,
-                        by: Role::Retailer,
-                    },
+                contract
+                    .purchase_by_consumer(&mut tx_guard, msg_source, item_id)
+                    .await
+            }
+            ConsumerAction::PutUpForSale {
+                item_id,
+                price,
+                currency,
+            } => {
+                let mut tx_guard = tx_manager.asquire_transaction(
+                    tx_kind,
+                    msg_source,
+                    CachedAction::PutUpForSale(item_id),
+                    &payload,
                 )?;
 
-                utils::transfer_ftokens(
-                    &mut tx_guard,
-                    contract.fungible_token,
-                    msg_source,
-                    item.info.retailer,
-                    item.info.price,
-                )
-                .await?;
-                utils::transfer_nft(
-                    &mut tx_guard,
-                    contract.non_fungible_token,
-                    msg_source,
-                    item_id,
-                )
-                .await?;
+                contract
+                    .put_up_for_sale(
+                        &mut tx_guard,
+                        msg_source,
+                        item_id,
+                        Role::Consumer,
+                        price,
+                        currency,
+                    )
+                    .await
+            }
+            ConsumerAction::WithdrawFromSale(item_id) => {
+                let mut tx_guard = tx_manager.asquire_transaction(
+                    tx_kind,
+                    msg_source,
+                    CachedAction::WithdrawFromSale(item_id),
+                    &payload,
+                )?;
 
-                Ok(item.set_state_and_get_event(
-                    item_id,
-                    ItemState {
-                        state: ItemEventState::Purchased,
-                        by: Role::Consumer,
-                    },
-                ))
+                contract
+                    .withdraw_from_sale(
+                        &mut tx_guard,
+                        msg_source,
+                        item_id,
+                        ItemEventState::Purchased,
+                        Role::Consumer,
+                    )
+                    .await
+            }
+            ConsumerAction::UpdatePrice { item_id, price } => {
+                contract.update_price(tx_manager, msg_source, item_id, Role::Consumer, price)
             }
         },
         InnerAction::Producer(action) => {
@@ -508,19 +2771,67 @@ async fn process_handle() -> Result<Event, Error> {
             }
 
             match action {
-                ProducerAction::Produce { token_metadata } => {
-                    let mut tx_guard =
-                        tx_manager.asquire_transaction(tx_kind, msg_source, CachedAction::Other)?;
+                ProducerAction::Produce {
+                    token_metadata,
+                    royalty,
+                } => {
+                    let mut tx_guard = tx_manager.asquire_transaction(
+                        tx_kind,
+                        msg_source,
+                        CachedAction::Other,
+                        &payload,
+                    )?;
+
+                    contract
+                        .produce(&mut tx_guard, msg_source, token_metadata, royalty)
+                        .await
+                }
+                ProducerAction::ProduceBatch { items, royalty } => {
+                    let mut tx_guard = tx_manager.asquire_transaction(
+                        tx_kind,
+                        msg_source,
+                        CachedAction::ProduceBatch,
+                        &payload,
+                    )?;
+
+                    contract
+                        .produce_batch(&mut tx_guard, msg_source, items, royalty)
+                        .await
+                }
+                ProducerAction::FundRecallPool(amount) => {
+                    let mut tx_guard = tx_manager.asquire_transaction(
+                        tx_kind,
+                        msg_source,
+                        CachedAction::FundRecallPool,
+                        &payload,
+                    )?;
+
+                    contract
+                        .fund_recall_pool(&mut tx_guard, msg_source, amount)
+                        .await
+                }
+                ProducerAction::Recall { item_ids, reason } => {
+                    let mut tx_guard = tx_manager.asquire_transaction(
+                        tx_kind,
+                        msg_source,
+                        CachedAction::Recall,
+                        &payload,
+                    )?;
 
                     contract
-                        .produce(&mut tx_guard, msg_source, token_metadata)
+                        .recall(&mut tx_guard, msg_source, item_ids, reason)
                         .await
                 }
-                ProducerAction::PutUpForSale { item_id, price } => {
+                ProducerAction::PutUpForSale {
+                    item_id,
+                    price,
+                    currency,
+                } => {
                     let mut tx_guard = tx_manager.asquire_transaction(
                         tx_kind,
                         msg_source,
                         CachedAction::PutUpForSale(item_id),
+                        &payload,
                     )?;
 
                     contract
@@ -528,17 +2839,39 @@ async fn process_handle() -> Result<Event, Error> {
                             &mut tx_guard,
                             msg_source,
                             item_id,
-                            ItemEventState::Produced,
                             Role::Producer,
                             price,
+                            currency,
+                        )
+                        .await
+                }
+                ProducerAction::WithdrawFromSale(item_id) => {
+                    let mut tx_guard = tx_manager.asquire_transaction(
+                        tx_kind,
+                        msg_source,
+                        CachedAction::WithdrawFromSale(item_id),
+                        &payload,
+                    )?;
+
+                    contract
+                        .withdraw_from_sale(
+                            &mut tx_guard,
+                            msg_source,
+                            item_id,
+                            ItemEventState::Produced,
+                            Role::Producer,
                         )
                         .await
                 }
+                ProducerAction::UpdatePrice { item_id, price } => {
+                    contract.update_price(tx_manager, msg_source, item_id, Role::Producer, price)
+                }
                 ProducerAction::Approve { item_id, approve } => {
                     let mut tx_guard = tx_manager.asquire_transaction(
                         tx_kind,
                         msg_source,
                         CachedAction::Approve(item_id),
+                        &payload,
                     )?;
 
                     contract
@@ -552,7 +2885,9 @@ async fn process_handle() -> Result<Event, Error> {
                         )
                         .await
                 }
-                ProducerAction::Ship(item_id) => contract.ship(msg_source, item_id, Role::Producer),
+                ProducerAction::Ship(item_id) => {
+                    contract.ship(tx_manager, msg_source, item_id, Role::Producer)
+                }
             }
         }
         InnerAction::Distributor(action) => {
@@ -564,21 +2899,45 @@ async fn process_handle() -> Result<Event, Error> {
                 DistributorAction::Purchase {
                     item_id,
                     delivery_time,
+                    penalty_schedule,
+                    approval_time,
                 } => {
                     let mut tx_guard = tx_manager.asquire_transaction(
                         tx_kind,
                         msg_source,
                         CachedAction::Purchase(item_id),
+                        &payload,
                     )?;
 
                     contract
                         .purchase(
+                            &mut tx_guard,
+                            msg_source,
+                            item_id,
+                            Role::Distributor,
+                            PurchaseTerms {
+                                delivery_time,
+                                penalty_schedule,
+                                approval_time,
+                            },
+                        )
+                        .await
+                }
+                DistributorAction::CancelPurchase(item_id) => {
+                    let mut tx_guard = tx_manager.asquire_transaction(
+                        tx_kind,
+                        msg_source,
+                        CachedAction::CancelPurchase(item_id),
+                        &payload,
+                    )?;
+
+                    contract
+                        .cancel_purchase(
                             &mut tx_guard,
                             msg_source,
                             item_id,
                             Role::Producer,
                             Role::Distributor,
-                            delivery_time,
                         )
                         .await
                 }
@@ -587,6 +2946,7 @@ async fn process_handle() -> Result<Event, Error> {
                         tx_kind,
                         msg_source,
                         CachedAction::Receive(item_id),
+                        &payload,
                     )?;
 
                     contract
@@ -599,23 +2959,33 @@ async fn process_handle() -> Result<Event, Error> {
                         )
                         .await
                 }
+                DistributorAction::Dispute { item_id, reason } => {
+                    contract.dispute(tx_manager, msg_source, item_id, Role::Distributor, reason)
+                }
                 DistributorAction::Process(item_id) => contract.process_or_package(
+                    tx_manager,
                     msg_source,
                     item_id,
                     ItemEventState::Received,
                     ItemEventState::Processed,
                 ),
                 DistributorAction::Package(item_id) => contract.process_or_package(
+                    tx_manager,
                     msg_source,
                     item_id,
                     ItemEventState::Processed,
                     ItemEventState::Packaged,
                 ),
-                DistributorAction::PutUpForSale { item_id, price } => {
+                DistributorAction::PutUpForSale {
+                    item_id,
+                    price,
+                    currency,
+                } => {
                     let mut tx_guard = tx_manager.asquire_transaction(
                         tx_kind,
                         msg_source,
                         CachedAction::PutUpForSale(item_id),
+                        &payload,
                     )?;
 
                     contract
@@ -623,17 +2993,39 @@ async fn process_handle() -> Result<Event, Error> {
                             &mut tx_guard,
                             msg_source,
                             item_id,
-                            ItemEventState::Packaged,
                             Role::Distributor,
                             price,
+                            currency,
+                        )
+                        .await
+                }
+                DistributorAction::WithdrawFromSale(item_id) => {
+                    let mut tx_guard = tx_manager.asquire_transaction(
+                        tx_kind,
+                        msg_source,
+                        CachedAction::WithdrawFromSale(item_id),
+                        &payload,
+                    )?;
+
+                    contract
+                        .withdraw_from_sale(
+                            &mut tx_guard,
+                            msg_source,
+                            item_id,
+                            ItemEventState::Packaged,
+                            Role::Distributor,
                         )
                         .await
                 }
+                DistributorAction::UpdatePrice { item_id, price } => {
+                    contract.update_price(tx_manager, msg_source, item_id, Role::Distributor, price)
+                }
                 DistributorAction::Approve { item_id, approve } => {
                     let mut tx_guard = tx_manager.asquire_transaction(
                         tx_kind,
                         msg_source,
                         CachedAction::Approve(item_id),
+                        &payload,
                     )?;
 
                     contract
@@ -648,7 +3040,7 @@ async fn process_handle() -> Result<Event, Error> {
                         .await
                 }
                 DistributorAction::Ship(item_id) => {
-                    contract.ship(msg_source, item_id, Role::Distributor)
+                    contract.ship(tx_manager, msg_source, item_id, Role::Distributor)
                 }
             }
         }
@@ -661,21 +3053,45 @@ async fn process_handle() -> Result<Event, Error> {
                 RetailerAction::Purchase {
                     item_id,
                     delivery_time,
+                    penalty_schedule,
+                    approval_time,
                 } => {
                     let mut tx_guard = tx_manager.asquire_transaction(
                         tx_kind,
                         msg_source,
                         CachedAction::Purchase(item_id),
+                        &payload,
                     )?;
 
                     contract
                         .purchase(
+                            &mut tx_guard,
+                            msg_source,
+                            item_id,
+                            Role::Retailer,
+                            PurchaseTerms {
+                                delivery_time,
+                                penalty_schedule,
+                                approval_time,
+                            },
+                        )
+                        .await
+                }
+                RetailerAction::CancelPurchase(item_id) => {
+                    let mut tx_guard = tx_manager.asquire_transaction(
+                        tx_kind,
+                        msg_source,
+                        CachedAction::CancelPurchase(item_id),
+                        &payload,
+                    )?;
+
+                    contract
+                        .cancel_purchase(
                             &mut tx_guard,
                             msg_source,
                             item_id,
                             Role::Distributor,
                             Role::Retailer,
-                            delivery_time,
                         )
                         .await
                 }
@@ -684,6 +3100,7 @@ async fn process_handle() -> Result<Event, Error> {
                         tx_kind,
                         msg_source,
                         CachedAction::Receive(item_id),
+                        &payload,
                     )?;
 
                     contract
@@ -696,11 +3113,19 @@ async fn process_handle() -> Result<Event, Error> {
                         )
                         .await
                 }
-                RetailerAction::PutUpForSale { item_id, price } => {
+                RetailerAction::Dispute { item_id, reason } => {
+                    contract.dispute(tx_manager, msg_source, item_id, Role::Retailer, reason)
+                }
+                RetailerAction::PutUpForSale {
+                    item_id,
+                    price,
+                    currency,
+                } => {
                     let mut tx_guard = tx_manager.asquire_transaction(
                         tx_kind,
                         msg_source,
                         CachedAction::PutUpForSale(item_id),
+                        &payload,
                     )?;
 
                     contract
@@ -708,13 +3133,318 @@ async fn process_handle() -> Result<Event, Error> {
                             &mut tx_guard,
                             msg_source,
                             item_id,
-                            ItemEventState::Received,
                             Role::Retailer,
                             price,
+                            currency,
+                        )
+                        .await
+                }
+                RetailerAction::WithdrawFromSale(item_id) => {
+                    let mut tx_guard = tx_manager.asquire_transaction(
+                        tx_kind,
+                        msg_source,
+                        CachedAction::WithdrawFromSale(item_id),
+                        &payload,
+                    )?;
+
+                    contract
+                        .withdraw_from_sale(
+                            &mut tx_guard,
+                            msg_source,
+                            item_id,
+                            ItemEventState::Received,
+                            Role::Retailer,
                         )
                         .await
                 }
+                RetailerAction::UpdatePrice { item_id, price } => {
+                    contract.update_price(tx_manager, msg_source, item_id, Role::Retailer, price)
+                }
+            }
+        }
+        InnerAction::Admin(action) => {
+            if contract.admin != msg_source {
+                return Err(Error::AccessRestricted);
+            }
+
+            match action {
+                AdminAction::AddParticipant { role, participant } => {
+                    contract.add_participant(role, participant)
+                }
+                AdminAction::RemoveParticipant { role, participant } => {
+                    contract.remove_participant(role, participant)
+                }
+                AdminAction::ChangeAdmin(admin) => contract.change_admin(admin),
+                AdminAction::SetPlatformFee(platform_fee) => {
+                    contract.set_platform_fee(platform_fee)
+                }
+                AdminAction::SetTransactionTtl(transaction_ttl) => {
+                    contract.set_transaction_ttl(tx_manager, transaction_ttl)
+                }
+                AdminAction::PurgeTransaction { actor, action } => {
+                    contract.purge_transaction(tx_manager, actor, action)
+                }
+            }
+        }
+        InnerAction::Arbiter(action) => {
+            if contract.arbiter != msg_source {
+                return Err(Error::AccessRestricted);
+            }
+
+            match action {
+                ArbiterAction::Resolve {
+                    item_id,
+                    seller_share,
+                } => {
+                    let mut tx_guard = tx_manager.asquire_transaction(
+                        tx_kind,
+                        msg_source,
+                        CachedAction::Resolve(item_id),
+                        &payload,
+                    )?;
+
+                    contract
+                        .resolve(&mut tx_guard, msg_source, item_id, seller_share)
+                        .await
+                }
+            }
+        }
+        InnerAction::Deadline(action) => match action {
+            DeadlineAction::FailDelivery(item_id) => {
+                let mut tx_guard = tx_manager.asquire_transaction_with_timestamp(
+                    tx_kind,
+                    msg_source,
+                    CachedAction::FailDelivery(item_id),
+                    &payload,
+                )?;
+
+                contract
+                    .fail_delivery(&mut tx_guard, msg_source, item_id)
+                    .await
+            }
+            DeadlineAction::SettleAuction(item_id) => {
+                let mut tx_guard = tx_manager.asquire_transaction_with_timestamp(
+                    tx_kind,
+                    msg_source,
+                    CachedAction::Auction(item_id),
+                    &payload,
+                )?;
+
+                contract
+                    .settle_auction(&mut tx_guard, msg_source, item_id)
+                    .await
+            }
+            DeadlineAction::ExpirePurchase(item_id) => {
+                let mut tx_guard = tx_manager.asquire_transaction_with_timestamp(
+                    tx_kind,
+                    msg_source,
+                    CachedAction::ExpirePurchase(item_id),
+                    &payload,
+                )?;
+
+                contract
+                    .expire_purchase(&mut tx_guard, msg_source, item_id)
+                    .await
+            }
+            DeadlineAction::FailLotDelivery(lot_id) => {
+                let mut tx_guard = tx_manager.asquire_transaction_with_timestamp(
+                    tx_kind,
+                    msg_source,
+                    CachedAction::Lot(lot_id),
+                    &payload,
+                )?;
+
+                contract
+                    .fail_lot_delivery(&mut tx_guard, msg_source, lot_id)
+                    .await
+            }
+        },
+        InnerAction::Lot(action) => match action {
+            LotAction::Create(item_ids) => contract.create_lot(tx_manager, msg_source, item_ids),
+            LotAction::Split(lot_id) => contract.split_lot(tx_manager, msg_source, lot_id),
+            LotAction::PutUpForSale {
+                lot_id,
+                price,
+                currency,
+            } => {
+                let mut tx_guard = tx_manager.asquire_transaction(
+                    tx_kind,
+                    msg_source,
+                    CachedAction::Lot(lot_id),
+                    &payload,
+                )?;
+
+                contract
+                    .put_up_lot_for_sale(&mut tx_guard, msg_source, lot_id, price, currency)
+                    .await
+            }
+            LotAction::WithdrawFromSale(lot_id) => {
+                let mut tx_guard = tx_manager.asquire_transaction(
+                    tx_kind,
+                    msg_source,
+                    CachedAction::Lot(lot_id),
+                    &payload,
+                )?;
+
+                contract
+                    .withdraw_lot_from_sale(&mut tx_guard, msg_source, lot_id)
+                    .await
+            }
+            LotAction::Purchase {
+                lot_id,
+                delivery_time,
+                penalty_schedule,
+            } => {
+                let mut tx_guard = tx_manager.asquire_transaction(
+                    tx_kind,
+                    msg_source,
+                    CachedAction::Lot(lot_id),
+                    &payload,
+                )?;
+
+                contract
+                    .purchase_lot(
+                        &mut tx_guard,
+                        msg_source,
+                        lot_id,
+                        delivery_time,
+                        penalty_schedule,
+                    )
+                    .await
+            }
+            LotAction::Approve { lot_id, approve } => {
+                let mut tx_guard = tx_manager.asquire_transaction(
+                    tx_kind,
+                    msg_source,
+                    CachedAction::Lot(lot_id),
+                    &payload,
+                )?;
+
+                contract
+                    .approve_lot(&mut tx_guard, msg_source, lot_id, approve)
+                    .await
+            }
+            LotAction::Ship(lot_id) => contract.ship_lot(tx_manager, msg_source, lot_id),
+            LotAction::Receive(lot_id) => {
+                let mut tx_guard = tx_manager.asquire_transaction_with_timestamp(
+                    tx_kind,
+                    msg_source,
+                    CachedAction::Lot(lot_id),
+                    &payload,
+                )?;
+
+                contract
+                    .receive_lot(&mut tx_guard, msg_source, lot_id)
+                    .await
+            }
+        },
+        InnerAction::Offer(action) => match action {
+            OfferAction::Make {
+                item_id,
+                price,
+                delivery_time,
+            } => {
+                let mut tx_guard = tx_manager.asquire_transaction(
+                    tx_kind,
+                    msg_source,
+                    CachedAction::Offer(item_id),
+                    &payload,
+                )?;
+
+                contract
+                    .make_offer(&mut tx_guard, msg_source, item_id, price, delivery_time)
+                    .await
+            }
+            OfferAction::Withdraw(item_id) => {
+                let mut tx_guard = tx_manager.asquire_transaction(
+                    tx_kind,
+                    msg_source,
+                    CachedAction::Offer(item_id),
+                    &payload,
+                )?;
+
+                contract
+                    .withdraw_offer(&mut tx_guard, msg_source, item_id)
+                    .await
+            }
+            OfferAction::Accept { item_id, buyer } => {
+                let mut tx_guard = tx_manager.asquire_transaction(
+                    tx_kind,
+                    msg_source,
+                    CachedAction::Offer(item_id),
+                    &payload,
+                )?;
+
+                contract
+                    .accept_offer(&mut tx_guard, msg_source, item_id, buyer)
+                    .await
+            }
+            OfferAction::Counter {
+                item_id,
+                buyer,
+                price,
+            } => contract.counter_offer(tx_manager, msg_source, item_id, buyer, price),
+            OfferAction::AcceptCounter(item_id) => {
+                let mut tx_guard = tx_manager.asquire_transaction(
+                    tx_kind,
+                    msg_source,
+                    CachedAction::Offer(item_id),
+                    &payload,
+                )?;
+
+                contract
+                    .accept_counter_offer(&mut tx_guard, msg_source, item_id)
+                    .await
+            }
+        },
+        InnerAction::Auction(action) => match action {
+            AuctionAction::Start {
+                item_id,
+                reserve_price,
+                duration,
+                currency,
+            } => {
+                let mut tx_guard = tx_manager.asquire_transaction(
+                    tx_kind,
+                    msg_source,
+                    CachedAction::Auction(item_id),
+                    &payload,
+                )?;
+
+                contract
+                    .start_auction(
+                        &mut tx_guard,
+                        msg_source,
+                        item_id,
+                        reserve_price,
+                        duration,
+                        currency,
+                    )
+                    .await
+            }
+            AuctionAction::Bid {
+                item_id,
+                price,
+                delivery_time,
+            } => {
+                let mut tx_guard = tx_manager.asquire_transaction_with_timestamp(
+                    tx_kind,
+                    msg_source,
+                    CachedAction::Auction(item_id),
+                    &payload,
+                )?;
+
+                contract
+                    .bid(&mut tx_guard, msg_source, item_id, price, delivery_time)
+                    .await
             }
+        },
+        InnerAction::Subscriber(action) => match action {
+            SubscriberAction::Subscribe(filter) => contract.subscribe(msg_source, filter),
+            SubscriberAction::Unsubscribe => contract.unsubscribe(msg_source),
+        },
+        InnerAction::Retry(_) => {
+            unreachable!("retries must be replaced with cached actions by `process_handle()`")
         }
     }
 }
@@ -729,6 +3459,20 @@ extern "C" fn state() {
             retailers,
             fungible_token,
             non_fungible_token,
+            payment_mode,
+            currencies,
+            penalty_schedule,
+            resale_royalty,
+            platform_fee,
+            collected_fees,
+            admin,
+            arbiter,
+            subscribers,
+            lots,
+            recall_pools,
+            offers,
+            auctions,
+            ..
         },
         tx_manager,
     ) = state_mut();
@@ -736,8 +3480,42 @@ extern "C" fn state() {
     let [producers, distributors, retailers] =
         [producers, distributors, retailers].map(|actors| actors.iter().cloned().collect());
 
+    let mut items: Vec<_> = items.iter().collect();
+    let mut lots: Vec<_> = lots.iter().map(|lot| (*lot.0, lot.1.clone())).collect();
+    let mut offers: Vec<_> = offers
+        .iter()
+        .map(|(item_id, offers)| {
+            let mut offers: Vec<_> = offers
+                .iter()
+                .map(|(buyer, offer)| (*buyer, *offer))
+                .collect();
+
+            offers.sort_unstable_by_key(|offer| offer.0);
+
+            (*item_id, offers)
+        })
+        .collect();
+    let mut auctions: Vec<_> = auctions
+        .iter()
+        .map(|(item_id, auction)| (*item_id, *auction))
+        .collect();
+    let mut cached_actions: Vec<_> = tx_manager.cached_actions().copied().collect();
+
+    items.sort_unstable_by_key(|item| item.0);
+    lots.sort_unstable_by_key(|lot| lot.0);
+    offers.sort_unstable_by_key(|offers| offers.0);
+    auctions.sort_unstable_by_key(|auction| auction.0);
+    cached_actions.sort_unstable();
+
     reply(State {
-        items: items.iter().map(|item| (*item.0, item.1.info)).collect(),
+        items: items
+            .iter()
+            .map(|item| (*item.0, item.1.info.clone()))
+            .collect(),
+        item_histories: items
+            .iter()
+            .map(|item| (*item.0, item.1.history.clone()))
+            .collect(),
 
         producers,
         distributors,
@@ -745,11 +3523,40 @@ extern "C" fn state() {
 
         fungible_token: *fungible_token,
         non_fungible_token: *non_fungible_token,
+        payment_mode: *payment_mode,
+        currencies: currencies.iter().cloned().collect(),
+
+        penalty_schedule: penalty_schedule.clone(),
+        resale_royalty: *resale_royalty,
+
+        platform_fee: *platform_fee,
+        collected_fees: collected_fees
+            .iter()
+            .map(|(currency, amount)| (*currency, *amount))
+            .collect(),
 
-        cached_actions: tx_manager
-            .cached_actions()
-            .map(|(actor, action)| (*actor, *action))
+        transaction_ttl: tx_manager.ttl,
+
+        admin: *admin,
+        arbiter: *arbiter,
+
+        subscribers: subscribers
+            .iter()
+            .map(|(subscriber, filter)| (*subscriber, *filter))
+            .collect(),
+
+        lots,
+
+        recall_pools: recall_pools
+            .iter()
+            .map(|(producer, balance)| (*producer, *balance))
             .collect(),
+
+        offers,
+
+        auctions,
+
+        cached_actions,
     })
     .expect("failed to encode or reply from `state()`");
 }
@@ -1,14 +1,56 @@
+use core::hash::Hash;
+use gear_lib::non_fungible_token::token::TokenId;
 use gstd::{exec, prelude::*, ActorId};
 use hashbrown::HashMap;
 use supply_chain_io::*;
 
 const MAX_NUMBER_OF_TXS: usize = 2usize.pow(16);
 
+/// An executed step of a transaction that can be compensated by
+/// [`TransactionKind::Abort`].
+#[derive(Clone, Copy)]
+pub enum Step {
+    TransferFTokens {
+        fungible_token: ActorId,
+        sender: ActorId,
+        recipient: ActorId,
+        amount: u128,
+    },
+    TransferNFT {
+        non_fungible_token: ActorId,
+        from: ActorId,
+        token_id: TokenId,
+    },
+    MintNFT {
+        non_fungible_token: ActorId,
+        token_id: TokenId,
+    },
+}
+
+struct Transaction {
+    id: u64,
+    timestamp: u64,
+    created_at: u64,
+    /// An action that a transaction was created for. A retry must have the
+    /// same one.
+    action: InnerAction,
+    /// Executed steps by their transaction IDs.
+    steps: BTreeMap<u64, Step>,
+    /// The first transaction ID of compensating steps if the transaction is
+    /// being aborted.
+    abort_id: Option<u64>,
+    /// The number of messages that are processing the transaction. Stays
+    /// positive after a message runs out of gas while awaiting a reply.
+    in_flight: u32,
+}
+
 pub struct TransactionManager<T> {
-    txs_for_actor: BTreeMap<u64, ActorId>,
-    actors_for_tx: HashMap<ActorId, (u64, T, u64)>,
+    txs_for_actor: BTreeMap<u64, (ActorId, T)>,
+    actors_for_tx: HashMap<(ActorId, T), Transaction>,
 
     tx_id_nonce: u64,
+    /// Milliseconds after which a cached transaction expires, if any.
+    pub ttl: Option<u64>,
 }
 
 impl<T> Default for TransactionManager<T> {
@@ -18,26 +60,88 @@ impl<T> Default for TransactionManager<T> {
             actors_for_tx: Default::default(),
 
             tx_id_nonce: Default::default(),
+            ttl: Default::default(),
         }
     }
 }
 
-impl<T: PartialEq + Clone> TransactionManager<T> {
+fn next_tx_id(tx_id_nonce: &mut u64) -> u64 {
+    let id = *tx_id_nonce;
+
+    *tx_id_nonce = id.wrapping_add(u8::MAX as _);
+
+    id
+}
+
+impl<T> TransactionManager<T> {
+    fn is_expired(&self, tx: &Transaction, now: u64) -> bool {
+        // A transaction that is being aborted doesn't expire, otherwise its
+        // executed steps would never be compensated.
+        tx.abort_id.is_none()
+            && self
+                .ttl
+                .map_or(false, |ttl| now.saturating_sub(tx.created_at) >= ttl)
+    }
+}
+
+impl<T: Eq + Hash + Clone> TransactionManager<T> {
+    /// Removes a cached transaction with given `key` if it's expired.
+    fn remove_if_expired(&mut self, key: &(ActorId, T)) {
+        if self
+            .actors_for_tx
+            .get(key)
+            .map_or(false, |tx| self.is_expired(tx, exec::block_timestamp()))
+        {
+            self.remove_transaction(key.0, key.1.clone());
+        }
+    }
+
     fn inner_asquire_transaction(
         &mut self,
         kind: TransactionKind,
         msg_source: ActorId,
         check_action: T,
+        action: &InnerAction,
         timestamp: u64,
     ) -> Result<TransactionGuard<T>, TransactionCacheError> {
+        let key = (msg_source, check_action);
+
+        self.remove_if_expired(&key);
+
+        if self
+            .actors_for_tx
+            .get(&key)
+            .map_or(false, |tx| tx.abort_id.is_some())
+        {
+            return Err(TransactionCacheError::AbortInProgress);
+        }
+
         let (tx_id, timestamp) = match kind {
             TransactionKind::New => {
-                let id = self.tx_id_nonce;
+                // A new transaction with new transaction IDs would repeat steps
+                // that a processing one may have already executed.
+                if self
+                    .actors_for_tx
+                    .get(&key)
+                    .map_or(false, |tx| tx.in_flight != 0)
+                {
+                    return Err(TransactionCacheError::TransactionInProgress);
+                }
 
-                self.tx_id_nonce = id.wrapping_add(u8::MAX as _);
+                // Replacing a transaction with executed steps would lose them,
+                // so they could never be continued or compensated.
+                if self
+                    .actors_for_tx
+                    .get(&key)
+                    .map_or(false, |tx| !tx.steps.is_empty())
+                {
+                    return Err(TransactionCacheError::UnfinishedTransaction);
+                }
+
+                let id = next_tx_id(&mut self.tx_id_nonce);
 
                 if self.txs_for_actor.len() == MAX_NUMBER_OF_TXS {
-                    let (tx, actor) = self
+                    let (tx, key) = self
                         .txs_for_actor
                         .range(self.tx_id_nonce..)
                         .next()
@@ -48,35 +152,60 @@ impl<T: PartialEq + Clone> TransactionManager<T> {
 
                             unsafe { key_value.unwrap_unchecked() }
                         });
-                    let (tx, actor) = (*tx, *actor);
+                    let (tx, key) = (*tx, key.clone());
 
                     self.txs_for_actor.remove(&tx);
-                    self.actors_for_tx.remove(&actor);
+                    self.actors_for_tx.remove(&key);
+                }
+
+                // A new transaction replaces a cached one with the same action
+                // that has no executed steps.
+                if let Some(old_tx) = self.actors_for_tx.insert(
+                    key.clone(),
+                    Transaction {
+                        id,
+                        timestamp,
+                        created_at: exec::block_timestamp(),
+                        action: action.clone(),
+                        steps: Default::default(),
+                        abort_id: None,
+                        in_flight: 0,
+                    },
+                ) {
+                    self.txs_for_actor.remove(&old_tx.id);
                 }
 
-                self.txs_for_actor.insert(id, msg_source);
-                self.actors_for_tx
-                    .insert(msg_source, (id, check_action, timestamp));
+                self.txs_for_actor.insert(id, key.clone());
 
                 (id, timestamp)
             }
             TransactionKind::Retry => {
-                let (id, true_checked_action, timestamp) = self
+                let tx = self
                     .actors_for_tx
-                    .get(&msg_source)
+                    .get(&key)
                     .ok_or(TransactionCacheError::TransactionNotFound)?;
 
-                if check_action.ne(true_checked_action) {
+                if tx.action != *action {
                     return Err(TransactionCacheError::MismatchedAction);
                 }
 
-                (*id, *timestamp)
+                (tx.id, tx.timestamp)
             }
+            TransactionKind::Abort => {
+                unreachable!("aborts must be processed by `TransactionManager::asquire_abort()`")
+            }
+            TransactionKind::Purge => unreachable!(
+                "purges must be processed by `TransactionManager::purge_transaction()`"
+            ),
         };
 
+        if let Some(tx) = self.actors_for_tx.get_mut(&key) {
+            tx.in_flight = tx.in_flight.saturating_add(1);
+        }
+
         Ok(TransactionGuard {
-            _manager: self,
-            _msg_source: msg_source,
+            manager: self,
+            key,
             tx_id,
 
             step: 0,
@@ -90,8 +219,9 @@ impl<T: PartialEq + Clone> TransactionManager<T> {
         kind: TransactionKind,
         msg_source: ActorId,
         check_action: T,
+        action: &InnerAction,
     ) -> Result<TransactionGuard<T>, TransactionCacheError> {
-        Self::inner_asquire_transaction(self, kind, msg_source, check_action, 0)
+        Self::inner_asquire_transaction(self, kind, msg_source, check_action, action, 0)
     }
 
     pub fn asquire_transaction_with_timestamp(
@@ -99,26 +229,138 @@ impl<T: PartialEq + Clone> TransactionManager<T> {
         kind: TransactionKind,
         msg_source: ActorId,
         check_action: T,
+        action: &InnerAction,
     ) -> Result<TransactionGuard<T>, TransactionCacheError> {
         Self::inner_asquire_transaction(
             self,
             kind,
             msg_source,
             check_action,
+            action,
             exec::block_timestamp(),
         )
     }
 
-    pub fn cached_actions(&self) -> impl Iterator<Item = (&ActorId, &T)> {
+    /// Starts or continues an abort of a cached transaction.
+    ///
+    /// Returns executed steps of the transaction in the reverse order, each
+    /// with a transaction ID for its compensating step. These IDs stay the same
+    /// on every call, so compensating steps are idempotent.
+    pub fn asquire_abort(
+        &mut self,
+        msg_source: ActorId,
+        check_action: T,
+    ) -> Result<Vec<(u64, Step)>, TransactionCacheError> {
+        let key = (msg_source, check_action);
+
+        self.remove_if_expired(&key);
+
+        let tx = self
+            .actors_for_tx
+            .get_mut(&key)
+            .ok_or(TransactionCacheError::TransactionNotFound)?;
+
+        // Compensating steps of a processing transaction would race its
+        // remaining ones.
+        if tx.in_flight != 0 {
+            return Err(TransactionCacheError::TransactionInProgress);
+        }
+
+        let abort_id = *tx
+            .abort_id
+            .get_or_insert_with(|| next_tx_id(&mut self.tx_id_nonce));
+
+        Ok(tx
+            .steps
+            .values()
+            .rev()
+            .zip(abort_id..)
+            .map(|(step, tx_id)| (tx_id, *step))
+            .collect())
+    }
+
+    /// Removes a cached transaction after its completion or abort.
+    ///
+    /// Returns [`false`] if there's no such transaction.
+    pub fn remove_transaction(&mut self, msg_source: ActorId, check_action: T) -> bool {
+        if let Some(tx) = self.actors_for_tx.remove(&(msg_source, check_action)) {
+            self.txs_for_actor.remove(&tx.id);
+
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Removes a cached transaction on [`TransactionKind::Purge`].
+    pub fn purge_transaction(
+        &mut self,
+        msg_source: ActorId,
+        check_action: T,
+    ) -> Result<(), TransactionCacheError> {
+        let key = (msg_source, check_action);
+
+        self.remove_if_expired(&key);
+
+        if self.remove_transaction(key.0, key.1) {
+            Ok(())
+        } else {
+            Err(TransactionCacheError::TransactionNotFound)
+        }
+    }
+
+    /// Returns an action of a cached transaction for
+    /// [`InnerAction::Retry`].
+    pub fn cached_action(
+        &mut self,
+        msg_source: ActorId,
+        check_action: T,
+    ) -> Result<InnerAction, TransactionCacheError> {
+        let key = (msg_source, check_action);
+
+        self.remove_if_expired(&key);
+
+        self.actors_for_tx
+            .get(&key)
+            .map(|tx| tx.action.clone())
+            .ok_or(TransactionCacheError::TransactionNotFound)
+    }
+
+    /// Checks if there's a cached transaction that isn't expired.
+    pub fn is_cached(&self, msg_source: ActorId, check_action: T) -> bool {
+        self.actors_for_tx
+            .get(&(msg_source, check_action))
+            .map_or(false, |tx| !self.is_expired(tx, exec::block_timestamp()))
+    }
+
+    /// Checks if a cached transaction is being processed.
+    pub fn is_in_flight(&self, msg_source: ActorId, check_action: T) -> bool {
+        self.actors_for_tx
+            .get(&(msg_source, check_action))
+            .map_or(false, |tx| tx.in_flight != 0)
+    }
+
+    /// Checks if a cached transaction has executed steps.
+    pub fn has_executed_steps(&self, msg_source: ActorId, check_action: T) -> bool {
+        self.actors_for_tx
+            .get(&(msg_source, check_action))
+            .map_or(false, |tx| !tx.steps.is_empty())
+    }
+
+    /// Returns actions of cached transactions that aren't expired.
+    pub fn cached_actions(&self) -> impl Iterator<Item = &(ActorId, T)> {
+        let now = exec::block_timestamp();
+
         self.actors_for_tx
             .iter()
-            .map(|(actor, (_, action, _))| (actor, action))
+            .filter(move |(_, tx)| !self.is_expired(tx, now))
+            .map(|(key, _)| key)
     }
 }
 
-pub struct TransactionGuard<'a, T> {
-    _manager: &'a mut TransactionManager<T>,
-    _msg_source: ActorId,
+pub struct TransactionGuard<'a, T: Eq + Hash> {
+    manager: &'a mut TransactionManager<T>,
+    key: (ActorId, T),
     tx_id: u64,
 
     step: u8,
@@ -126,7 +368,7 @@ pub struct TransactionGuard<'a, T> {
     pub timestamp: u64,
 }
 
-impl<T> TransactionGuard<'_, T> {
+impl<T: Eq + Hash> TransactionGuard<'_, T> {
     pub fn step(&mut self) -> Result<u64, TransactionCacheError> {
         let step = self.tx_id + self.step as u64;
 
@@ -138,4 +380,25 @@ impl<T> TransactionGuard<'_, T> {
             Err(TransactionCacheError::StepOverflow)
         }
     }
+
+    pub fn manager(&self) -> &TransactionManager<T> {
+        self.manager
+    }
+
+    /// Records an executed `step` with given `tx_id` (acquired by
+    /// [`step()`](Self::step)) for its compensation on an abort.
+    pub fn record(&mut self, tx_id: u64, step: Step) {
+        // The transaction may be already evicted from the cache.
+        if let Some(tx) = self.manager.actors_for_tx.get_mut(&self.key) {
+            tx.steps.insert(tx_id, step);
+        }
+    }
+}
+
+impl<T: Eq + Hash> Drop for TransactionGuard<'_, T> {
+    fn drop(&mut self) {
+        if let Some(tx) = self.manager.actors_for_tx.get_mut(&self.key) {
+            tx.in_flight = tx.in_flight.saturating_sub(1);
+        }
+    }
 }
@@ -1,4 +1,5 @@
-use super::tx_manager::TransactionGuard;
+use super::tx_manager::{Step, TransactionGuard};
+use core::hash::Hash;
 use ft_main_io::{FTokenAction, FTokenEvent, LogicAction};
 use gear_lib::non_fungible_token::{
     io::NFTTransfer,
@@ -6,13 +7,29 @@ use gear_lib::non_fungible_token::{
 };
 use gstd::{
     errors::Result as GstdResult,
+    exec,
     msg::{self, CodecMessageFuture},
     prelude::*,
-    ActorId,
+    ActorId, MessageId,
 };
 use nft_io::{NFTAction, NFTEvent};
 use supply_chain_io::*;
 
+#[derive(Default)]
+struct ValueTransfers {
+    is_value_received: bool,
+    transfers: Vec<(ActorId, u128)>,
+}
+
+/// Value transfers of actions that are being processed. An action can be
+/// processed in several executions if it awaits replies, so transfers are
+/// stored by [`msg::id()`] of the action.
+static mut VALUE_TRANSFERS: BTreeMap<MessageId, ValueTransfers> = BTreeMap::new();
+
+fn value_transfers() -> &'static mut ValueTransfers {
+    unsafe { VALUE_TRANSFERS.entry(msg::id()).or_default() }
+}
+
 fn send<T: Decode>(actor: ActorId, payload: impl Encode) -> GstdResult<CodecMessageFuture<T>> {
     msg::send_for_reply_as(actor, payload, 0, 0)
 }
@@ -25,16 +42,17 @@ fn nft_event_to_transfer(event: GstdResult<NFTEvent>) -> Result<NFTTransfer, Err
     }
 }
 
-pub async fn mint_nft<T>(
+pub async fn mint_nft<T: Eq + Hash>(
     tx_guard: &mut TransactionGuard<'_, T>,
     non_fungible_token: ActorId,
     token_metadata: TokenMetadata,
 ) -> Result<TokenId, Error> {
+    let transaction_id = tx_guard.step()?;
     let transfer = nft_event_to_transfer(
         send(
             non_fungible_token,
             NFTAction::Mint {
-                transaction_id: tx_guard.step()?,
+                transaction_id,
                 token_metadata,
             },
         )?
@@ -48,39 +66,136 @@ pub async fn mint_nft<T>(
         }
     })?;
 
+    tx_guard.record(
+        transaction_id,
+        Step::MintNFT {
+            non_fungible_token,
+            token_id: transfer.token_id,
+        },
+    );
+
     Ok(transfer.token_id)
 }
 
-pub async fn transfer_nft<T>(
-    tx_guard: &mut TransactionGuard<'_, T>,
+async fn send_nft(
+    transaction_id: u64,
     non_fungible_token: ActorId,
     to: ActorId,
     token_id: TokenId,
-) -> Result<(), Error> {
+) -> Result<NFTTransfer, Error> {
     nft_event_to_transfer(
         send(
             non_fungible_token,
             NFTAction::Transfer {
-                transaction_id: tx_guard.step()?,
+                transaction_id,
                 to,
                 token_id,
             },
         )?
         .await,
-    )?;
+    )
+}
+
+pub async fn transfer_nft<T: Eq + Hash>(
+    tx_guard: &mut TransactionGuard<'_, T>,
+    non_fungible_token: ActorId,
+    to: ActorId,
+    token_id: TokenId,
+) -> Result<(), Error> {
+    let transaction_id = tx_guard.step()?;
+    let transfer = send_nft(transaction_id, non_fungible_token, to, token_id).await?;
+
+    tx_guard.record(
+        transaction_id,
+        Step::TransferNFT {
+            non_fungible_token,
+            from: transfer.from,
+            token_id,
+        },
+    );
+
+    Ok(())
+}
+
+/// Checks that [`msg::value()`] equals `amount` if `payment_mode` is
+/// [`PaymentMode::Value`].
+pub fn receive_value(payment_mode: PaymentMode, amount: u128) -> Result<(), Error> {
+    if payment_mode == PaymentMode::Value {
+        if msg::value() != amount {
+            return Err(Error::UnexpectedValue);
+        }
+
+        value_transfers().is_value_received = true;
+    }
 
     Ok(())
 }
 
-pub async fn transfer_ftokens<T>(
+/// Transfers `amount` from `sender` to `recipient` with fungible tokens or
+/// value depending on `payment_mode`.
+///
+/// Value from [`msg::source()`] must be received by [`receive_value()`]
+/// beforehand, and value to a recipient is sent by [`settle_value()`] only
+/// after an action is processed.
+pub async fn transfer_funds<T: Eq + Hash>(
     tx_guard: &mut TransactionGuard<'_, T>,
+    payment_mode: PaymentMode,
+    fungible_token: ActorId,
+    sender: ActorId,
+    recipient: ActorId,
+    amount: u128,
+) -> Result<(), Error> {
+    match payment_mode {
+        PaymentMode::FungibleToken => {
+            transfer_ftokens(tx_guard, fungible_token, sender, recipient, amount).await
+        }
+        PaymentMode::Value => {
+            // No step is recorded because value is sent only after an action
+            // is processed and is refunded otherwise, so there's nothing for
+            // `TransactionKind::Abort` to compensate.
+            if recipient != exec::program_id() && amount != 0 {
+                value_transfers().transfers.push((recipient, amount));
+            }
+
+            Ok(())
+        }
+    }
+}
+
+/// Sends value transfers of a processed action, or discards them if the action
+/// has failed.
+///
+/// Returns value that should be refunded to [`msg::source()`].
+pub fn settle_value(is_processed: bool) -> u128 {
+    let ValueTransfers {
+        is_value_received,
+        transfers,
+    } = unsafe { VALUE_TRANSFERS.remove(&msg::id()) }.unwrap_or_default();
+
+    if !is_processed {
+        return msg::value();
+    }
+
+    for (recipient, value) in transfers {
+        msg::send_bytes(recipient, [], value).expect("failed to transfer value");
+    }
+
+    if is_value_received {
+        0
+    } else {
+        msg::value()
+    }
+}
+
+async fn send_ftokens(
+    transaction_id: u64,
     fungible_token: ActorId,
     sender: ActorId,
     recipient: ActorId,
     amount: u128,
 ) -> Result<(), Error> {
     let payload = FTokenAction::Message {
-        transaction_id: tx_guard.step()?,
+        transaction_id,
         payload: LogicAction::Transfer {
             sender,
             recipient,
@@ -94,3 +209,60 @@ pub async fn transfer_ftokens<T>(
         Ok(())
     }
 }
+
+async fn transfer_ftokens<T: Eq + Hash>(
+    tx_guard: &mut TransactionGuard<'_, T>,
+    fungible_token: ActorId,
+    sender: ActorId,
+    recipient: ActorId,
+    amount: u128,
+) -> Result<(), Error> {
+    let transaction_id = tx_guard.step()?;
+
+    send_ftokens(transaction_id, fungible_token, sender, recipient, amount).await?;
+    tx_guard.record(
+        transaction_id,
+        Step::TransferFTokens {
+            fungible_token,
+            sender,
+            recipient,
+            amount,
+        },
+    );
+
+    Ok(())
+}
+
+/// Runs a compensating step with given `transaction_id` for an executed
+/// `step`. See [`TransactionKind::Abort`].
+pub async fn compensate(transaction_id: u64, step: Step) -> Result<(), Error> {
+    match step {
+        Step::TransferFTokens {
+            fungible_token,
+            sender,
+            recipient,
+            amount,
+        } => send_ftokens(transaction_id, fungible_token, recipient, sender, amount).await,
+        Step::TransferNFT {
+            non_fungible_token,
+            from,
+            token_id,
+        } => send_nft(transaction_id, non_fungible_token, from, token_id)
+            .await
+            .map(|_| ()),
+        Step::MintNFT {
+            non_fungible_token,
+            token_id,
+        } => nft_event_to_transfer(
+            send(
+                non_fungible_token,
+                NFTAction::Burn {
+                    transaction_id,
+                    token_id,
+                },
+            )?
+            .await,
+        )
+        .map(|_| ()),
+    }
+}
@@ -11,11 +11,12 @@ include!(concat!(env!("OUT_DIR"), "/wasm_binary.rs"));
 pub mod metafns {
     pub type State = <ContractMetadata as Metadata>::State;
 
-    pub fn item_info(state: State, item_id: ItemId) -> Option<ItemInfo> {
+    pub fn item_info(mut state: State, item_id: ItemId) -> Option<ItemInfo> {
         state
             .items
-            .into_iter()
-            .find_map(|(some_item_id, item_info)| (some_item_id == item_id).then_some(item_info))
+            .binary_search_by_key(&item_id, |item| item.0)
+            .ok()
+            .map(|index| state.items.swap_remove(index).1)
     }
 
     pub fn participants(state: State) -> Participants {
@@ -23,6 +24,8 @@ pub mod metafns {
             producers: state.producers,
             distributors: state.distributors,
             retailers: state.retailers,
+            admin: state.admin,
+            arbiter: state.arbiter,
         }
     }
 
@@ -61,4 +64,111 @@ pub mod metafns {
             false
         }
     }
+
+    pub fn penalty_schedule(state: State) -> PenaltySchedule {
+        state.penalty_schedule
+    }
+
+    pub fn item_history(mut state: State, item_id: ItemId) -> Option<Vec<HistoryRecord>> {
+        state
+            .item_histories
+            .binary_search_by_key(&item_id, |item| item.0)
+            .ok()
+            .map(|index| state.item_histories.swap_remove(index).1)
+    }
+
+    pub fn items_page(state: State, filter: ItemFilter, offset: u32, limit: u32) -> ItemsPage {
+        let items: Vec<_> = state
+            .items
+            .into_iter()
+            .filter(|(_, item_info)| filter.matches(item_info))
+            .collect();
+        let total = items.len() as _;
+
+        ItemsPage {
+            items: items
+                .into_iter()
+                .skip(offset as _)
+                .take(limit as _)
+                .collect(),
+            total,
+        }
+    }
+
+    pub fn subscribers(state: State) -> Vec<(ActorId, SubscriptionFilter)> {
+        state.subscribers
+    }
+
+    pub fn lot_info(mut state: State, lot_id: LotId) -> Option<LotInfo> {
+        state
+            .lots
+            .binary_search_by_key(&lot_id, |lot| lot.0)
+            .ok()
+            .map(|index| state.lots.swap_remove(index).1)
+    }
+
+    pub fn recalled_items(state: State) -> Vec<(ItemId, String)> {
+        state
+            .items
+            .into_iter()
+            .filter_map(|(item_id, item_info)| {
+                item_info
+                    .recall_reason
+                    .map(|recall_reason| (item_id, recall_reason))
+            })
+            .collect()
+    }
+
+    pub fn platform_fee(state: State) -> Option<PlatformFee> {
+        state.platform_fee
+    }
+
+    pub fn collected_fees(state: State) -> Vec<(ActorId, u128)> {
+        state.collected_fees
+    }
+
+    pub fn currencies(state: State) -> Vec<ActorId> {
+        if state.payment_mode == PaymentMode::Value {
+            return vec![];
+        }
+
+        let mut currencies = vec![state.fungible_token];
+
+        currencies.extend(
+            state
+                .currencies
+                .into_iter()
+                .filter(|currency| *currency != state.fungible_token),
+        );
+
+        currencies
+    }
+
+    pub fn offers(mut state: State, item_id: ItemId) -> Vec<(ActorId, Offer)> {
+        state
+            .offers
+            .binary_search_by_key(&item_id, |offers| offers.0)
+            .map(|index| state.offers.swap_remove(index).1)
+            .unwrap_or_default()
+    }
+
+    pub fn auction_info(mut state: State, item_id: ItemId) -> Option<Auction> {
+        state
+            .auctions
+            .binary_search_by_key(&item_id, |auction| auction.0)
+            .ok()
+            .map(|index| state.auctions.swap_remove(index).1)
+    }
+
+    pub fn cached_actions(state: State, actor: ActorId) -> Vec<CachedAction> {
+        state
+            .cached_actions
+            .into_iter()
+            .filter_map(|(cached_actor, action)| (cached_actor == actor).then_some(action))
+            .collect()
+    }
+
+    pub fn transaction_ttl(state: State) -> Option<u64> {
+        state.transaction_ttl
+    }
 }
@@ -0,0 +1,259 @@
+use utils::{prelude::*, FungibleToken, NonFungibleToken};
+
+pub mod utils;
+
+const ROYALTY: u16 = 1000;
+const ROYALTY_AMOUNT: u128 = ITEM_PRICE * ROYALTY as u128 / MAX_BASIS_POINTS as u128;
+
+#[test]
+fn abort() {
+    let system = utils::initialize_system();
+
+    let mut non_fungible_token = NonFungibleToken::initialize(&system);
+    let mut fungible_token = FungibleToken::initialize(&system);
+    let mut supply_chain = SupplyChain::initialize(
+        &system,
+        fungible_token.actor_id(),
+        non_fungible_token.actor_id(),
+    );
+
+    non_fungible_token.add_minter(supply_chain.actor_id());
+
+    for from in [DISTRIBUTOR, RETAILER] {
+        fungible_token.mint(from, ITEM_PRICE);
+        fungible_token.approve(from, supply_chain.actor_id(), ITEM_PRICE);
+    }
+
+    supply_chain
+        .produce_with_royalty(PRODUCER, ROYALTY)
+        .succeed(0);
+    non_fungible_token.approve(PRODUCER, supply_chain.actor_id(), 0);
+    supply_chain
+        .put_up_for_sale_by_producer(PRODUCER, 0, ITEM_PRICE)
+        .succeed(0);
+    supply_chain
+        .purchase_by_distributor(DISTRIBUTOR, 0, DELIVERY_TIME)
+        .succeed(0);
+    supply_chain
+        .approve_by_producer(PRODUCER, 0, true)
+        .succeed((0, true));
+    supply_chain.ship_by_producer(PRODUCER, 0).succeed(0);
+    supply_chain
+        .receive_by_distributor(DISTRIBUTOR, 0)
+        .succeed(0);
+    supply_chain.process(DISTRIBUTOR, 0).succeed(0);
+    supply_chain.package(DISTRIBUTOR, 0).succeed(0);
+    non_fungible_token.approve(DISTRIBUTOR, supply_chain.actor_id(), 0);
+    supply_chain
+        .put_up_for_sale_by_distributor(DISTRIBUTOR, 0, ITEM_PRICE)
+        .succeed(0);
+    supply_chain
+        .purchase_by_retailer(RETAILER, 0, DELIVERY_TIME)
+        .succeed(0);
+    supply_chain
+        .approve_by_distributor(DISTRIBUTOR, 0, true)
+        .succeed((0, true));
+    supply_chain.ship_by_distributor(DISTRIBUTOR, 0).succeed(0);
+    supply_chain.receive_by_retailer(RETAILER, 0).succeed(0);
+    non_fungible_token.approve(RETAILER, supply_chain.actor_id(), 0);
+    supply_chain
+        .put_up_for_sale_by_retailer(RETAILER, 0, ITEM_PRICE)
+        .succeed(0);
+
+    // The consumer has enough fungible tokens only for the retailer's share,
+    // so the purchase fails on the royalty transfer.
+    fungible_token.mint(CONSUMER, ITEM_PRICE - ROYALTY_AMOUNT);
+    fungible_token.approve(CONSUMER, supply_chain.actor_id(), ITEM_PRICE);
+    supply_chain
+        .purchase_by_consumer(CONSUMER, 0)
+        .failed(Error::FTTransferFailed);
+    fungible_token.balance(CONSUMER).contains(0);
+    fungible_token
+        .balance(RETAILER)
+        .contains(ITEM_PRICE - ROYALTY_AMOUNT);
+
+    let purchase = Action::new(InnerAction::Consumer(ConsumerAction::Purchase(0.into())));
+
+    supply_chain
+        .send_action(FOREIGN_USER, purchase.clone().to_abort())
+        .failed(Error::TxCacheError(
+            TransactionCacheError::TransactionNotFound,
+        ));
+
+    // Without the retailer's approval, the abort can't be completed and blocks
+    // the action...
+    supply_chain
+        .send_action(CONSUMER, purchase.clone().to_abort())
+        .failed(Error::FTTransferFailed);
+    supply_chain
+        .purchase_by_consumer(CONSUMER, 0)
+        .failed(Error::TxCacheError(TransactionCacheError::AbortInProgress));
+
+    // ...until the admin purges it.
+    let purge = Action::new(InnerAction::Admin(AdminAction::PurgeTransaction {
+        actor: CONSUMER.into(),
+        action: CachedAction::Purchase(0.into()),
+    }));
+
+    supply_chain
+        .send_action(CONSUMER, purge.clone())
+        .failed(Error::AccessRestricted);
+    supply_chain
+        .send_action(FOREIGN_USER, purge.clone())
+        .succeed(Event::TransactionPurged(CachedAction::Purchase(0.into())));
+    supply_chain
+        .send_action(FOREIGN_USER, purge)
+        .failed(Error::TxCacheError(
+            TransactionCacheError::TransactionNotFound,
+        ));
+    supply_chain.state().cached_actions(CONSUMER).eq(vec![]);
+
+    // The purchase fails again the same way.
+    fungible_token.mint(CONSUMER, ITEM_PRICE - ROYALTY_AMOUNT);
+    fungible_token.approve(CONSUMER, supply_chain.actor_id(), ITEM_PRICE);
+    supply_chain
+        .purchase_by_consumer(CONSUMER, 0)
+        .failed(Error::FTTransferFailed);
+    fungible_token
+        .balance(RETAILER)
+        .contains((ITEM_PRICE - ROYALTY_AMOUNT) * 2);
+
+    // The retailer's share is transferred back to the consumer.
+    fungible_token.approve(RETAILER, supply_chain.actor_id(), ITEM_PRICE);
+    supply_chain
+        .send_action(CONSUMER, purchase.clone().to_abort())
+        .succeed(Event::TransactionAborted(CachedAction::Purchase(0.into())));
+    fungible_token
+        .balance(CONSUMER)
+        .contains(ITEM_PRICE - ROYALTY_AMOUNT);
+    fungible_token
+        .balance(RETAILER)
+        .contains(ITEM_PRICE - ROYALTY_AMOUNT);
+    supply_chain.state().item_state(0).eq(Some(ItemState {
+        state: ItemEventState::ForSale,
+        by: Role::Retailer,
+    }));
+    supply_chain
+        .send_action(CONSUMER, purchase.to_retry())
+        .failed(Error::TxCacheError(
+            TransactionCacheError::TransactionNotFound,
+        ));
+
+    // The aborted action can be sent again.
+    fungible_token.mint(CONSUMER, ROYALTY_AMOUNT);
+    supply_chain.purchase_by_consumer(CONSUMER, 0).succeed(0);
+    fungible_token
+        .balance(RETAILER)
+        .contains((ITEM_PRICE - ROYALTY_AMOUNT) * 2);
+    fungible_token
+        .balance(PRODUCER)
+        .contains(ITEM_PRICE + ROYALTY_AMOUNT * 2);
+}
+
+#[test]
+fn abort_nft_transfer() {
+    let system = utils::initialize_system();
+
+    let mut non_fungible_token = NonFungibleToken::initialize(&system);
+    let fungible_token = FungibleToken::initialize(&system);
+    let mut supply_chain = SupplyChain::initialize(
+        &system,
+        fungible_token.actor_id(),
+        non_fungible_token.actor_id(),
+    );
+
+    non_fungible_token.add_minter(supply_chain.actor_id());
+
+    supply_chain.produce_batch(PRODUCER, 2).succeed(vec![0, 1]);
+    supply_chain
+        .lot_action(PRODUCER, LotAction::Create(vec![0.into(), 1.into()]))
+        .succeed((
+            0,
+            vec![0, 1],
+            ItemState {
+                state: ItemEventState::Produced,
+                by: Role::Producer,
+            },
+        ));
+    non_fungible_token.approve(PRODUCER, supply_chain.actor_id(), 0);
+
+    let item_infos = [0, 1].map(|item_id| supply_chain.state().item_info(item_id).0);
+    let lot_info = supply_chain.state().lot_info(0).0;
+    let put_up_for_sale = Action::new(InnerAction::Lot(LotAction::PutUpForSale {
+        lot_id: 0,
+        price: ITEM_PRICE,
+        currency: None,
+    }));
+
+    // The second NFT of the lot isn't approved, so the action fails after the
+    // first one is transferred.
+    let _ = supply_chain.send_action(PRODUCER, put_up_for_sale.clone());
+    supply_chain
+        .state()
+        .cached_actions(PRODUCER)
+        .eq(vec![CachedAction::Lot(0)]);
+    non_fungible_token
+        .meta_state()
+        .owner_id(0)
+        .eq(supply_chain.actor_id());
+
+    // The transferred NFT is returned, and the lot with its items stays as it
+    // was before the action.
+    supply_chain
+        .send_action(PRODUCER, put_up_for_sale.to_abort())
+        .succeed(Event::TransactionAborted(CachedAction::Lot(0)));
+
+    for (item_id, item_info) in [0, 1].into_iter().zip(item_infos) {
+        non_fungible_token
+            .meta_state()
+            .owner_id(item_id)
+            .eq(PRODUCER.into());
+        supply_chain.state().item_info(item_id).eq(item_info);
+    }
+
+    supply_chain.state().lot_info(0).eq(lot_info);
+    supply_chain.state().cached_actions(PRODUCER).eq(vec![]);
+}
+
+#[test]
+fn abort_mint() {
+    let system = utils::initialize_system();
+
+    // Only one NFT can be minted, so a batch of 2 items fails after the first
+    // one is minted & transferred to the producer.
+    let mut non_fungible_token = NonFungibleToken::initialize_with_max_mint_count(&system, Some(1));
+    let fungible_token = FungibleToken::initialize(&system);
+    let mut supply_chain = SupplyChain::initialize(
+        &system,
+        fungible_token.actor_id(),
+        non_fungible_token.actor_id(),
+    );
+
+    non_fungible_token.add_minter(supply_chain.actor_id());
+
+    let owner = non_fungible_token.meta_state().owner_id(0).0;
+    let produce_batch = Action::new(InnerAction::Producer(ProducerAction::ProduceBatch {
+        items: vec![Default::default(); 2],
+        royalty: 0,
+    }));
+
+    let _ = supply_chain.send_action(PRODUCER, produce_batch.clone());
+    supply_chain
+        .state()
+        .cached_actions(PRODUCER)
+        .eq(vec![CachedAction::ProduceBatch]);
+    non_fungible_token
+        .meta_state()
+        .owner_id(0)
+        .eq(PRODUCER.into());
+
+    // The minted NFT is transferred back and burned, and its item is removed.
+    non_fungible_token.approve(PRODUCER, supply_chain.actor_id(), 0);
+    supply_chain
+        .send_action(PRODUCER, produce_batch.to_abort())
+        .succeed(Event::TransactionAborted(CachedAction::ProduceBatch));
+    non_fungible_token.meta_state().owner_id(0).eq(owner);
+    supply_chain.state().item_info(0).eq(None);
+    supply_chain.state().existing_items().eq(Default::default());
+    supply_chain.state().cached_actions(PRODUCER).eq(vec![]);
+}
@@ -0,0 +1,118 @@
+use utils::{prelude::*, FungibleToken, NonFungibleToken};
+
+pub mod utils;
+
+const NEW_PARTICIPANT: u64 = 13;
+
+#[test]
+fn participant_management() {
+    let system = utils::initialize_system();
+
+    let fungible_token = FungibleToken::initialize(&system);
+    let mut non_fungible_token = NonFungibleToken::initialize(&system);
+    let mut supply_chain = SupplyChain::initialize(
+        &system,
+        fungible_token.actor_id(),
+        non_fungible_token.actor_id(),
+    );
+
+    non_fungible_token.add_minter(supply_chain.actor_id());
+
+    supply_chain
+        .produce(NEW_PARTICIPANT)
+        .failed(Error::AccessRestricted);
+
+    // Only the admin (the initializer) can manage participants.
+    supply_chain
+        .add_participant(PRODUCER, Role::Producer, NEW_PARTICIPANT)
+        .failed(Error::AccessRestricted);
+    supply_chain
+        .add_participant(FOREIGN_USER, Role::Producer, 0)
+        .failed(Error::ZeroActorId);
+    supply_chain
+        .add_participant(FOREIGN_USER, Role::Consumer, NEW_PARTICIPANT)
+        .failed(Error::UnexpectedRole);
+    supply_chain
+        .add_participant(FOREIGN_USER, Role::Producer, PRODUCER)
+        .failed(Error::ParticipantExists);
+
+    for role in [Role::Producer, Role::Distributor, Role::Retailer] {
+        supply_chain
+            .add_participant(FOREIGN_USER, role, NEW_PARTICIPANT)
+            .succeed((role, NEW_PARTICIPANT));
+    }
+
+    supply_chain.state().roles(NEW_PARTICIPANT).eq([
+        Role::Consumer,
+        Role::Producer,
+        Role::Distributor,
+        Role::Retailer,
+    ]
+    .into());
+    supply_chain.produce(NEW_PARTICIPANT).succeed(0);
+
+    supply_chain
+        .remove_participant(PRODUCER, Role::Producer, NEW_PARTICIPANT)
+        .failed(Error::AccessRestricted);
+    supply_chain
+        .remove_participant(FOREIGN_USER, Role::Consumer, NEW_PARTICIPANT)
+        .failed(Error::UnexpectedRole);
+    supply_chain
+        .remove_participant(FOREIGN_USER, Role::Producer, NEW_PARTICIPANT)
+        .succeed((Role::Producer, NEW_PARTICIPANT));
+    supply_chain
+        .remove_participant(FOREIGN_USER, Role::Producer, NEW_PARTICIPANT)
+        .failed(Error::ParticipantNotFound);
+
+    supply_chain.state().roles(NEW_PARTICIPANT).eq([
+        Role::Consumer,
+        Role::Distributor,
+        Role::Retailer,
+    ]
+    .into());
+    supply_chain
+        .produce(NEW_PARTICIPANT)
+        .failed(Error::AccessRestricted);
+    // The removed producer loses access to its items too.
+    supply_chain
+        .put_up_for_sale_by_producer(NEW_PARTICIPANT, 0, ITEM_PRICE)
+        .failed(Error::AccessRestricted);
+}
+
+#[test]
+fn admin_change() {
+    let system = utils::initialize_system();
+
+    let fungible_token = FungibleToken::initialize(&system);
+    let non_fungible_token = NonFungibleToken::initialize(&system);
+    let mut supply_chain = SupplyChain::initialize(
+        &system,
+        fungible_token.actor_id(),
+        non_fungible_token.actor_id(),
+    );
+
+    supply_chain
+        .change_admin(PRODUCER, PRODUCER)
+        .failed(Error::AccessRestricted);
+    supply_chain
+        .change_admin(FOREIGN_USER, 0)
+        .failed(Error::ZeroActorId);
+    supply_chain
+        .change_admin(FOREIGN_USER, NEW_PARTICIPANT)
+        .succeed(NEW_PARTICIPANT);
+
+    supply_chain
+        .add_participant(FOREIGN_USER, Role::Producer, NEW_PARTICIPANT)
+        .failed(Error::AccessRestricted);
+    supply_chain
+        .add_participant(NEW_PARTICIPANT, Role::Producer, NEW_PARTICIPANT)
+        .succeed((Role::Producer, NEW_PARTICIPANT));
+    supply_chain
+        .state()
+        .roles(NEW_PARTICIPANT)
+        .eq([Role::Consumer, Role::Producer].into());
+    assert_eq!(
+        supply_chain.state().participants().0.admin,
+        ActorId::from(NEW_PARTICIPANT)
+    );
+}
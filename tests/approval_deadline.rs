@@ -0,0 +1,120 @@
+use utils::{prelude::*, FungibleToken, NonFungibleToken};
+
+pub mod utils;
+
+const APPROVAL_TIME: u64 = 5000;
+const APPROVAL_TIME_IN_BLOCKS: u32 = (APPROVAL_TIME / 1000) as _;
+
+fn for_sale_by_producer() -> Option<ItemState> {
+    Some(ItemState {
+        state: ItemEventState::ForSale,
+        by: Role::Producer,
+    })
+}
+
+#[test]
+fn approval_deadline() {
+    let system = utils::initialize_system();
+
+    let mut non_fungible_token = NonFungibleToken::initialize(&system);
+    let mut fungible_token = FungibleToken::initialize(&system);
+    let mut supply_chain = SupplyChain::initialize(
+        &system,
+        fungible_token.actor_id(),
+        non_fungible_token.actor_id(),
+    );
+
+    non_fungible_token.add_minter(supply_chain.actor_id());
+    fungible_token.mint(DISTRIBUTOR, ITEM_PRICE * 2);
+    fungible_token.approve(DISTRIBUTOR, supply_chain.actor_id(), ITEM_PRICE * 4);
+
+    for item_id in [0, 1] {
+        supply_chain.produce(PRODUCER).succeed(item_id);
+        non_fungible_token.approve(PRODUCER, supply_chain.actor_id(), item_id);
+        supply_chain
+            .put_up_for_sale_by_producer(PRODUCER, item_id, ITEM_PRICE)
+            .succeed(item_id);
+    }
+
+    let purchase = |supply_chain: &mut SupplyChain, item_id| {
+        supply_chain
+            .purchase_by_distributor_custom(
+                DISTRIBUTOR,
+                item_id,
+                DELIVERY_TIME,
+                None,
+                Some(APPROVAL_TIME),
+            )
+            .succeed(item_id);
+    };
+
+    // The producer approves a purchase before its deadline.
+    purchase(&mut supply_chain, 0);
+    assert!(supply_chain
+        .state()
+        .item_info(0)
+        .0
+        .unwrap()
+        .approval_deadline
+        .is_some());
+    supply_chain
+        .approve_by_producer(PRODUCER, 0, true)
+        .succeed((0, true));
+    assert_eq!(
+        supply_chain
+            .state()
+            .item_info(0)
+            .0
+            .unwrap()
+            .approval_deadline,
+        None
+    );
+
+    // The producer is late, so the contract expires a purchase itself.
+    purchase(&mut supply_chain, 1);
+    system.spend_blocks(APPROVAL_TIME_IN_BLOCKS);
+    supply_chain
+        .approve_by_producer(PRODUCER, 1, true)
+        .failed(Error::ApprovalExpired);
+    system.spend_blocks(1);
+    supply_chain
+        .state()
+        .item_state(1)
+        .eq(for_sale_by_producer());
+    fungible_token.balance(DISTRIBUTOR).contains(ITEM_PRICE);
+    fungible_token
+        .balance(supply_chain.actor_id())
+        .contains(ITEM_PRICE);
+
+    // Anyone can expire a purchase as soon as its deadline passes.
+    purchase(&mut supply_chain, 1);
+    supply_chain
+        .expire_purchase(FOREIGN_USER, 1)
+        .failed(Error::DeadlineNotReached);
+    system.spend_blocks(APPROVAL_TIME_IN_BLOCKS - 1);
+    supply_chain
+        .expire_purchase(FOREIGN_USER, 1)
+        .succeed((1, Role::Producer));
+    supply_chain
+        .state()
+        .item_state(1)
+        .eq(for_sale_by_producer());
+    fungible_token.balance(DISTRIBUTOR).contains(ITEM_PRICE);
+
+    // The approved purchase isn't affected by its deadline.
+    supply_chain
+        .expire_purchase(FOREIGN_USER, 0)
+        .failed(Error::UnexpectedItemState);
+
+    // A purchase without a deadline never expires.
+    supply_chain
+        .purchase_by_distributor(DISTRIBUTOR, 1, DELIVERY_TIME)
+        .succeed(1);
+    system.spend_blocks(APPROVAL_TIME_IN_BLOCKS * 2);
+    supply_chain
+        .expire_purchase(FOREIGN_USER, 1)
+        .failed(Error::DeadlineNotReached);
+    supply_chain
+        .approve_by_producer(PRODUCER, 1, true)
+        .succeed((1, true));
+}
@@ -0,0 +1,147 @@
+use utils::{prelude::*, FungibleToken, NonFungibleToken};
+
+pub mod utils;
+
+const OTHER_DISTRIBUTOR: u64 = 15;
+const DURATION: u64 = 10000;
+const DURATION_IN_BLOCKS: u32 = (DURATION / 1000) as _;
+
+fn start(item_id: u128) -> AuctionAction {
+    AuctionAction::Start {
+        item_id: item_id.into(),
+        reserve_price: ITEM_PRICE,
+        duration: DURATION,
+        currency: None,
+    }
+}
+
+fn bid(price: u128) -> AuctionAction {
+    AuctionAction::Bid {
+        item_id: 0.into(),
+        price,
+        delivery_time: DELIVERY_TIME,
+    }
+}
+
+fn auctioned(item_id: u128) -> Event {
+    Event::Item {
+        item_id: item_id.into(),
+        item_state: ItemState {
+            state: ItemEventState::Auctioned,
+            by: Role::Producer,
+        },
+    }
+}
+
+#[test]
+fn auction() {
+    let system = utils::initialize_system();
+
+    let mut non_fungible_token = NonFungibleToken::initialize(&system);
+    let mut fungible_token = FungibleToken::initialize(&system);
+    let mut supply_chain = SupplyChain::initialize(
+        &system,
+        fungible_token.actor_id(),
+        non_fungible_token.actor_id(),
+    );
+
+    non_fungible_token.add_minter(supply_chain.actor_id());
+    supply_chain
+        .add_participant(FOREIGN_USER, Role::Distributor, OTHER_DISTRIBUTOR)
+        .succeed((Role::Distributor, OTHER_DISTRIBUTOR));
+
+    for from in [DISTRIBUTOR, OTHER_DISTRIBUTOR] {
+        fungible_token.mint(from, ITEM_PRICE * 2);
+        fungible_token.approve(from, supply_chain.actor_id(), ITEM_PRICE * 2);
+    }
+
+    for item_id in [0, 1] {
+        supply_chain.produce(PRODUCER).succeed(item_id);
+        non_fungible_token.approve(PRODUCER, supply_chain.actor_id(), item_id);
+    }
+
+    supply_chain
+        .auction_action(FOREIGN_USER, start(0))
+        .failed(Error::AccessRestricted);
+    supply_chain
+        .auction_action(PRODUCER, start(0))
+        .succeed(auctioned(0));
+    supply_chain
+        .purchase_by_distributor(DISTRIBUTOR, 0, DELIVERY_TIME)
+        .failed(Error::UnexpectedItemState);
+
+    supply_chain
+        .auction_action(DISTRIBUTOR, bid(ITEM_PRICE - 1))
+        .failed(Error::BidTooLow);
+    supply_chain
+        .auction_action(DISTRIBUTOR, bid(ITEM_PRICE))
+        .succeed(Event::AuctionBid {
+            item_id: 0.into(),
+            bidder: DISTRIBUTOR.into(),
+            price: ITEM_PRICE,
+        });
+    supply_chain
+        .auction_action(OTHER_DISTRIBUTOR, bid(ITEM_PRICE))
+        .failed(Error::BidTooLow);
+    // The previous highest bid is refunded on an outbid.
+    supply_chain
+        .auction_action(OTHER_DISTRIBUTOR, bid(ITEM_PRICE + 1))
+        .succeed(Event::AuctionBid {
+            item_id: 0.into(),
+            bidder: OTHER_DISTRIBUTOR.into(),
+            price: ITEM_PRICE + 1,
+        });
+    fungible_token.balance(DISTRIBUTOR).contains(ITEM_PRICE * 2);
+    fungible_token
+        .balance(supply_chain.actor_id())
+        .contains(ITEM_PRICE + 1);
+    assert_eq!(
+        supply_chain.state().auction_info(0).0.unwrap().highest_bid,
+        Some(Bid {
+            bidder: OTHER_DISTRIBUTOR.into(),
+            price: ITEM_PRICE + 1,
+            delivery_time: DELIVERY_TIME,
+        })
+    );
+
+    supply_chain
+        .settle_auction(FOREIGN_USER, 0)
+        .failed(Error::DeadlineNotReached);
+    system.spend_blocks(DURATION_IN_BLOCKS);
+    supply_chain
+        .auction_action(DISTRIBUTOR, bid(ITEM_PRICE * 2))
+        .failed(Error::AuctionEnded);
+    // Anyone can settle the auction as soon as it ends.
+    supply_chain.settle_auction(FOREIGN_USER, 0).succeed((
+        0,
+        ItemState {
+            state: ItemEventState::Purchased,
+            by: Role::Distributor,
+        },
+    ));
+    supply_chain.state().auction_info(0).eq(None);
+
+    supply_chain
+        .approve_by_producer(PRODUCER, 0, true)
+        .succeed((0, true));
+    supply_chain.ship_by_producer(PRODUCER, 0).succeed(0);
+    supply_chain
+        .receive_by_distributor(OTHER_DISTRIBUTOR, 0)
+        .succeed(0);
+    fungible_token.balance(PRODUCER).contains(ITEM_PRICE + 1);
+
+    // If nobody settles an auction without bids, the contract does it itself
+    // and transfers the NFT back to the seller.
+    supply_chain
+        .auction_action(PRODUCER, start(1))
+        .succeed(auctioned(1));
+    system.spend_blocks(DURATION_IN_BLOCKS + 1);
+    supply_chain.state().item_state(1).eq(Some(ItemState {
+        state: ItemEventState::Produced,
+        by: Role::Producer,
+    }));
+    non_fungible_token
+        .meta_state()
+        .owner_id(1)
+        .eq(PRODUCER.into());
+}
@@ -0,0 +1,207 @@
+use utils::{prelude::*, FungibleToken, NonFungibleToken};
+
+pub mod utils;
+
+fn purchase(item_id: u128) -> Action {
+    Action::new(InnerAction::Distributor(DistributorAction::Purchase {
+        item_id: item_id.into(),
+        delivery_time: DELIVERY_TIME,
+        penalty_schedule: None,
+        approval_time: None,
+    }))
+}
+
+#[test]
+fn cached_actions() {
+    let system = utils::initialize_system();
+
+    let mut non_fungible_token = NonFungibleToken::initialize(&system);
+    let mut fungible_token = FungibleToken::initialize(&system);
+    let mut supply_chain = SupplyChain::initialize(
+        &system,
+        fungible_token.actor_id(),
+        non_fungible_token.actor_id(),
+    );
+
+    non_fungible_token.add_minter(supply_chain.actor_id());
+    fungible_token.approve(DISTRIBUTOR, supply_chain.actor_id(), ITEM_PRICE * 2);
+
+    for item_id in [0, 1] {
+        supply_chain.produce(PRODUCER).succeed(item_id);
+        non_fungible_token.approve(PRODUCER, supply_chain.actor_id(), item_id);
+        supply_chain
+            .put_up_for_sale_by_producer(PRODUCER, item_id, ITEM_PRICE)
+            .succeed(item_id);
+    }
+
+    // Completed transactions aren't cached.
+    supply_chain.state().cached_actions(PRODUCER).eq(vec![]);
+
+    // The distributor has no fungible tokens yet, so both purchases fail, and
+    // their transactions are cached at once.
+    for item_id in [0, 1] {
+        supply_chain
+            .send_action(DISTRIBUTOR, purchase(item_id))
+            .failed(Error::FTTransferFailed);
+    }
+
+    supply_chain.state().cached_actions(DISTRIBUTOR).eq(vec![
+        CachedAction::Purchase(0.into()),
+        CachedAction::Purchase(1.into()),
+    ]);
+
+    fungible_token.mint(DISTRIBUTOR, ITEM_PRICE);
+    supply_chain
+        .send_action(DISTRIBUTOR, purchase(1).to_retry())
+        .succeed(Event::Item {
+            item_id: 1.into(),
+            item_state: ItemState {
+                state: ItemEventState::Purchased,
+                by: Role::Distributor,
+            },
+        });
+    supply_chain
+        .state()
+        .cached_actions(DISTRIBUTOR)
+        .eq(vec![CachedAction::Purchase(0.into())]);
+    supply_chain
+        .send_action(DISTRIBUTOR, purchase(1).to_retry())
+        .failed(Error::TxCacheError(
+            TransactionCacheError::TransactionNotFound,
+        ));
+
+    supply_chain
+        .send_action(DISTRIBUTOR, purchase(0).to_abort())
+        .succeed(Event::TransactionAborted(CachedAction::Purchase(0.into())));
+    supply_chain.state().cached_actions(DISTRIBUTOR).eq(vec![]);
+}
+
+#[test]
+fn retry_payload() {
+    let system = utils::initialize_system();
+
+    let mut non_fungible_token = NonFungibleToken::initialize(&system);
+    let mut fungible_token = FungibleToken::initialize(&system);
+    let mut supply_chain = SupplyChain::initialize(
+        &system,
+        fungible_token.actor_id(),
+        non_fungible_token.actor_id(),
+    );
+
+    non_fungible_token.add_minter(supply_chain.actor_id());
+    fungible_token.approve(DISTRIBUTOR, supply_chain.actor_id(), ITEM_PRICE);
+
+    supply_chain.produce(PRODUCER).succeed(0);
+    non_fungible_token.approve(PRODUCER, supply_chain.actor_id(), 0);
+    supply_chain
+        .put_up_for_sale_by_producer(PRODUCER, 0, ITEM_PRICE)
+        .succeed(0);
+
+    supply_chain
+        .send_action(DISTRIBUTOR, Action::retry(CachedAction::Purchase(0.into())))
+        .failed(Error::TxCacheError(
+            TransactionCacheError::TransactionNotFound,
+        ));
+    supply_chain
+        .send_action(DISTRIBUTOR, purchase(0))
+        .failed(Error::FTTransferFailed);
+
+    // A retry can't change the payload of the cached action.
+    supply_chain
+        .send_action(
+            DISTRIBUTOR,
+            Action::new(InnerAction::Distributor(DistributorAction::Purchase {
+                item_id: 0.into(),
+                delivery_time: DELIVERY_TIME * 2,
+                penalty_schedule: None,
+                approval_time: None,
+            }))
+            .to_retry(),
+        )
+        .failed(Error::TxCacheError(TransactionCacheError::MismatchedAction));
+
+    // A retry without a payload replays the cached one.
+    fungible_token.mint(DISTRIBUTOR, ITEM_PRICE);
+    supply_chain
+        .send_action(DISTRIBUTOR, Action::retry(CachedAction::Purchase(0.into())))
+        .succeed(Event::Item {
+            item_id: 0.into(),
+            item_state: ItemState {
+                state: ItemEventState::Purchased,
+                by: Role::Distributor,
+            },
+        });
+    assert_eq!(
+        supply_chain.state().item_info(0).0.unwrap().delivery_time,
+        DELIVERY_TIME
+    );
+    supply_chain.state().cached_actions(DISTRIBUTOR).eq(vec![]);
+}
+
+#[test]
+fn unfinished_transaction() {
+    let system = utils::initialize_system();
+
+    let mut non_fungible_token = NonFungibleToken::initialize(&system);
+    let fungible_token = FungibleToken::initialize(&system);
+    let mut supply_chain = SupplyChain::initialize(
+        &system,
+        fungible_token.actor_id(),
+        non_fungible_token.actor_id(),
+    );
+
+    non_fungible_token.add_minter(supply_chain.actor_id());
+
+    supply_chain.produce_batch(PRODUCER, 2).succeed(vec![0, 1]);
+    supply_chain
+        .lot_action(PRODUCER, LotAction::Create(vec![0.into(), 1.into()]))
+        .succeed((
+            0,
+            vec![0, 1],
+            ItemState {
+                state: ItemEventState::Produced,
+                by: Role::Producer,
+            },
+        ));
+    non_fungible_token.approve(PRODUCER, supply_chain.actor_id(), 0);
+
+    let put_up_for_sale = Action::new(InnerAction::Lot(LotAction::PutUpForSale {
+        lot_id: 0,
+        price: ITEM_PRICE,
+        currency: None,
+    }));
+
+    // The second NFT of the lot isn't approved, so the action fails after the
+    // first one is transferred.
+    let _ = supply_chain.send_action(PRODUCER, put_up_for_sale.clone());
+    supply_chain
+        .state()
+        .cached_actions(PRODUCER)
+        .eq(vec![CachedAction::Lot(0)]);
+
+    // The cached action can't be replaced by a new one, otherwise its executed
+    // steps would be lost...
+    non_fungible_token.approve(PRODUCER, supply_chain.actor_id(), 1);
+    supply_chain
+        .send_action(PRODUCER, put_up_for_sale.clone())
+        .failed(Error::TxCacheError(
+            TransactionCacheError::UnfinishedTransaction,
+        ));
+    non_fungible_token
+        .meta_state()
+        .owner_id(1)
+        .eq(PRODUCER.into());
+
+    // ...but can be retried.
+    supply_chain
+        .send_action(PRODUCER, put_up_for_sale.to_retry())
+        .succeed(Event::Lot {
+            lot_id: 0,
+            item_ids: vec![0.into(), 1.into()],
+            item_state: ItemState {
+                state: ItemEventState::ForSale,
+                by: Role::Producer,
+            },
+        });
+    supply_chain.state().cached_actions(PRODUCER).eq(vec![]);
+}
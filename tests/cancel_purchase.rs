@@ -0,0 +1,98 @@
+use utils::{prelude::*, FungibleToken, NonFungibleToken};
+
+pub mod utils;
+
+#[test]
+fn cancel_purchase() {
+    let system = utils::initialize_system();
+
+    let mut non_fungible_token = NonFungibleToken::initialize(&system);
+    let mut fungible_token = FungibleToken::initialize(&system);
+    let mut supply_chain = SupplyChain::initialize(
+        &system,
+        fungible_token.actor_id(),
+        non_fungible_token.actor_id(),
+    );
+
+    non_fungible_token.add_minter(supply_chain.actor_id());
+
+    for from in [DISTRIBUTOR, RETAILER] {
+        fungible_token.mint(from, ITEM_PRICE);
+        fungible_token.approve(from, supply_chain.actor_id(), ITEM_PRICE * 2);
+    }
+
+    supply_chain.produce(PRODUCER).succeed(0);
+    non_fungible_token.approve(PRODUCER, supply_chain.actor_id(), 0);
+    supply_chain
+        .put_up_for_sale_by_producer(PRODUCER, 0, ITEM_PRICE)
+        .succeed(0);
+
+    // Can't cancel a purchase that doesn't exist.
+    supply_chain
+        .cancel_purchase_by_distributor(DISTRIBUTOR, 0)
+        .failed(Error::UnexpectedItemState);
+
+    supply_chain
+        .purchase_by_distributor(DISTRIBUTOR, 0, DELIVERY_TIME)
+        .succeed(0);
+    fungible_token
+        .balance(supply_chain.actor_id())
+        .contains(ITEM_PRICE);
+
+    supply_chain
+        .cancel_purchase_by_distributor(FOREIGN_USER, 0)
+        .failed(Error::AccessRestricted);
+    // If the seller doesn't answer, the buyer can take its tokens back...
+    supply_chain
+        .cancel_purchase_by_distributor(DISTRIBUTOR, 0)
+        .succeed(0);
+    fungible_token.balance(DISTRIBUTOR).contains(ITEM_PRICE);
+    fungible_token.balance(supply_chain.actor_id()).contains(0);
+    // ...and the seller can't approve the cancelled purchase anymore.
+    supply_chain
+        .approve_by_producer(PRODUCER, 0, true)
+        .failed(Error::UnexpectedItemState);
+
+    supply_chain
+        .purchase_by_distributor(DISTRIBUTOR, 0, DELIVERY_TIME)
+        .succeed(0);
+    supply_chain
+        .approve_by_producer(PRODUCER, 0, true)
+        .succeed((0, true));
+    // An approved purchase can't be cancelled.
+    supply_chain
+        .cancel_purchase_by_distributor(DISTRIBUTOR, 0)
+        .failed(Error::UnexpectedItemState);
+
+    supply_chain.ship_by_producer(PRODUCER, 0).succeed(0);
+    supply_chain
+        .receive_by_distributor(DISTRIBUTOR, 0)
+        .succeed(0);
+    supply_chain.process(DISTRIBUTOR, 0).succeed(0);
+    supply_chain.package(DISTRIBUTOR, 0).succeed(0);
+    non_fungible_token.approve(DISTRIBUTOR, supply_chain.actor_id(), 0);
+    supply_chain
+        .put_up_for_sale_by_distributor(DISTRIBUTOR, 0, ITEM_PRICE)
+        .succeed(0);
+
+    supply_chain
+        .purchase_by_retailer(RETAILER, 0, DELIVERY_TIME)
+        .succeed(0);
+    supply_chain
+        .cancel_purchase_by_retailer(FOREIGN_USER, 0)
+        .failed(Error::AccessRestricted);
+    supply_chain
+        .cancel_purchase_by_retailer(RETAILER, 0)
+        .succeed(0);
+    fungible_token.balance(RETAILER).contains(ITEM_PRICE);
+    supply_chain
+        .approve_by_distributor(DISTRIBUTOR, 0, true)
+        .failed(Error::UnexpectedItemState);
+
+    supply_chain
+        .purchase_by_retailer(RETAILER, 0, DELIVERY_TIME)
+        .succeed(0);
+    supply_chain
+        .approve_by_distributor(DISTRIBUTOR, 0, true)
+        .succeed((0, true));
+}
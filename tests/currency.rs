@@ -0,0 +1,85 @@
+use utils::{prelude::*, FungibleToken, NonFungibleToken};
+
+pub mod utils;
+
+#[test]
+fn currency() {
+    let system = utils::initialize_system();
+
+    let mut non_fungible_token = NonFungibleToken::initialize(&system);
+    let mut fungible_token = FungibleToken::initialize(&system);
+    let mut other_fungible_token = FungibleToken::initialize(&system);
+    let mut supply_chain = SupplyChain::initialize_custom(
+        &system,
+        Initialize {
+            producers: vec![PRODUCER.into()],
+            distributors: vec![DISTRIBUTOR.into()],
+            retailers: vec![RETAILER.into()],
+
+            fungible_token: fungible_token.actor_id(),
+            non_fungible_token: non_fungible_token.actor_id(),
+            payment_mode: Default::default(),
+            currencies: vec![other_fungible_token.actor_id()],
+
+            penalty_schedule: Default::default(),
+            resale_royalty: Default::default(),
+            platform_fee: None,
+            transaction_ttl: None,
+
+            arbiter: ARBITER.into(),
+        },
+    )
+    .succeed();
+
+    supply_chain.state().currencies().eq(vec![
+        fungible_token.actor_id(),
+        other_fungible_token.actor_id(),
+    ]);
+
+    non_fungible_token.add_minter(supply_chain.actor_id());
+    other_fungible_token.mint(DISTRIBUTOR, ITEM_PRICE);
+    other_fungible_token.approve(DISTRIBUTOR, supply_chain.actor_id(), ITEM_PRICE);
+
+    supply_chain.produce(PRODUCER).succeed(0);
+    non_fungible_token.approve(PRODUCER, supply_chain.actor_id(), 0);
+    supply_chain
+        .put_up_for_sale_by_producer_with_currency(
+            PRODUCER,
+            0,
+            ITEM_PRICE,
+            Some(non_fungible_token.actor_id()),
+        )
+        .failed(Error::UnacceptedCurrency);
+    supply_chain
+        .put_up_for_sale_by_producer_with_currency(
+            PRODUCER,
+            0,
+            ITEM_PRICE,
+            Some(other_fungible_token.actor_id()),
+        )
+        .succeed(0);
+    assert_eq!(
+        supply_chain.state().item_info(0).0.unwrap().currency,
+        other_fungible_token.actor_id()
+    );
+
+    supply_chain
+        .purchase_by_distributor(DISTRIBUTOR, 0, DELIVERY_TIME)
+        .succeed(0);
+    other_fungible_token
+        .balance(supply_chain.actor_id())
+        .contains(ITEM_PRICE);
+    fungible_token.balance(supply_chain.actor_id()).contains(0);
+
+    supply_chain
+        .approve_by_producer(PRODUCER, 0, true)
+        .succeed((0, true));
+    supply_chain.ship_by_producer(PRODUCER, 0).succeed(0);
+    supply_chain
+        .receive_by_distributor(DISTRIBUTOR, 0)
+        .succeed(0);
+    other_fungible_token.balance(PRODUCER).contains(ITEM_PRICE);
+    other_fungible_token
+        .balance(supply_chain.actor_id())
+        .contains(0);
+}
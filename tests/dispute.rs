@@ -0,0 +1,219 @@
+use utils::{prelude::*, FungibleToken, NonFungibleToken};
+
+pub mod utils;
+
+const REASON: &str = "The item is damaged.";
+const SELLER_SHARE: u16 = 3000;
+const SELLER_AMOUNT: u128 = ITEM_PRICE * SELLER_SHARE as u128 / MAX_BASIS_POINTS as u128;
+
+#[test]
+fn dispute_and_resolve() {
+    let system = utils::initialize_system();
+
+    let mut non_fungible_token = NonFungibleToken::initialize(&system);
+    let mut fungible_token = FungibleToken::initialize(&system);
+    let mut supply_chain = SupplyChain::initialize(
+        &system,
+        fungible_token.actor_id(),
+        non_fungible_token.actor_id(),
+    );
+
+    non_fungible_token.add_minter(supply_chain.actor_id());
+
+    for from in [DISTRIBUTOR, RETAILER] {
+        fungible_token.mint(from, ITEM_PRICE);
+        fungible_token.approve(from, supply_chain.actor_id(), ITEM_PRICE);
+    }
+
+    supply_chain.produce(PRODUCER).succeed(0);
+    non_fungible_token.approve(PRODUCER, supply_chain.actor_id(), 0);
+    supply_chain
+        .put_up_for_sale_by_producer(PRODUCER, 0, ITEM_PRICE)
+        .succeed(0);
+    supply_chain
+        .purchase_by_distributor(DISTRIBUTOR, 0, DELIVERY_TIME)
+        .succeed(0);
+    supply_chain
+        .approve_by_producer(PRODUCER, 0, true)
+        .succeed((0, true));
+
+    // Only a shipped item can be disputed.
+    supply_chain
+        .dispute_by_distributor(DISTRIBUTOR, 0, REASON)
+        .failed(Error::UnexpectedItemState);
+
+    supply_chain.ship_by_producer(PRODUCER, 0).succeed(0);
+    supply_chain
+        .dispute_by_distributor(FOREIGN_USER, 0, REASON)
+        .failed(Error::AccessRestricted);
+    supply_chain
+        .dispute_by_distributor(DISTRIBUTOR, 0, REASON)
+        .succeed(0);
+    assert_eq!(
+        supply_chain.state().item_info(0).0.unwrap().dispute_reason,
+        Some(REASON.into())
+    );
+
+    // A disputed item can't be received, only resolved by the arbiter.
+    supply_chain
+        .receive_by_distributor(DISTRIBUTOR, 0)
+        .failed(Error::UnexpectedItemState);
+    supply_chain
+        .resolve(DISTRIBUTOR, 0, SELLER_SHARE)
+        .failed(Error::AccessRestricted);
+    supply_chain
+        .resolve(ARBITER, 0, MAX_BASIS_POINTS + 1)
+        .failed(Error::InvalidBasisPoints);
+
+    // The buyer keeps the item at a discount...
+    supply_chain.resolve(ARBITER, 0, SELLER_SHARE).succeed((
+        0,
+        ItemState {
+            state: ItemEventState::Received,
+            by: Role::Distributor,
+        },
+    ));
+    fungible_token.balance(PRODUCER).contains(SELLER_AMOUNT);
+    fungible_token
+        .balance(DISTRIBUTOR)
+        .contains(ITEM_PRICE - SELLER_AMOUNT);
+    fungible_token.balance(supply_chain.actor_id()).contains(0);
+    non_fungible_token
+        .meta_state()
+        .owner_id(0)
+        .eq(DISTRIBUTOR.into());
+    supply_chain
+        .resolve(ARBITER, 0, SELLER_SHARE)
+        .failed(Error::UnexpectedItemState);
+
+    supply_chain.process(DISTRIBUTOR, 0).succeed(0);
+    supply_chain.package(DISTRIBUTOR, 0).succeed(0);
+    non_fungible_token.approve(DISTRIBUTOR, supply_chain.actor_id(), 0);
+    supply_chain
+        .put_up_for_sale_by_distributor(DISTRIBUTOR, 0, ITEM_PRICE)
+        .succeed(0);
+    supply_chain
+        .purchase_by_retailer(RETAILER, 0, DELIVERY_TIME)
+        .succeed(0);
+    supply_chain
+        .approve_by_distributor(DISTRIBUTOR, 0, true)
+        .succeed((0, true));
+    supply_chain.ship_by_distributor(DISTRIBUTOR, 0).succeed(0);
+    supply_chain
+        .dispute_by_retailer(RETAILER, 0, REASON)
+        .succeed(0);
+
+    // ...or returns it to the seller for a full refund.
+    supply_chain.resolve(ARBITER, 0, 0).succeed((
+        0,
+        ItemState {
+            state: ItemEventState::Packaged,
+            by: Role::Distributor,
+        },
+    ));
+    fungible_token.balance(RETAILER).contains(ITEM_PRICE);
+    fungible_token.balance(supply_chain.actor_id()).contains(0);
+    non_fungible_token
+        .meta_state()
+        .owner_id(0)
+        .eq(DISTRIBUTOR.into());
+
+    non_fungible_token.approve(DISTRIBUTOR, supply_chain.actor_id(), 0);
+    supply_chain
+        .put_up_for_sale_by_distributor(DISTRIBUTOR, 0, ITEM_PRICE)
+        .succeed(0);
+}
+
+#[test]
+fn resolve_with_fee_and_royalty() {
+    const TREASURY: u64 = 13;
+    const FEE: u16 = 200;
+    const ROYALTY: u16 = 1000;
+    const FEE_AMOUNT: u128 = SELLER_AMOUNT * FEE as u128 / MAX_BASIS_POINTS as u128;
+    const ROYALTY_AMOUNT: u128 =
+        (SELLER_AMOUNT - FEE_AMOUNT) * ROYALTY as u128 / MAX_BASIS_POINTS as u128;
+
+    let system = utils::initialize_system();
+
+    let mut non_fungible_token = NonFungibleToken::initialize(&system);
+    let mut fungible_token = FungibleToken::initialize(&system);
+    let mut supply_chain = SupplyChain::initialize(
+        &system,
+        fungible_token.actor_id(),
+        non_fungible_token.actor_id(),
+    );
+
+    non_fungible_token.add_minter(supply_chain.actor_id());
+
+    for from in [DISTRIBUTOR, RETAILER] {
+        fungible_token.mint(from, ITEM_PRICE);
+        fungible_token.approve(from, supply_chain.actor_id(), ITEM_PRICE);
+    }
+
+    supply_chain
+        .produce_with_royalty(PRODUCER, ROYALTY)
+        .succeed(0);
+    non_fungible_token.approve(PRODUCER, supply_chain.actor_id(), 0);
+    supply_chain
+        .put_up_for_sale_by_producer(PRODUCER, 0, ITEM_PRICE)
+        .succeed(0);
+    supply_chain
+        .purchase_by_distributor(DISTRIBUTOR, 0, DELIVERY_TIME)
+        .succeed(0);
+    supply_chain
+        .approve_by_producer(PRODUCER, 0, true)
+        .succeed((0, true));
+    supply_chain.ship_by_producer(PRODUCER, 0).succeed(0);
+    supply_chain
+        .receive_by_distributor(DISTRIBUTOR, 0)
+        .succeed(0);
+    supply_chain.process(DISTRIBUTOR, 0).succeed(0);
+    supply_chain.package(DISTRIBUTOR, 0).succeed(0);
+
+    let platform_fee = Some(PlatformFee {
+        fee: FEE,
+        treasury: TREASURY.into(),
+    });
+
+    supply_chain
+        .set_platform_fee(FOREIGN_USER, platform_fee)
+        .succeed(platform_fee);
+    non_fungible_token.approve(DISTRIBUTOR, supply_chain.actor_id(), 0);
+    supply_chain
+        .put_up_for_sale_by_distributor(DISTRIBUTOR, 0, ITEM_PRICE)
+        .succeed(0);
+    supply_chain
+        .purchase_by_retailer(RETAILER, 0, DELIVERY_TIME)
+        .succeed(0);
+    supply_chain
+        .approve_by_distributor(DISTRIBUTOR, 0, true)
+        .succeed((0, true));
+    supply_chain.ship_by_distributor(DISTRIBUTOR, 0).succeed(0);
+    supply_chain
+        .dispute_by_retailer(RETAILER, 0, REASON)
+        .succeed(0);
+
+    // The seller's share is split like on a receipt of the item.
+    supply_chain.resolve(ARBITER, 0, SELLER_SHARE).succeed((
+        0,
+        ItemState {
+            state: ItemEventState::Received,
+            by: Role::Retailer,
+        },
+    ));
+    fungible_token
+        .balance(RETAILER)
+        .contains(ITEM_PRICE - SELLER_AMOUNT);
+    fungible_token
+        .balance(DISTRIBUTOR)
+        .contains(SELLER_AMOUNT - FEE_AMOUNT - ROYALTY_AMOUNT);
+    fungible_token
+        .balance(PRODUCER)
+        .contains(ITEM_PRICE + ROYALTY_AMOUNT);
+    fungible_token.balance(TREASURY).contains(FEE_AMOUNT);
+    fungible_token.balance(supply_chain.actor_id()).contains(0);
+    supply_chain
+        .state()
+        .collected_fees()
+        .eq([(fungible_token.actor_id(), FEE_AMOUNT)].into());
+}
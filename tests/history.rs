@@ -0,0 +1,157 @@
+use utils::{prelude::*, FungibleToken, NonFungibleToken};
+
+pub mod utils;
+
+const NEW_ITEM_PRICE: u128 = ITEM_PRICE * 2;
+const DELIVERY_TIME_IN_BLOCKS: u32 = (DELIVERY_TIME / 1000) as _;
+
+#[test]
+fn item_history() {
+    let system = utils::initialize_system();
+
+    let mut non_fungible_token = NonFungibleToken::initialize(&system);
+    let mut fungible_token = FungibleToken::initialize(&system);
+    let mut supply_chain = SupplyChain::initialize(
+        &system,
+        fungible_token.actor_id(),
+        non_fungible_token.actor_id(),
+    );
+
+    non_fungible_token.add_minter(supply_chain.actor_id());
+    fungible_token.mint(DISTRIBUTOR, NEW_ITEM_PRICE);
+    fungible_token.approve(DISTRIBUTOR, supply_chain.actor_id(), NEW_ITEM_PRICE);
+
+    supply_chain.state().item_history(0).eq(None);
+
+    supply_chain.produce(PRODUCER).succeed(0);
+    non_fungible_token.approve(PRODUCER, supply_chain.actor_id(), 0);
+    supply_chain
+        .put_up_for_sale_by_producer(PRODUCER, 0, ITEM_PRICE)
+        .succeed(0);
+    supply_chain
+        .update_price_by_producer(PRODUCER, 0, NEW_ITEM_PRICE)
+        .succeed(0);
+    supply_chain
+        .purchase_by_distributor(DISTRIBUTOR, 0, DELIVERY_TIME)
+        .succeed(0);
+    supply_chain
+        .approve_by_producer(PRODUCER, 0, true)
+        .succeed((0, true));
+    supply_chain.ship_by_producer(PRODUCER, 0).succeed(0);
+    system.spend_blocks(DELIVERY_TIME_IN_BLOCKS);
+    supply_chain
+        .receive_by_distributor(DISTRIBUTOR, 0)
+        .succeed(0);
+    supply_chain.process(DISTRIBUTOR, 0).succeed(0);
+    supply_chain.package(DISTRIBUTOR, 0).succeed(0);
+
+    let history = supply_chain.state().item_history(0).0.unwrap();
+
+    assert_eq!(
+        history
+            .iter()
+            .map(|record| (
+                record.state.state,
+                record.state.by,
+                record.actor,
+                record.price
+            ))
+            .collect::<Vec<_>>(),
+        [
+            (ItemEventState::Produced, Role::Producer, PRODUCER, 0),
+            (
+                ItemEventState::ForSale,
+                Role::Producer,
+                PRODUCER,
+                ITEM_PRICE
+            ),
+            (
+                ItemEventState::Purchased,
+                Role::Distributor,
+                DISTRIBUTOR,
+                NEW_ITEM_PRICE
+            ),
+            (
+                ItemEventState::Approved,
+                Role::Producer,
+                PRODUCER,
+                NEW_ITEM_PRICE
+            ),
+            (
+                ItemEventState::Shipped,
+                Role::Producer,
+                PRODUCER,
+                NEW_ITEM_PRICE
+            ),
+            (
+                ItemEventState::Received,
+                Role::Distributor,
+                DISTRIBUTOR,
+                NEW_ITEM_PRICE
+            ),
+            (
+                ItemEventState::Processed,
+                Role::Distributor,
+                DISTRIBUTOR,
+                NEW_ITEM_PRICE
+            ),
+            (
+                ItemEventState::Packaged,
+                Role::Distributor,
+                DISTRIBUTOR,
+                NEW_ITEM_PRICE
+            ),
+        ]
+        .map(|(state, by, actor, price)| (state, by, ActorId::from(actor), price))
+    );
+
+    assert!(history
+        .windows(2)
+        .all(|records| records[0].timestamp <= records[1].timestamp
+            && records[0].block_height <= records[1].block_height));
+
+    let (shipped, received) = (history[4], history[5]);
+
+    assert_eq!(
+        received.block_height - shipped.block_height,
+        DELIVERY_TIME_IN_BLOCKS
+    );
+    assert_eq!(received.timestamp - shipped.timestamp, DELIVERY_TIME);
+}
+
+#[test]
+fn history_limit() {
+    let system = utils::initialize_system();
+
+    let mut non_fungible_token = NonFungibleToken::initialize(&system);
+    let fungible_token = FungibleToken::initialize(&system);
+    let mut supply_chain = SupplyChain::initialize(
+        &system,
+        fungible_token.actor_id(),
+        non_fungible_token.actor_id(),
+    );
+
+    non_fungible_token.add_minter(supply_chain.actor_id());
+
+    supply_chain.produce(PRODUCER).succeed(0);
+
+    for _ in 0..MAX_HISTORY_LENGTH / 2 {
+        non_fungible_token.approve(PRODUCER, supply_chain.actor_id(), 0);
+        supply_chain
+            .put_up_for_sale_by_producer(PRODUCER, 0, ITEM_PRICE)
+            .succeed(0);
+        supply_chain
+            .withdraw_from_sale_by_producer(PRODUCER, 0)
+            .succeed(0);
+    }
+
+    let history = supply_chain.state().item_history(0).0.unwrap();
+
+    assert_eq!(history.len(), MAX_HISTORY_LENGTH);
+    // The oldest record (the production) has been dropped.
+    assert_eq!(history[0].state.state, ItemEventState::ForSale);
+    assert_eq!(
+        history[MAX_HISTORY_LENGTH - 1].state.state,
+        ItemEventState::Produced
+    );
+}
@@ -0,0 +1,380 @@
+use gtest::System;
+use utils::{prelude::*, FungibleToken, NonFungibleToken};
+
+pub mod utils;
+
+const ROYALTY: u16 = 1000;
+const ROYALTY_AMOUNT: u128 = ITEM_PRICE * ROYALTY as u128 / MAX_BASIS_POINTS as u128;
+const RESALE_ROYALTY_AMOUNT: u128 = ITEM_PRICE * RESALE_ROYALTY as u128 / MAX_BASIS_POINTS as u128;
+const BUYER: u64 = 15;
+const TTL: u64 = 10000;
+const TTL_IN_BLOCKS: u32 = (TTL / 1000) as _;
+/// An actor that never replies, so an action paying with it stays in flight.
+const SILENT_CURRENCY: u64 = 16;
+
+#[test]
+fn item_lock() {
+    let system = utils::initialize_system();
+
+    let mut non_fungible_token = NonFungibleToken::initialize(&system);
+    let mut fungible_token = FungibleToken::initialize(&system);
+    let mut supply_chain = SupplyChain::initialize(
+        &system,
+        fungible_token.actor_id(),
+        non_fungible_token.actor_id(),
+    );
+
+    non_fungible_token.add_minter(supply_chain.actor_id());
+
+    for from in [DISTRIBUTOR, RETAILER] {
+        fungible_token.mint(from, ITEM_PRICE);
+        fungible_token.approve(from, supply_chain.actor_id(), ITEM_PRICE);
+    }
+
+    supply_chain
+        .produce_with_royalty(PRODUCER, ROYALTY)
+        .succeed(0);
+    non_fungible_token.approve(PRODUCER, supply_chain.actor_id(), 0);
+    supply_chain
+        .put_up_for_sale_by_producer(PRODUCER, 0, ITEM_PRICE)
+        .succeed(0);
+    supply_chain
+        .purchase_by_distributor(DISTRIBUTOR, 0, DELIVERY_TIME)
+        .succeed(0);
+    supply_chain
+        .approve_by_producer(PRODUCER, 0, true)
+        .succeed((0, true));
+    supply_chain.ship_by_producer(PRODUCER, 0).succeed(0);
+    supply_chain
+        .receive_by_distributor(DISTRIBUTOR, 0)
+        .succeed(0);
+    supply_chain.process(DISTRIBUTOR, 0).succeed(0);
+    supply_chain.package(DISTRIBUTOR, 0).succeed(0);
+    non_fungible_token.approve(DISTRIBUTOR, supply_chain.actor_id(), 0);
+    supply_chain
+        .put_up_for_sale_by_distributor(DISTRIBUTOR, 0, ITEM_PRICE)
+        .succeed(0);
+    supply_chain
+        .purchase_by_retailer(RETAILER, 0, DELIVERY_TIME)
+        .succeed(0);
+    supply_chain
+        .approve_by_distributor(DISTRIBUTOR, 0, true)
+        .succeed((0, true));
+    supply_chain.ship_by_distributor(DISTRIBUTOR, 0).succeed(0);
+    supply_chain.receive_by_retailer(RETAILER, 0).succeed(0);
+    non_fungible_token.approve(RETAILER, supply_chain.actor_id(), 0);
+    supply_chain
+        .put_up_for_sale_by_retailer(RETAILER, 0, ITEM_PRICE)
+        .succeed(0);
+
+    // A failed action without executed steps doesn't lock the item.
+    supply_chain
+        .purchase_by_consumer(FOREIGN_USER, 0)
+        .failed(Error::FTTransferFailed);
+    supply_chain
+        .update_price_by_retailer(RETAILER, 0, ITEM_PRICE)
+        .succeed(0);
+
+    // The consumer has enough fungible tokens only for the retailer's share,
+    // so the purchase fails on the royalty transfer after the retailer has
+    // been paid, and the item stays locked.
+    fungible_token.mint(CONSUMER, ITEM_PRICE - ROYALTY_AMOUNT);
+    fungible_token.approve(CONSUMER, supply_chain.actor_id(), ITEM_PRICE);
+    supply_chain
+        .purchase_by_consumer(CONSUMER, 0)
+        .failed(Error::FTTransferFailed);
+
+    // Conflicting actions sent between attempts of the purchase are rejected.
+    supply_chain
+        .update_price_by_retailer(RETAILER, 0, ITEM_PRICE * 2)
+        .failed(Error::ItemLocked);
+    supply_chain
+        .withdraw_from_sale_by_retailer(RETAILER, 0)
+        .failed(Error::ItemLocked);
+    fungible_token.mint(FOREIGN_USER, ITEM_PRICE);
+    fungible_token.approve(FOREIGN_USER, supply_chain.actor_id(), ITEM_PRICE);
+    supply_chain
+        .purchase_by_consumer(FOREIGN_USER, 0)
+        .failed(Error::ItemLocked);
+    fungible_token.balance(FOREIGN_USER).contains(ITEM_PRICE);
+
+    let purchase = Action::new(InnerAction::Consumer(ConsumerAction::Purchase(0.into())));
+
+    // The lock is released once the purchase is aborted.
+    fungible_token.approve(RETAILER, supply_chain.actor_id(), ITEM_PRICE);
+    supply_chain
+        .send_action(CONSUMER, purchase.clone().to_abort())
+        .succeed(Event::TransactionAborted(CachedAction::Purchase(0.into())));
+    supply_chain
+        .update_price_by_retailer(RETAILER, 0, ITEM_PRICE)
+        .succeed(0);
+
+    // ...or completed.
+    supply_chain
+        .purchase_by_consumer(CONSUMER, 0)
+        .failed(Error::FTTransferFailed);
+    supply_chain
+        .purchase_by_consumer(FOREIGN_USER, 0)
+        .failed(Error::ItemLocked);
+    fungible_token.mint(CONSUMER, ROYALTY_AMOUNT);
+    supply_chain
+        .send_action(CONSUMER, purchase.clone().to_retry())
+        .succeed(Event::Item {
+            item_id: 0.into(),
+            item_state: ItemState {
+                state: ItemEventState::Purchased,
+                by: Role::Consumer,
+            },
+        });
+    fungible_token.balance(CONSUMER).contains(0);
+    non_fungible_token.approve(CONSUMER, supply_chain.actor_id(), 0);
+    supply_chain
+        .put_up_for_sale_by_consumer(CONSUMER, 0, ITEM_PRICE)
+        .succeed(0);
+
+    // A lock of an expired action doesn't block other actions.
+    supply_chain
+        .set_transaction_ttl(FOREIGN_USER, Some(TTL))
+        .succeed(Some(TTL));
+    fungible_token.mint(BUYER, ITEM_PRICE - RESALE_ROYALTY_AMOUNT);
+    fungible_token.approve(BUYER, supply_chain.actor_id(), ITEM_PRICE);
+    supply_chain
+        .purchase_by_consumer(BUYER, 0)
+        .failed(Error::FTTransferFailed);
+    supply_chain
+        .update_price_by_consumer(CONSUMER, 0, ITEM_PRICE)
+        .failed(Error::ItemLocked);
+    system.spend_blocks(TTL_IN_BLOCKS);
+    supply_chain
+        .update_price_by_consumer(CONSUMER, 0, ITEM_PRICE)
+        .succeed(0);
+
+    // Purging the expired action releases its lock as well.
+    supply_chain
+        .send_action(BUYER, purchase.to_purge())
+        .failed(Error::TxCacheError(
+            TransactionCacheError::TransactionNotFound,
+        ));
+    supply_chain
+        .withdraw_from_sale_by_consumer(CONSUMER, 0)
+        .succeed(0);
+}
+
+fn initialize_with_silent_currency<'a>(
+    system: &'a System,
+    fungible_token: ActorId,
+    non_fungible_token: ActorId,
+) -> SupplyChain<'a> {
+    SupplyChain::initialize_custom(
+        system,
+        Initialize {
+            producers: vec![PRODUCER.into()],
+            distributors: vec![DISTRIBUTOR.into()],
+            retailers: vec![RETAILER.into()],
+
+            fungible_token,
+            non_fungible_token,
+            payment_mode: Default::default(),
+            currencies: vec![SILENT_CURRENCY.into()],
+
+            penalty_schedule: Default::default(),
+            resale_royalty: Default::default(),
+            platform_fee: None,
+            transaction_ttl: None,
+
+            arbiter: ARBITER.into(),
+        },
+    )
+    .succeed()
+}
+
+#[test]
+fn concurrent_purchases() {
+    let system = utils::initialize_system();
+
+    let mut non_fungible_token = NonFungibleToken::initialize(&system);
+    let fungible_token = FungibleToken::initialize(&system);
+    let mut supply_chain = initialize_with_silent_currency(
+        &system,
+        fungible_token.actor_id(),
+        non_fungible_token.actor_id(),
+    );
+
+    non_fungible_token.add_minter(supply_chain.actor_id());
+
+    supply_chain.produce(PRODUCER).succeed(0);
+    non_fungible_token.approve(PRODUCER, supply_chain.actor_id(), 0);
+    supply_chain
+        .put_up_for_sale_by_producer_with_currency(
+            PRODUCER,
+            0,
+            ITEM_PRICE,
+            Some(SILENT_CURRENCY.into()),
+        )
+        .succeed(0);
+
+    // The first purchase awaits a reply for its payment...
+    let purchase = Action::new(InnerAction::Distributor(DistributorAction::Purchase {
+        item_id: 0.into(),
+        delivery_time: DELIVERY_TIME,
+        penalty_schedule: None,
+        approval_time: None,
+    }));
+    let result = supply_chain.send_action(DISTRIBUTOR, purchase.clone());
+
+    assert!(!result.result.main_failed());
+    supply_chain
+        .state()
+        .cached_actions(DISTRIBUTOR)
+        .eq(vec![CachedAction::Purchase(0.into())]);
+
+    // ...while an identical one is rejected instead of paying again...
+    supply_chain
+        .send_action(DISTRIBUTOR, purchase)
+        .failed(Error::TxCacheError(
+            TransactionCacheError::TransactionInProgress,
+        ));
+
+    // ...and the item stays locked for other actions.
+    supply_chain
+        .update_price_by_producer(PRODUCER, 0, ITEM_PRICE * 2)
+        .failed(Error::ItemLocked);
+    supply_chain
+        .withdraw_from_sale_by_producer(PRODUCER, 0)
+        .failed(Error::ItemLocked);
+}
+
+#[test]
+fn abort_in_flight() {
+    let system = utils::initialize_system();
+
+    let mut non_fungible_token = NonFungibleToken::initialize(&system);
+    let fungible_token = FungibleToken::initialize(&system);
+    let mut supply_chain = initialize_with_silent_currency(
+        &system,
+        fungible_token.actor_id(),
+        non_fungible_token.actor_id(),
+    );
+
+    non_fungible_token.add_minter(supply_chain.actor_id());
+
+    supply_chain.produce(PRODUCER).succeed(0);
+    non_fungible_token.approve(PRODUCER, supply_chain.actor_id(), 0);
+    supply_chain
+        .put_up_for_sale_by_producer_with_currency(
+            PRODUCER,
+            0,
+            ITEM_PRICE,
+            Some(SILENT_CURRENCY.into()),
+        )
+        .succeed(0);
+
+    let purchase = Action::new(InnerAction::Distributor(DistributorAction::Purchase {
+        item_id: 0.into(),
+        delivery_time: DELIVERY_TIME,
+        penalty_schedule: None,
+        approval_time: None,
+    }));
+    let result = supply_chain.send_action(DISTRIBUTOR, purchase.clone());
+
+    assert!(!result.result.main_failed());
+
+    // Compensating steps would race the remaining steps of the purchase, so
+    // it can't be aborted while it awaits a reply...
+    supply_chain
+        .send_action(DISTRIBUTOR, purchase.to_abort())
+        .failed(Error::TxCacheError(
+            TransactionCacheError::TransactionInProgress,
+        ));
+
+    // ...and stays cached with its item locked.
+    supply_chain
+        .state()
+        .cached_actions(DISTRIBUTOR)
+        .eq(vec![CachedAction::Purchase(0.into())]);
+    supply_chain
+        .update_price_by_producer(PRODUCER, 0, ITEM_PRICE * 2)
+        .failed(Error::ItemLocked);
+
+    // Only the admin can remove it.
+    supply_chain
+        .send_action(
+            FOREIGN_USER,
+            Action::new(InnerAction::Admin(AdminAction::PurgeTransaction {
+                actor: DISTRIBUTOR.into(),
+                action: CachedAction::Purchase(0.into()),
+            })),
+        )
+        .succeed(Event::TransactionPurged(CachedAction::Purchase(0.into())));
+    supply_chain
+        .update_price_by_producer(PRODUCER, 0, ITEM_PRICE * 2)
+        .succeed(0);
+}
+
+#[test]
+fn lot_lock() {
+    let system = utils::initialize_system();
+
+    let mut non_fungible_token = NonFungibleToken::initialize(&system);
+    let fungible_token = FungibleToken::initialize(&system);
+    let mut supply_chain = initialize_with_silent_currency(
+        &system,
+        fungible_token.actor_id(),
+        non_fungible_token.actor_id(),
+    );
+
+    non_fungible_token.add_minter(supply_chain.actor_id());
+
+    supply_chain.produce_batch(PRODUCER, 2).succeed(vec![0, 1]);
+    supply_chain
+        .lot_action(PRODUCER, LotAction::Create(vec![0.into(), 1.into()]))
+        .succeed((
+            0,
+            vec![0, 1],
+            ItemState {
+                state: ItemEventState::Produced,
+                by: Role::Producer,
+            },
+        ));
+
+    for item_id in [0, 1] {
+        non_fungible_token.approve(PRODUCER, supply_chain.actor_id(), item_id);
+    }
+
+    supply_chain
+        .lot_action(
+            PRODUCER,
+            LotAction::PutUpForSale {
+                lot_id: 0,
+                price: ITEM_PRICE,
+                currency: Some(SILENT_CURRENCY.into()),
+            },
+        )
+        .succeed((
+            0,
+            vec![0, 1],
+            ItemState {
+                state: ItemEventState::ForSale,
+                by: Role::Producer,
+            },
+        ));
+
+    // A purchase of the lot awaits a reply for its payment and locks all items
+    // of the lot.
+    let result = supply_chain.lot_action(
+        DISTRIBUTOR,
+        LotAction::Purchase {
+            lot_id: 0,
+            delivery_time: DELIVERY_TIME,
+            penalty_schedule: None,
+        },
+    );
+
+    assert!(!result.result.main_failed());
+    supply_chain
+        .lot_action(PRODUCER, LotAction::WithdrawFromSale(0))
+        .failed(Error::ItemLocked);
+    supply_chain
+        .recall(PRODUCER, &[1], "")
+        .failed(Error::ItemLocked);
+}
@@ -0,0 +1,109 @@
+use utils::{prelude::*, FungibleToken, NonFungibleToken};
+
+pub mod utils;
+
+fn item_ids(page: &ItemsPage) -> Vec<ItemId> {
+    page.items.iter().map(|item| item.0).collect()
+}
+
+#[test]
+fn items_page() {
+    let system = utils::initialize_system();
+
+    let fungible_token = FungibleToken::initialize(&system);
+    let mut non_fungible_token = NonFungibleToken::initialize(&system);
+    let mut supply_chain = SupplyChain::initialize(
+        &system,
+        fungible_token.actor_id(),
+        non_fungible_token.actor_id(),
+    );
+
+    non_fungible_token.add_minter(supply_chain.actor_id());
+
+    for item_id in 0..5 {
+        supply_chain.produce(PRODUCER).succeed(item_id);
+    }
+
+    for item_id in 1..4 {
+        non_fungible_token.approve(PRODUCER, supply_chain.actor_id(), item_id);
+        supply_chain
+            .put_up_for_sale_by_producer(PRODUCER, item_id, item_id * 100)
+            .succeed(item_id);
+    }
+
+    let page = |filter, offset, limit| {
+        let page = supply_chain.state().items_page(filter, offset, limit).0;
+
+        (item_ids(&page), page.total)
+    };
+    let ids = |ids: &[u128]| ids.iter().copied().map(ItemId::from).collect::<Vec<_>>();
+
+    assert_eq!(page(Default::default(), 0, 2), (ids(&[0, 1]), 5));
+    assert_eq!(page(Default::default(), 2, 2), (ids(&[2, 3]), 5));
+    assert_eq!(page(Default::default(), 4, 2), (ids(&[4]), 5));
+    assert_eq!(page(Default::default(), 5, 2), (ids(&[]), 5));
+
+    let for_sale = ItemFilter {
+        state: Some(ItemEventState::ForSale),
+        ..Default::default()
+    };
+
+    assert_eq!(page(for_sale, 0, 10), (ids(&[1, 2, 3]), 3));
+    assert_eq!(
+        page(
+            ItemFilter {
+                min_price: Some(150),
+                ..for_sale
+            },
+            0,
+            10
+        ),
+        (ids(&[2, 3]), 2)
+    );
+    assert_eq!(
+        page(
+            ItemFilter {
+                max_price: Some(250),
+                ..for_sale
+            },
+            1,
+            10
+        ),
+        (ids(&[2]), 2)
+    );
+    assert_eq!(
+        page(
+            ItemFilter {
+                state: Some(ItemEventState::Produced),
+                by: Some(Role::Producer),
+                producer: Some(PRODUCER.into()),
+                ..Default::default()
+            },
+            0,
+            10
+        ),
+        (ids(&[0, 4]), 2)
+    );
+    assert_eq!(
+        page(
+            ItemFilter {
+                producer: Some(FOREIGN_USER.into()),
+                ..Default::default()
+            },
+            0,
+            10
+        ),
+        (ids(&[]), 0)
+    );
+    assert_eq!(
+        page(
+            ItemFilter {
+                distributor: Some(DISTRIBUTOR.into()),
+                ..Default::default()
+            },
+            0,
+            10
+        ),
+        (ids(&[]), 0)
+    );
+}
@@ -0,0 +1,346 @@
+use utils::{prelude::*, FungibleToken, NonFungibleToken};
+
+pub mod utils;
+
+const LOT: [u128; 3] = [0, 1, 2];
+const LOT_PRICE: u128 = ITEM_PRICE * LOT.len() as u128;
+
+fn item_ids(item_ids: &[u128]) -> Vec<ItemId> {
+    item_ids.iter().copied().map(Into::into).collect()
+}
+
+#[test]
+fn lot() {
+    let system = utils::initialize_system();
+
+    let mut non_fungible_token = NonFungibleToken::initialize(&system);
+    let mut fungible_token = FungibleToken::initialize(&system);
+    let mut supply_chain = SupplyChain::initialize(
+        &system,
+        fungible_token.actor_id(),
+        non_fungible_token.actor_id(),
+    );
+
+    non_fungible_token.add_minter(supply_chain.actor_id());
+    fungible_token.mint(DISTRIBUTOR, LOT_PRICE);
+    fungible_token.approve(DISTRIBUTOR, supply_chain.actor_id(), LOT_PRICE);
+
+    supply_chain
+        .produce_batch(PRODUCER, LOT.len() + 1)
+        .succeed(vec![0, 1, 2, 3]);
+
+    for (item_ids, error) in [
+        (vec![], Error::InvalidLot),
+        (vec![0, 0], Error::InvalidLot),
+        (vec![0, 9], Error::ItemNotFound),
+    ] {
+        supply_chain
+            .lot_action(PRODUCER, LotAction::Create(self::item_ids(&item_ids)))
+            .failed(error);
+    }
+
+    supply_chain
+        .lot_action(FOREIGN_USER, LotAction::Create(item_ids(&LOT)))
+        .failed(Error::AccessRestricted);
+
+    let produced = ItemState {
+        state: ItemEventState::Produced,
+        by: Role::Producer,
+    };
+
+    supply_chain
+        .lot_action(PRODUCER, LotAction::Create(item_ids(&LOT)))
+        .succeed((0, LOT.into(), produced));
+    supply_chain.state().lot_info(0).eq(Some(LotInfo {
+        item_ids: item_ids(&LOT),
+        state: produced,
+    }));
+    assert_eq!(supply_chain.state().item_info(0).0.unwrap().lot_id, Some(0));
+
+    // Items of a lot can't be used separately.
+    supply_chain
+        .lot_action(PRODUCER, LotAction::Create(item_ids(&[2, 3])))
+        .failed(Error::ItemInLot);
+    supply_chain
+        .put_up_for_sale_by_producer(PRODUCER, 0, ITEM_PRICE)
+        .failed(Error::ItemInLot);
+
+    for item_id in LOT {
+        non_fungible_token.approve(PRODUCER, supply_chain.actor_id(), item_id);
+    }
+
+    let for_sale = ItemState {
+        state: ItemEventState::ForSale,
+        by: Role::Producer,
+    };
+
+    supply_chain
+        .lot_action(
+            PRODUCER,
+            LotAction::PutUpForSale {
+                lot_id: 0,
+                price: ITEM_PRICE,
+                currency: None,
+            },
+        )
+        .succeed((0, LOT.into(), for_sale));
+    supply_chain
+        .lot_action(PRODUCER, LotAction::WithdrawFromSale(0))
+        .succeed((0, LOT.into(), produced));
+
+    for item_id in LOT {
+        non_fungible_token
+            .meta_state()
+            .owner_id(item_id)
+            .eq(PRODUCER.into());
+        non_fungible_token.approve(PRODUCER, supply_chain.actor_id(), item_id);
+    }
+
+    supply_chain
+        .lot_action(
+            PRODUCER,
+            LotAction::PutUpForSale {
+                lot_id: 0,
+                price: ITEM_PRICE,
+                currency: None,
+            },
+        )
+        .succeed((0, LOT.into(), for_sale));
+
+    let purchase = LotAction::Purchase {
+        lot_id: 0,
+        delivery_time: DELIVERY_TIME,
+        penalty_schedule: None,
+    };
+
+    supply_chain
+        .lot_action(RETAILER, purchase.clone())
+        .failed(Error::AccessRestricted);
+    supply_chain.lot_action(DISTRIBUTOR, purchase).succeed((
+        0,
+        LOT.into(),
+        ItemState {
+            state: ItemEventState::Purchased,
+            by: Role::Distributor,
+        },
+    ));
+    fungible_token
+        .balance(supply_chain.actor_id())
+        .contains(LOT_PRICE);
+
+    supply_chain
+        .lot_action(
+            PRODUCER,
+            LotAction::Approve {
+                lot_id: 0,
+                approve: true,
+            },
+        )
+        .succeed((
+            0,
+            LOT.into(),
+            ItemState {
+                state: ItemEventState::Approved,
+                by: Role::Producer,
+            },
+        ));
+    supply_chain
+        .lot_action(DISTRIBUTOR, LotAction::Ship(0))
+        .failed(Error::AccessRestricted);
+    supply_chain
+        .lot_action(PRODUCER, LotAction::Ship(0))
+        .succeed((
+            0,
+            LOT.into(),
+            ItemState {
+                state: ItemEventState::Shipped,
+                by: Role::Producer,
+            },
+        ));
+
+    let received = ItemState {
+        state: ItemEventState::Received,
+        by: Role::Distributor,
+    };
+
+    supply_chain
+        .lot_action(DISTRIBUTOR, LotAction::Receive(0))
+        .succeed((0, LOT.into(), received));
+    fungible_token.balance(PRODUCER).contains(LOT_PRICE);
+    fungible_token.balance(supply_chain.actor_id()).contains(0);
+
+    for item_id in LOT {
+        supply_chain.state().item_state(item_id).eq(Some(received));
+        non_fungible_token
+            .meta_state()
+            .owner_id(item_id)
+            .eq(DISTRIBUTOR.into());
+    }
+
+    supply_chain
+        .split_lot(PRODUCER, 0)
+        .failed(Error::AccessRestricted);
+    supply_chain
+        .split_lot(DISTRIBUTOR, 0)
+        .succeed((0, LOT.into()));
+    supply_chain.state().lot_info(0).eq(None);
+    supply_chain
+        .split_lot(DISTRIBUTOR, 0)
+        .failed(Error::LotNotFound);
+
+    supply_chain.process(DISTRIBUTOR, 0).succeed(0);
+}
+
+#[test]
+fn lot_delivery_failure() {
+    const BIG_DELAY: u32 = (DELIVERY_TIME / 1000 * 2) as _;
+
+    let system = utils::initialize_system();
+
+    let mut non_fungible_token = NonFungibleToken::initialize(&system);
+    let mut fungible_token = FungibleToken::initialize(&system);
+    let mut supply_chain = SupplyChain::initialize(
+        &system,
+        fungible_token.actor_id(),
+        non_fungible_token.actor_id(),
+    );
+
+    non_fungible_token.add_minter(supply_chain.actor_id());
+    fungible_token.mint(DISTRIBUTOR, LOT_PRICE);
+    fungible_token.approve(DISTRIBUTOR, supply_chain.actor_id(), LOT_PRICE);
+
+    supply_chain
+        .produce_batch(PRODUCER, LOT.len())
+        .succeed(LOT.into());
+
+    let produced = ItemState {
+        state: ItemEventState::Produced,
+        by: Role::Producer,
+    };
+
+    supply_chain
+        .lot_action(PRODUCER, LotAction::Create(item_ids(&LOT)))
+        .succeed((0, LOT.into(), produced));
+
+    for item_id in LOT {
+        non_fungible_token.approve(PRODUCER, supply_chain.actor_id(), item_id);
+    }
+
+    let put_up_for_sale = LotAction::PutUpForSale {
+        lot_id: 0,
+        price: ITEM_PRICE,
+        currency: None,
+    };
+
+    supply_chain
+        .lot_action(PRODUCER, put_up_for_sale.clone())
+        .succeed((
+            0,
+            LOT.into(),
+            ItemState {
+                state: ItemEventState::ForSale,
+                by: Role::Producer,
+            },
+        ));
+    supply_chain
+        .lot_action(
+            DISTRIBUTOR,
+            LotAction::Purchase {
+                lot_id: 0,
+                delivery_time: DELIVERY_TIME,
+                penalty_schedule: None,
+            },
+        )
+        .succeed((
+            0,
+            LOT.into(),
+            ItemState {
+                state: ItemEventState::Purchased,
+                by: Role::Distributor,
+            },
+        ));
+    supply_chain
+        .lot_action(
+            PRODUCER,
+            LotAction::Approve {
+                lot_id: 0,
+                approve: true,
+            },
+        )
+        .succeed((
+            0,
+            LOT.into(),
+            ItemState {
+                state: ItemEventState::Approved,
+                by: Role::Producer,
+            },
+        ));
+    supply_chain
+        .lot_action(PRODUCER, LotAction::Ship(0))
+        .succeed((
+            0,
+            LOT.into(),
+            ItemState {
+                state: ItemEventState::Shipped,
+                by: Role::Producer,
+            },
+        ));
+
+    let fail_lot_delivery = Action::new(InnerAction::Deadline(DeadlineAction::FailLotDelivery(0)));
+    let delivery_failed = Event::Lot {
+        lot_id: 0,
+        item_ids: item_ids(&LOT),
+        item_state: ItemState {
+            state: ItemEventState::DeliveryFailed,
+            by: Role::Producer,
+        },
+    };
+
+    supply_chain
+        .send_action(FOREIGN_USER, fail_lot_delivery.clone())
+        .failed(Error::DeadlineNotReached);
+    system.spend_blocks(BIG_DELAY);
+    supply_chain
+        .send_action(FOREIGN_USER, fail_lot_delivery)
+        .succeed(delivery_failed);
+
+    // The buyer is refunded, and the lot returns to the seller.
+    fungible_token.balance(DISTRIBUTOR).contains(LOT_PRICE);
+    fungible_token.balance(supply_chain.actor_id()).contains(0);
+    supply_chain.state().lot_info(0).eq(Some(LotInfo {
+        item_ids: item_ids(&LOT),
+        state: produced,
+    }));
+
+    for item_id in LOT {
+        supply_chain.state().item_state(item_id).eq(Some(produced));
+        non_fungible_token
+            .meta_state()
+            .owner_id(item_id)
+            .eq(PRODUCER.into());
+        non_fungible_token.approve(PRODUCER, supply_chain.actor_id(), item_id);
+    }
+
+    supply_chain
+        .lot_action(DISTRIBUTOR, LotAction::Receive(0))
+        .failed(Error::UnexpectedItemState);
+    supply_chain.lot_action(PRODUCER, put_up_for_sale).succeed((
+        0,
+        LOT.into(),
+        ItemState {
+            state: ItemEventState::ForSale,
+            by: Role::Producer,
+        },
+    ));
+
+    // The scheduled deadline action arrives after the lot was returned and
+    // changes nothing.
+    system.spend_blocks(1);
+    supply_chain.state().lot_info(0).eq(Some(LotInfo {
+        item_ids: item_ids(&LOT),
+        state: ItemState {
+            state: ItemEventState::ForSale,
+            by: Role::Producer,
+        },
+    }));
+}
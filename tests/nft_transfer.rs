@@ -97,13 +97,21 @@ fn nft_transfer() {
         producer: PRODUCER.into(),
         distributor: DISTRIBUTOR.into(),
         retailer: RETAILER.into(),
+        consumer: CONSUMER.into(),
 
         state: ItemState {
             state: ItemEventState::Purchased,
             by: Role::Consumer,
         },
         price: ITEM_PRICE,
+        currency: fungible_token.actor_id(),
         delivery_time: DELIVERY_TIME,
+        penalty_schedule: Default::default(),
+        approval_deadline: None,
+        royalty: 0,
+        dispute_reason: None,
+        lot_id: None,
+        recall_reason: None,
     }));
     non_fungible_token
         .meta_state()
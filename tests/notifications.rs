@@ -0,0 +1,169 @@
+use utils::{prelude::*, FungibleToken, NonFungibleToken};
+
+pub mod utils;
+
+const ALL_EVENTS_SUBSCRIBER: u64 = 21;
+const ITEM_SUBSCRIBER: u64 = 22;
+const ROLE_SUBSCRIBER: u64 = 23;
+
+fn item_event(item_id: u128, state: ItemEventState, by: Role) -> Event {
+    Event::Item {
+        item_id: item_id.into(),
+        item_state: ItemState { state, by },
+    }
+}
+
+#[test]
+fn notifications() {
+    let system = utils::initialize_system();
+
+    let mut non_fungible_token = NonFungibleToken::initialize(&system);
+    let mut fungible_token = FungibleToken::initialize(&system);
+    let mut supply_chain = SupplyChain::initialize(
+        &system,
+        fungible_token.actor_id(),
+        non_fungible_token.actor_id(),
+    );
+
+    non_fungible_token.add_minter(supply_chain.actor_id());
+    fungible_token.mint(DISTRIBUTOR, ITEM_PRICE);
+    fungible_token.approve(DISTRIBUTOR, supply_chain.actor_id(), ITEM_PRICE);
+
+    let item_filter = SubscriptionFilter {
+        item_id: Some(1.into()),
+        ..Default::default()
+    };
+    let role_filter = SubscriptionFilter {
+        role: Some(Role::Distributor),
+        ..Default::default()
+    };
+
+    supply_chain
+        .subscribe(ALL_EVENTS_SUBSCRIBER, Default::default())
+        .failed(Error::AccessRestricted);
+
+    for subscriber in [ALL_EVENTS_SUBSCRIBER, ITEM_SUBSCRIBER, ROLE_SUBSCRIBER] {
+        supply_chain
+            .add_participant(FOREIGN_USER, Role::Retailer, subscriber)
+            .succeed((Role::Retailer, subscriber));
+    }
+
+    supply_chain
+        .unsubscribe(ALL_EVENTS_SUBSCRIBER)
+        .failed(Error::SubscriberNotFound);
+    supply_chain
+        .subscribe(ALL_EVENTS_SUBSCRIBER, Default::default())
+        .succeed((ALL_EVENTS_SUBSCRIBER, Default::default()));
+    supply_chain
+        .subscribe(ITEM_SUBSCRIBER, item_filter)
+        .succeed((ITEM_SUBSCRIBER, item_filter));
+    supply_chain
+        .subscribe(ROLE_SUBSCRIBER, role_filter)
+        .succeed((ROLE_SUBSCRIBER, role_filter));
+    supply_chain.state().subscribers().eq([
+        (ActorId::from(ALL_EVENTS_SUBSCRIBER), Default::default()),
+        (ITEM_SUBSCRIBER.into(), item_filter),
+        (ROLE_SUBSCRIBER.into(), role_filter),
+    ]
+    .into());
+
+    let result = supply_chain.produce(PRODUCER);
+    let event = item_event(0, ItemEventState::Produced, Role::Producer);
+
+    assert!(result.is_sent_to(ALL_EVENTS_SUBSCRIBER, &event));
+    assert!(!result.is_sent_to(ITEM_SUBSCRIBER, &event));
+    assert!(!result.is_sent_to(ROLE_SUBSCRIBER, &event));
+    result.succeed(0);
+
+    let result = supply_chain.produce(PRODUCER);
+    let event = item_event(1, ItemEventState::Produced, Role::Producer);
+
+    assert!(result.is_sent_to(ALL_EVENTS_SUBSCRIBER, &event));
+    assert!(result.is_sent_to(ITEM_SUBSCRIBER, &event));
+    assert!(!result.is_sent_to(ROLE_SUBSCRIBER, &event));
+    result.succeed(1);
+
+    non_fungible_token.approve(PRODUCER, supply_chain.actor_id(), 1);
+    supply_chain
+        .put_up_for_sale_by_producer(PRODUCER, 1, ITEM_PRICE)
+        .succeed(1);
+
+    let result = supply_chain.purchase_by_distributor(DISTRIBUTOR, 1, DELIVERY_TIME);
+    let event = item_event(1, ItemEventState::Purchased, Role::Distributor);
+
+    assert!(result.is_sent_to(ALL_EVENTS_SUBSCRIBER, &event));
+    assert!(result.is_sent_to(ITEM_SUBSCRIBER, &event));
+    assert!(result.is_sent_to(ROLE_SUBSCRIBER, &event));
+    result.succeed(1);
+
+    // A failed action isn't notified.
+    let result = supply_chain.purchase_by_distributor(DISTRIBUTOR, 1, DELIVERY_TIME);
+
+    assert!(!result.is_sent_to(ALL_EVENTS_SUBSCRIBER, &event));
+    result.failed(Error::UnexpectedItemState);
+
+    supply_chain
+        .unsubscribe(ALL_EVENTS_SUBSCRIBER)
+        .succeed(ALL_EVENTS_SUBSCRIBER);
+    supply_chain.state().subscribers().eq([
+        (ActorId::from(ITEM_SUBSCRIBER), item_filter),
+        (ROLE_SUBSCRIBER.into(), role_filter),
+    ]
+    .into());
+
+    let result = supply_chain.approve_by_producer(PRODUCER, 1, true);
+    let event = item_event(1, ItemEventState::Approved, Role::Producer);
+
+    assert!(!result.is_sent_to(ALL_EVENTS_SUBSCRIBER, &event));
+    assert!(result.is_sent_to(ITEM_SUBSCRIBER, &event));
+    assert!(!result.is_sent_to(ROLE_SUBSCRIBER, &event));
+    result.succeed((1, true));
+}
+
+#[test]
+fn subscribers_limit() {
+    let system = utils::initialize_system();
+
+    let fungible_token = FungibleToken::initialize(&system);
+    let non_fungible_token = NonFungibleToken::initialize(&system);
+    let mut supply_chain = SupplyChain::initialize(
+        &system,
+        fungible_token.actor_id(),
+        non_fungible_token.actor_id(),
+    );
+
+    let first_subscriber = 100;
+    let subscribers = first_subscriber..first_subscriber + MAX_NUMBER_OF_SUBSCRIBERS as u64;
+
+    for subscriber in subscribers.clone() {
+        supply_chain
+            .add_participant(FOREIGN_USER, Role::Retailer, subscriber)
+            .succeed((Role::Retailer, subscriber));
+        supply_chain
+            .subscribe(subscriber, Default::default())
+            .succeed((subscriber, Default::default()));
+    }
+
+    supply_chain
+        .subscribe(FOREIGN_USER, Default::default())
+        .failed(Error::MemoryLimitExceeded);
+    // An existing subscriber can still replace its filter.
+    let filter = SubscriptionFilter {
+        role: Some(Role::Retailer),
+        ..Default::default()
+    };
+    supply_chain
+        .subscribe(first_subscriber, filter)
+        .succeed((first_subscriber, filter));
+
+    // A removed participant frees its slot.
+    supply_chain
+        .remove_participant(FOREIGN_USER, Role::Retailer, first_subscriber)
+        .succeed((Role::Retailer, first_subscriber));
+    supply_chain
+        .unsubscribe(first_subscriber)
+        .failed(Error::SubscriberNotFound);
+    supply_chain
+        .subscribe(FOREIGN_USER, Default::default())
+        .succeed((FOREIGN_USER, Default::default()));
+}
@@ -0,0 +1,203 @@
+use utils::{prelude::*, FungibleToken, NonFungibleToken};
+
+pub mod utils;
+
+const OTHER_DISTRIBUTOR: u64 = 15;
+const OFFER_PRICE: u128 = ITEM_PRICE / 2;
+const COUNTER_PRICE: u128 = OFFER_PRICE + 1000;
+
+fn approved_by_producer(item_id: u128) -> Event {
+    Event::Item {
+        item_id: item_id.into(),
+        item_state: ItemState {
+            state: ItemEventState::Approved,
+            by: Role::Producer,
+        },
+    }
+}
+
+#[test]
+fn offer() {
+    let system = utils::initialize_system();
+
+    let mut non_fungible_token = NonFungibleToken::initialize(&system);
+    let mut fungible_token = FungibleToken::initialize(&system);
+    let mut supply_chain = SupplyChain::initialize(
+        &system,
+        fungible_token.actor_id(),
+        non_fungible_token.actor_id(),
+    );
+
+    non_fungible_token.add_minter(supply_chain.actor_id());
+    supply_chain
+        .add_participant(FOREIGN_USER, Role::Distributor, OTHER_DISTRIBUTOR)
+        .succeed((Role::Distributor, OTHER_DISTRIBUTOR));
+
+    for from in [DISTRIBUTOR, OTHER_DISTRIBUTOR] {
+        fungible_token.mint(from, ITEM_PRICE);
+        fungible_token.approve(from, supply_chain.actor_id(), ITEM_PRICE);
+    }
+
+    for item_id in [0, 1] {
+        supply_chain.produce(PRODUCER).succeed(item_id);
+        non_fungible_token.approve(PRODUCER, supply_chain.actor_id(), item_id);
+        supply_chain
+            .put_up_for_sale_by_producer(PRODUCER, item_id, ITEM_PRICE)
+            .succeed(item_id);
+    }
+
+    let make = |price| OfferAction::Make {
+        item_id: 0.into(),
+        price,
+        delivery_time: DELIVERY_TIME,
+    };
+
+    supply_chain
+        .offer_action(FOREIGN_USER, make(OFFER_PRICE))
+        .failed(Error::AccessRestricted);
+    supply_chain
+        .offer_action(DISTRIBUTOR, make(OFFER_PRICE))
+        .succeed(Event::OfferMade {
+            item_id: 0.into(),
+            buyer: DISTRIBUTOR.into(),
+            price: OFFER_PRICE,
+        });
+    supply_chain
+        .offer_action(DISTRIBUTOR, make(OFFER_PRICE))
+        .failed(Error::OfferExists);
+    supply_chain
+        .offer_action(OTHER_DISTRIBUTOR, make(OFFER_PRICE / 2))
+        .succeed(Event::OfferMade {
+            item_id: 0.into(),
+            buyer: OTHER_DISTRIBUTOR.into(),
+            price: OFFER_PRICE / 2,
+        });
+    fungible_token
+        .balance(supply_chain.actor_id())
+        .contains(OFFER_PRICE + OFFER_PRICE / 2);
+
+    supply_chain
+        .offer_action(OTHER_DISTRIBUTOR, OfferAction::Withdraw(0.into()))
+        .succeed(Event::OfferWithdrawn {
+            item_id: 0.into(),
+            buyer: OTHER_DISTRIBUTOR.into(),
+        });
+    supply_chain
+        .offer_action(OTHER_DISTRIBUTOR, OfferAction::Withdraw(0.into()))
+        .failed(Error::OfferNotFound);
+    fungible_token
+        .balance(OTHER_DISTRIBUTOR)
+        .contains(ITEM_PRICE);
+    supply_chain
+        .offer_action(OTHER_DISTRIBUTOR, make(OFFER_PRICE / 2))
+        .succeed(Event::OfferMade {
+            item_id: 0.into(),
+            buyer: OTHER_DISTRIBUTOR.into(),
+            price: OFFER_PRICE / 2,
+        });
+
+    let counter = OfferAction::Counter {
+        item_id: 0.into(),
+        buyer: DISTRIBUTOR.into(),
+        price: COUNTER_PRICE,
+    };
+
+    supply_chain
+        .offer_action(FOREIGN_USER, counter)
+        .failed(Error::AccessRestricted);
+    supply_chain
+        .offer_action(PRODUCER, counter)
+        .succeed(Event::OfferCountered {
+            item_id: 0.into(),
+            buyer: DISTRIBUTOR.into(),
+            price: COUNTER_PRICE,
+        });
+    supply_chain.state().offers(0).eq([
+        (
+            ActorId::from(DISTRIBUTOR),
+            Offer {
+                price: OFFER_PRICE,
+                currency: fungible_token.actor_id(),
+                delivery_time: DELIVERY_TIME,
+                counter_price: Some(COUNTER_PRICE),
+            },
+        ),
+        (
+            OTHER_DISTRIBUTOR.into(),
+            Offer {
+                price: OFFER_PRICE / 2,
+                currency: fungible_token.actor_id(),
+                delivery_time: DELIVERY_TIME,
+                counter_price: None,
+            },
+        ),
+    ]
+    .into());
+
+    // The other distributor's offer isn't countered.
+    supply_chain
+        .offer_action(OTHER_DISTRIBUTOR, OfferAction::AcceptCounter(0.into()))
+        .failed(Error::OfferNotFound);
+    supply_chain
+        .offer_action(DISTRIBUTOR, OfferAction::AcceptCounter(0.into()))
+        .succeed(approved_by_producer(0));
+    supply_chain.state().offers(0).eq(Default::default());
+    fungible_token
+        .balance(supply_chain.actor_id())
+        .contains(COUNTER_PRICE);
+    fungible_token
+        .balance(OTHER_DISTRIBUTOR)
+        .contains(ITEM_PRICE);
+
+    let item_info = supply_chain.state().item_info(0).0.unwrap();
+
+    assert_eq!(item_info.distributor, DISTRIBUTOR.into());
+    assert_eq!(item_info.price, COUNTER_PRICE);
+
+    supply_chain.ship_by_producer(PRODUCER, 0).succeed(0);
+    supply_chain
+        .receive_by_distributor(DISTRIBUTOR, 0)
+        .succeed(0);
+    fungible_token.balance(PRODUCER).contains(COUNTER_PRICE);
+
+    // A seller accepts an offer.
+    supply_chain
+        .offer_action(
+            OTHER_DISTRIBUTOR,
+            OfferAction::Make {
+                item_id: 1.into(),
+                price: OFFER_PRICE,
+                delivery_time: DELIVERY_TIME,
+            },
+        )
+        .succeed(Event::OfferMade {
+            item_id: 1.into(),
+            buyer: OTHER_DISTRIBUTOR.into(),
+            price: OFFER_PRICE,
+        });
+
+    let accept = OfferAction::Accept {
+        item_id: 1.into(),
+        buyer: OTHER_DISTRIBUTOR.into(),
+    };
+
+    supply_chain
+        .offer_action(DISTRIBUTOR, accept)
+        .failed(Error::AccessRestricted);
+    supply_chain
+        .offer_action(
+            PRODUCER,
+            OfferAction::Accept {
+                item_id: 1.into(),
+                buyer: DISTRIBUTOR.into(),
+            },
+        )
+        .failed(Error::OfferNotFound);
+    supply_chain
+        .offer_action(PRODUCER, accept)
+        .succeed(approved_by_producer(1));
+    assert_eq!(
+        supply_chain.state().item_info(1).0.unwrap().distributor,
+        OTHER_DISTRIBUTOR.into()
+    );
+}
@@ -77,6 +77,15 @@ fn initialization() {
 
         fungible_token: fungible_token.actor_id(),
         non_fungible_token: non_fungible_token.actor_id(),
+        payment_mode: Default::default(),
+        currencies: vec![],
+
+        penalty_schedule: Default::default(),
+        resale_royalty: Default::default(),
+        platform_fee: None,
+        transaction_ttl: None,
+
+        arbiter: ActorId::zero(),
     };
     SupplyChain::initialize_custom_with_existential_deposit(&system, supply_chain_config.clone())
         .failed(Error::ZeroActorId);
@@ -90,6 +99,35 @@ fn initialization() {
         .failed(Error::ZeroActorId);
 
     supply_chain_config.retailers = [RETAILER.into()].into();
+    SupplyChain::initialize_custom_with_existential_deposit(&system, supply_chain_config.clone())
+        .failed(Error::ZeroActorId);
+
+    supply_chain_config.arbiter = ARBITER.into();
+    supply_chain_config.resale_royalty = MAX_BASIS_POINTS + 1;
+    SupplyChain::initialize_custom_with_existential_deposit(&system, supply_chain_config.clone())
+        .failed(Error::InvalidBasisPoints);
+
+    supply_chain_config.resale_royalty = RESALE_ROYALTY;
+    supply_chain_config.platform_fee = Some(PlatformFee {
+        fee: MAX_BASIS_POINTS + 1,
+        treasury: FOREIGN_USER.into(),
+    });
+    SupplyChain::initialize_custom_with_existential_deposit(&system, supply_chain_config.clone())
+        .failed(Error::InvalidBasisPoints);
+
+    supply_chain_config.platform_fee = Some(PlatformFee {
+        fee: MAX_BASIS_POINTS,
+        treasury: ActorId::zero(),
+    });
+    SupplyChain::initialize_custom_with_existential_deposit(&system, supply_chain_config.clone())
+        .failed(Error::ZeroActorId);
+
+    supply_chain_config.platform_fee = None;
+    supply_chain_config.currencies = vec![ActorId::zero()];
+    SupplyChain::initialize_custom_with_existential_deposit(&system, supply_chain_config.clone())
+        .failed(Error::ZeroActorId);
+
+    supply_chain_config.currencies = vec![];
     let supply_chain =
         SupplyChain::initialize_custom(&system, supply_chain_config.clone()).succeed();
 
@@ -97,6 +135,8 @@ fn initialization() {
         producers: supply_chain_config.producers,
         distributors: supply_chain_config.distributors,
         retailers: supply_chain_config.retailers,
+        admin: FOREIGN_USER.into(),
+        arbiter: ARBITER.into(),
     });
     supply_chain
         .state()
@@ -132,13 +172,21 @@ fn query_existing_items() {
                     producer: PRODUCER.into(),
                     distributor: Default::default(),
                     retailer: Default::default(),
+                    consumer: Default::default(),
 
                     state: ItemState {
                         state: Default::default(),
                         by: Role::Producer,
                     },
                     price: Default::default(),
+                    currency: Default::default(),
                     delivery_time: Default::default(),
+                    penalty_schedule: Default::default(),
+                    approval_deadline: None,
+                    royalty: Default::default(),
+                    dispute_reason: None,
+                    lot_id: None,
+                    recall_reason: None,
                 },
             )
         })
@@ -22,6 +22,15 @@ fn ownership_and_role() {
 
             fungible_token: fungible_token.actor_id(),
             non_fungible_token: non_fungible_token.actor_id(),
+            payment_mode: Default::default(),
+            currencies: vec![],
+
+            penalty_schedule: Default::default(),
+            resale_royalty: Default::default(),
+            platform_fee: None,
+            transaction_ttl: None,
+
+            arbiter: FOREIGN_USER.into(),
         },
     )
     .succeed();
@@ -171,6 +180,15 @@ fn query_roles() {
 
             fungible_token: fungible_token.actor_id(),
             non_fungible_token: non_fungible_token.actor_id(),
+            payment_mode: Default::default(),
+            currencies: vec![],
+
+            penalty_schedule: Default::default(),
+            resale_royalty: Default::default(),
+            platform_fee: None,
+            transaction_ttl: None,
+
+            arbiter: FOREIGN_USER.into(),
         },
     )
     .succeed();
@@ -191,6 +209,15 @@ fn query_roles() {
 
             fungible_token: fungible_token.actor_id(),
             non_fungible_token: non_fungible_token.actor_id(),
+            payment_mode: Default::default(),
+            currencies: vec![],
+
+            penalty_schedule: Default::default(),
+            resale_royalty: Default::default(),
+            platform_fee: None,
+            transaction_ttl: None,
+
+            arbiter: FOREIGN_USER.into(),
         },
     )
     .succeed();
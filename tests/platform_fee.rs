@@ -0,0 +1,117 @@
+use utils::{prelude::*, FungibleToken, NonFungibleToken};
+
+pub mod utils;
+
+const TREASURY: u64 = 13;
+const FEE: u16 = 200;
+const FEE_AMOUNT: u128 = ITEM_PRICE * FEE as u128 / MAX_BASIS_POINTS as u128;
+
+#[test]
+fn platform_fee() {
+    let system = utils::initialize_system();
+
+    let mut non_fungible_token = NonFungibleToken::initialize(&system);
+    let mut fungible_token = FungibleToken::initialize(&system);
+    let mut supply_chain = SupplyChain::initialize(
+        &system,
+        fungible_token.actor_id(),
+        non_fungible_token.actor_id(),
+    );
+
+    non_fungible_token.add_minter(supply_chain.actor_id());
+
+    for from in [DISTRIBUTOR, RETAILER, CONSUMER] {
+        fungible_token.mint(from, ITEM_PRICE);
+        fungible_token.approve(from, supply_chain.actor_id(), ITEM_PRICE);
+    }
+
+    let platform_fee = PlatformFee {
+        fee: FEE,
+        treasury: TREASURY.into(),
+    };
+
+    supply_chain
+        .set_platform_fee(PRODUCER, Some(platform_fee))
+        .failed(Error::AccessRestricted);
+    supply_chain
+        .set_platform_fee(
+            FOREIGN_USER,
+            Some(PlatformFee {
+                fee: MAX_BASIS_POINTS + 1,
+                ..platform_fee
+            }),
+        )
+        .failed(Error::InvalidBasisPoints);
+    supply_chain
+        .set_platform_fee(
+            FOREIGN_USER,
+            Some(PlatformFee {
+                treasury: ActorId::zero(),
+                ..platform_fee
+            }),
+        )
+        .failed(Error::ZeroActorId);
+    supply_chain
+        .set_platform_fee(FOREIGN_USER, Some(platform_fee))
+        .succeed(Some(platform_fee));
+    supply_chain.state().platform_fee().eq(Some(platform_fee));
+
+    supply_chain.produce(PRODUCER).succeed(0);
+    non_fungible_token.approve(PRODUCER, supply_chain.actor_id(), 0);
+    supply_chain
+        .put_up_for_sale_by_producer(PRODUCER, 0, ITEM_PRICE)
+        .succeed(0);
+    supply_chain
+        .purchase_by_distributor(DISTRIBUTOR, 0, DELIVERY_TIME)
+        .succeed(0);
+    supply_chain
+        .approve_by_producer(PRODUCER, 0, true)
+        .succeed((0, true));
+    supply_chain.ship_by_producer(PRODUCER, 0).succeed(0);
+    supply_chain
+        .receive_by_distributor(DISTRIBUTOR, 0)
+        .succeed(0);
+    fungible_token
+        .balance(PRODUCER)
+        .contains(ITEM_PRICE - FEE_AMOUNT);
+    fungible_token.balance(TREASURY).contains(FEE_AMOUNT);
+
+    supply_chain.process(DISTRIBUTOR, 0).succeed(0);
+    supply_chain.package(DISTRIBUTOR, 0).succeed(0);
+    non_fungible_token.approve(DISTRIBUTOR, supply_chain.actor_id(), 0);
+    supply_chain
+        .put_up_for_sale_by_distributor(DISTRIBUTOR, 0, ITEM_PRICE)
+        .succeed(0);
+    supply_chain
+        .purchase_by_retailer(RETAILER, 0, DELIVERY_TIME)
+        .succeed(0);
+    supply_chain
+        .approve_by_distributor(DISTRIBUTOR, 0, true)
+        .succeed((0, true));
+    supply_chain.ship_by_distributor(DISTRIBUTOR, 0).succeed(0);
+    supply_chain.receive_by_retailer(RETAILER, 0).succeed(0);
+    fungible_token
+        .balance(DISTRIBUTOR)
+        .contains(ITEM_PRICE - FEE_AMOUNT);
+    fungible_token.balance(TREASURY).contains(FEE_AMOUNT * 2);
+
+    non_fungible_token.approve(RETAILER, supply_chain.actor_id(), 0);
+    supply_chain
+        .put_up_for_sale_by_retailer(RETAILER, 0, ITEM_PRICE)
+        .succeed(0);
+    supply_chain.purchase_by_consumer(CONSUMER, 0).succeed(0);
+    fungible_token
+        .balance(RETAILER)
+        .contains(ITEM_PRICE - FEE_AMOUNT);
+    fungible_token.balance(TREASURY).contains(FEE_AMOUNT * 3);
+    fungible_token.balance(supply_chain.actor_id()).contains(0);
+    supply_chain
+        .state()
+        .collected_fees()
+        .eq([(fungible_token.actor_id(), FEE_AMOUNT * 3)].into());
+
+    supply_chain
+        .set_platform_fee(FOREIGN_USER, None)
+        .succeed(None);
+    supply_chain.state().platform_fee().eq(None);
+}
@@ -0,0 +1,51 @@
+use utils::{prelude::*, FungibleToken, NonFungibleToken};
+
+pub mod utils;
+
+const BATCH_SIZE: u128 = 5;
+
+#[test]
+fn produce_batch() {
+    let system = utils::initialize_system();
+
+    let fungible_token = FungibleToken::initialize(&system);
+    let mut non_fungible_token = NonFungibleToken::initialize(&system);
+    let mut supply_chain = SupplyChain::initialize(
+        &system,
+        fungible_token.actor_id(),
+        non_fungible_token.actor_id(),
+    );
+
+    non_fungible_token.add_minter(supply_chain.actor_id());
+
+    supply_chain
+        .produce_batch(FOREIGN_USER, BATCH_SIZE as _)
+        .failed(Error::AccessRestricted);
+    supply_chain
+        .produce_batch(PRODUCER, MAX_BATCH_SIZE + 1)
+        .failed(Error::BatchSizeExceeded);
+
+    supply_chain
+        .produce_batch(PRODUCER, BATCH_SIZE as _)
+        .succeed((0..BATCH_SIZE).collect());
+
+    for item_id in 0..BATCH_SIZE {
+        supply_chain.state().item_state(item_id).eq(Some(ItemState {
+            state: ItemEventState::Produced,
+            by: Role::Producer,
+        }));
+        non_fungible_token
+            .meta_state()
+            .owner_id(item_id)
+            .eq(PRODUCER.into());
+    }
+
+    supply_chain.produce(PRODUCER).succeed(BATCH_SIZE);
+    supply_chain.produce_batch(PRODUCER, 0).succeed(vec![]);
+
+    // Items of a batch are usual items.
+    non_fungible_token.approve(PRODUCER, supply_chain.actor_id(), 0);
+    supply_chain
+        .put_up_for_sale_by_producer(PRODUCER, 0, ITEM_PRICE)
+        .succeed(0);
+}
@@ -0,0 +1,104 @@
+use utils::{prelude::*, FungibleToken, NonFungibleToken};
+
+pub mod utils;
+
+const REASON: &str = "A defective batch.";
+
+#[test]
+fn recall() {
+    let system = utils::initialize_system();
+
+    let mut non_fungible_token = NonFungibleToken::initialize(&system);
+    let mut fungible_token = FungibleToken::initialize(&system);
+    let mut supply_chain = SupplyChain::initialize(
+        &system,
+        fungible_token.actor_id(),
+        non_fungible_token.actor_id(),
+    );
+
+    non_fungible_token.add_minter(supply_chain.actor_id());
+    fungible_token.mint(DISTRIBUTOR, ITEM_PRICE * 2);
+    fungible_token.approve(DISTRIBUTOR, supply_chain.actor_id(), ITEM_PRICE * 2);
+    fungible_token.mint(PRODUCER, ITEM_PRICE);
+    fungible_token.approve(PRODUCER, supply_chain.actor_id(), ITEM_PRICE);
+
+    supply_chain
+        .produce_batch(PRODUCER, 4)
+        .succeed(vec![0, 1, 2, 3]);
+
+    // Items 0 & 1 are held by the distributor, item 2 - by the producer, and
+    // item 3 is up for sale.
+    for item_id in [0, 1, 3] {
+        non_fungible_token.approve(PRODUCER, supply_chain.actor_id(), item_id);
+        supply_chain
+            .put_up_for_sale_by_producer(PRODUCER, item_id, ITEM_PRICE)
+            .succeed(item_id);
+    }
+
+    for item_id in [0, 1] {
+        supply_chain
+            .purchase_by_distributor(DISTRIBUTOR, item_id, DELIVERY_TIME)
+            .succeed(item_id);
+        supply_chain
+            .approve_by_producer(PRODUCER, item_id, true)
+            .succeed((item_id, true));
+        supply_chain
+            .ship_by_producer(PRODUCER, item_id)
+            .succeed(item_id);
+        supply_chain
+            .receive_by_distributor(DISTRIBUTOR, item_id)
+            .succeed(item_id);
+    }
+
+    supply_chain
+        .fund_recall_pool(FOREIGN_USER, ITEM_PRICE)
+        .failed(Error::AccessRestricted);
+    supply_chain
+        .fund_recall_pool(PRODUCER, ITEM_PRICE)
+        .succeed((PRODUCER, ITEM_PRICE));
+    fungible_token
+        .balance(supply_chain.actor_id())
+        .contains(ITEM_PRICE);
+
+    supply_chain
+        .recall(DISTRIBUTOR, &[0], REASON)
+        .failed(Error::AccessRestricted);
+    supply_chain
+        .recall(PRODUCER, &[0, 9], REASON)
+        .failed(Error::ItemNotFound);
+
+    // The pool covers only one refund.
+    supply_chain
+        .recall(PRODUCER, &[0, 1, 2, 3], REASON)
+        .succeed((vec![0, 1, 2, 3], vec![0]));
+    fungible_token.balance(DISTRIBUTOR).contains(ITEM_PRICE);
+    fungible_token.balance(supply_chain.actor_id()).contains(0);
+    supply_chain
+        .recall(PRODUCER, &[1], REASON)
+        .failed(Error::ItemRecalled);
+
+    assert_eq!(
+        supply_chain.state().item_info(0).0.unwrap().recall_reason,
+        Some(REASON.into())
+    );
+    supply_chain.state().recalled_items().eq((0..4u128)
+        .map(|item_id| (item_id.into(), REASON.into()))
+        .collect());
+
+    // Recalled items can't be sold anymore.
+    supply_chain
+        .purchase_by_distributor(DISTRIBUTOR, 3, DELIVERY_TIME)
+        .failed(Error::ItemRecalled);
+    supply_chain
+        .put_up_for_sale_by_producer(PRODUCER, 2, ITEM_PRICE)
+        .failed(Error::ItemRecalled);
+    supply_chain
+        .withdraw_from_sale_by_producer(PRODUCER, 3)
+        .succeed(3);
+
+    supply_chain.process(DISTRIBUTOR, 0).succeed(0);
+    supply_chain.package(DISTRIBUTOR, 0).succeed(0);
+    supply_chain
+        .put_up_for_sale_by_distributor(DISTRIBUTOR, 0, ITEM_PRICE)
+        .failed(Error::ItemRecalled);
+}
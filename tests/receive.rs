@@ -208,3 +208,347 @@ fn delivery_with_big_delay() {
     fungible_token.balance(DISTRIBUTOR).contains(ITEM_PRICE);
     fungible_token.balance(RETAILER).contains(ITEM_PRICE);
 }
+
+#[test]
+fn delivery_failure() {
+    const BIG_DELAY: u32 = DELIVERY_TIME_IN_BLOCKS * 2;
+
+    let system = utils::initialize_system();
+
+    let mut non_fungible_token = NonFungibleToken::initialize(&system);
+    let mut fungible_token = FungibleToken::initialize(&system);
+    let mut supply_chain = SupplyChain::initialize(
+        &system,
+        fungible_token.actor_id(),
+        non_fungible_token.actor_id(),
+    );
+
+    non_fungible_token.add_minter(supply_chain.actor_id());
+
+    fungible_token.mint(DISTRIBUTOR, ITEM_PRICE);
+    fungible_token.approve(DISTRIBUTOR, supply_chain.actor_id(), ITEM_PRICE * 2);
+    fungible_token.mint(RETAILER, ITEM_PRICE);
+    fungible_token.approve(RETAILER, supply_chain.actor_id(), ITEM_PRICE);
+
+    for item_id in [0, 1] {
+        supply_chain.produce(PRODUCER).succeed(item_id);
+        non_fungible_token.approve(PRODUCER, supply_chain.actor_id(), item_id);
+        supply_chain
+            .put_up_for_sale_by_producer(PRODUCER, item_id, ITEM_PRICE)
+            .succeed(item_id);
+    }
+
+    supply_chain
+        .purchase_by_distributor(DISTRIBUTOR, 0, DELIVERY_TIME)
+        .succeed(0);
+    supply_chain
+        .approve_by_producer(PRODUCER, 0, true)
+        .succeed((0, true));
+    supply_chain.ship_by_producer(PRODUCER, 0).succeed(0);
+
+    supply_chain
+        .fail_delivery(FOREIGN_USER, 0)
+        .failed(Error::DeadlineNotReached);
+    system.spend_blocks(BIG_DELAY);
+    // Anyone can fail the delivery as soon as the deadline passes...
+    supply_chain
+        .fail_delivery(FOREIGN_USER, 0)
+        .succeed((0, Role::Producer));
+    // ...and then all fungible tokens are refunded to the distributor (buyer),
+    // and the NFT is transferred back to the producer (seller).
+    fungible_token.balance(PRODUCER).contains(0);
+    fungible_token.balance(DISTRIBUTOR).contains(ITEM_PRICE);
+    non_fungible_token
+        .meta_state()
+        .owner_id(0)
+        .eq(PRODUCER.into());
+    supply_chain
+        .receive_by_distributor(DISTRIBUTOR, 0)
+        .failed(Error::UnexpectedItemState);
+    supply_chain.state().item_state(0).eq(Some(ItemState {
+        state: ItemEventState::Produced,
+        by: Role::Producer,
+    }));
+
+    // The item can be put up for sale again after the failed delivery.
+    non_fungible_token.approve(PRODUCER, supply_chain.actor_id(), 0);
+    supply_chain
+        .put_up_for_sale_by_producer(PRODUCER, 0, ITEM_PRICE)
+        .succeed(0);
+
+    supply_chain
+        .purchase_by_distributor(DISTRIBUTOR, 1, DELIVERY_TIME)
+        .succeed(1);
+    supply_chain
+        .approve_by_producer(PRODUCER, 1, true)
+        .succeed((1, true));
+    supply_chain.ship_by_producer(PRODUCER, 1).succeed(1);
+    supply_chain
+        .receive_by_distributor(DISTRIBUTOR, 1)
+        .succeed(1);
+    supply_chain.process(DISTRIBUTOR, 1).succeed(1);
+    supply_chain.package(DISTRIBUTOR, 1).succeed(1);
+    non_fungible_token.approve(DISTRIBUTOR, supply_chain.actor_id(), 1);
+    supply_chain
+        .put_up_for_sale_by_distributor(DISTRIBUTOR, 1, ITEM_PRICE)
+        .succeed(1);
+    supply_chain
+        .purchase_by_retailer(RETAILER, 1, DELIVERY_TIME)
+        .succeed(1);
+    supply_chain
+        .approve_by_distributor(DISTRIBUTOR, 1, true)
+        .succeed((1, true));
+    supply_chain.ship_by_distributor(DISTRIBUTOR, 1).succeed(1);
+
+    // If nobody fails the delivery, the contract does it itself.
+    system.spend_blocks(BIG_DELAY + 1);
+    supply_chain.state().item_state(1).eq(Some(ItemState {
+        state: ItemEventState::Packaged,
+        by: Role::Distributor,
+    }));
+    fungible_token.balance(DISTRIBUTOR).contains(0);
+    fungible_token.balance(RETAILER).contains(ITEM_PRICE);
+    non_fungible_token
+        .meta_state()
+        .owner_id(1)
+        .eq(DISTRIBUTOR.into());
+    supply_chain
+        .receive_by_retailer(RETAILER, 1)
+        .failed(Error::UnexpectedItemState);
+}
+
+#[test]
+fn custom_penalty_schedule() {
+    let penalty_schedule = PenaltySchedule(vec![
+        PenaltyStep {
+            lateness: 0,
+            seller_share: 9000,
+        },
+        PenaltyStep {
+            lateness: 5000,
+            seller_share: 6000,
+        },
+        PenaltyStep {
+            lateness: 7500,
+            seller_share: 2000,
+        },
+    ]);
+
+    let system = utils::initialize_system();
+
+    let mut non_fungible_token = NonFungibleToken::initialize(&system);
+    let mut fungible_token = FungibleToken::initialize(&system);
+
+    SupplyChain::initialize_custom_with_existential_deposit(
+        &system,
+        Initialize {
+            producers: vec![PRODUCER.into()],
+            distributors: vec![DISTRIBUTOR.into()],
+            retailers: vec![RETAILER.into()],
+
+            fungible_token: fungible_token.actor_id(),
+            non_fungible_token: non_fungible_token.actor_id(),
+            payment_mode: Default::default(),
+            currencies: vec![],
+
+            penalty_schedule: PenaltySchedule(vec![
+                PenaltyStep {
+                    lateness: 5000,
+                    seller_share: 0,
+                },
+                PenaltyStep {
+                    lateness: 0,
+                    seller_share: 0,
+                },
+            ]),
+            resale_royalty: Default::default(),
+            platform_fee: None,
+            transaction_ttl: None,
+
+            arbiter: ARBITER.into(),
+        },
+    )
+    .failed(Error::InvalidPenaltySchedule);
+
+    // A step at the delivery deadline would never be reached.
+    SupplyChain::initialize_custom_with_existential_deposit(
+        &system,
+        Initialize {
+            producers: vec![PRODUCER.into()],
+            distributors: vec![DISTRIBUTOR.into()],
+            retailers: vec![RETAILER.into()],
+
+            fungible_token: fungible_token.actor_id(),
+            non_fungible_token: non_fungible_token.actor_id(),
+            payment_mode: Default::default(),
+            currencies: vec![],
+
+            penalty_schedule: PenaltySchedule(vec![PenaltyStep {
+                lateness: MAX_BASIS_POINTS.into(),
+                seller_share: 0,
+            }]),
+            resale_royalty: Default::default(),
+            platform_fee: None,
+            transaction_ttl: None,
+
+            arbiter: ARBITER.into(),
+        },
+    )
+    .failed(Error::InvalidPenaltySchedule);
+
+    let mut supply_chain = SupplyChain::initialize_custom(
+        &system,
+        Initialize {
+            producers: vec![PRODUCER.into()],
+            distributors: vec![DISTRIBUTOR.into()],
+            retailers: vec![RETAILER.into()],
+
+            fungible_token: fungible_token.actor_id(),
+            non_fungible_token: non_fungible_token.actor_id(),
+            payment_mode: Default::default(),
+            currencies: vec![],
+
+            penalty_schedule: penalty_schedule.clone(),
+            resale_royalty: Default::default(),
+            platform_fee: None,
+            transaction_ttl: None,
+
+            arbiter: ARBITER.into(),
+        },
+    )
+    .succeed();
+
+    supply_chain
+        .state()
+        .penalty_schedule()
+        .eq(penalty_schedule.clone());
+    non_fungible_token.add_minter(supply_chain.actor_id());
+    fungible_token.mint(DISTRIBUTOR, ITEM_PRICE * 3);
+    fungible_token.approve(DISTRIBUTOR, supply_chain.actor_id(), ITEM_PRICE * 3);
+
+    let mut seller_balance = 0;
+
+    for (item_id, delay, seller_share) in [
+        (0, DELIVERY_TIME_IN_BLOCKS + 1, 9000),
+        (1, DELIVERY_TIME_IN_BLOCKS * 3 / 2, 6000),
+        (2, DELIVERY_TIME_IN_BLOCKS * 7 / 4, 2000),
+    ] {
+        supply_chain.produce(PRODUCER).succeed(item_id);
+        non_fungible_token.approve(PRODUCER, supply_chain.actor_id(), item_id);
+        supply_chain
+            .put_up_for_sale_by_producer(PRODUCER, item_id, ITEM_PRICE)
+            .succeed(item_id);
+        supply_chain
+            .purchase_by_distributor(DISTRIBUTOR, item_id, DELIVERY_TIME)
+            .succeed(item_id);
+        supply_chain
+            .approve_by_producer(PRODUCER, item_id, true)
+            .succeed((item_id, true));
+        supply_chain
+            .ship_by_producer(PRODUCER, item_id)
+            .succeed(item_id);
+
+        system.spend_blocks(delay);
+        supply_chain
+            .receive_by_distributor(DISTRIBUTOR, item_id)
+            .succeed(item_id);
+
+        seller_balance += ITEM_PRICE * seller_share / MAX_BASIS_POINTS as u128;
+        fungible_token.balance(PRODUCER).contains(seller_balance);
+        fungible_token
+            .balance(DISTRIBUTOR)
+            .contains(ITEM_PRICE * 3 - seller_balance);
+    }
+}
+
+#[test]
+fn penalty_schedule_on_purchase() {
+    // A linear-like penalty: the seller loses a quarter of the price for every
+    // quarter of the delivery time it's late.
+    let penalty_schedule = PenaltySchedule(
+        (1..=4)
+            .map(|quarter| PenaltyStep {
+                lateness: 2500 * (quarter - 1),
+                seller_share: 10000 - 2500 * quarter as u16,
+            })
+            .collect(),
+    );
+
+    let system = utils::initialize_system();
+
+    let mut non_fungible_token = NonFungibleToken::initialize(&system);
+    let mut fungible_token = FungibleToken::initialize(&system);
+    let mut supply_chain = SupplyChain::initialize(
+        &system,
+        fungible_token.actor_id(),
+        non_fungible_token.actor_id(),
+    );
+
+    non_fungible_token.add_minter(supply_chain.actor_id());
+    fungible_token.mint(DISTRIBUTOR, ITEM_PRICE);
+    fungible_token.approve(DISTRIBUTOR, supply_chain.actor_id(), ITEM_PRICE);
+
+    supply_chain.produce(PRODUCER).succeed(0);
+    non_fungible_token.approve(PRODUCER, supply_chain.actor_id(), 0);
+    supply_chain
+        .put_up_for_sale_by_producer(PRODUCER, 0, ITEM_PRICE)
+        .succeed(0);
+
+    supply_chain
+        .purchase_by_distributor_custom(
+            DISTRIBUTOR,
+            0,
+            DELIVERY_TIME,
+            Some(PenaltySchedule(vec![PenaltyStep {
+                lateness: 0,
+                seller_share: MAX_BASIS_POINTS + 1,
+            }])),
+            None,
+        )
+        .failed(Error::InvalidPenaltySchedule);
+    supply_chain
+        .purchase_by_distributor_custom(
+            DISTRIBUTOR,
+            0,
+            DELIVERY_TIME,
+            Some(PenaltySchedule(vec![PenaltyStep {
+                lateness: MAX_BASIS_POINTS as u64 * 2,
+                seller_share: 0,
+            }])),
+            None,
+        )
+        .failed(Error::InvalidPenaltySchedule);
+    supply_chain
+        .purchase_by_distributor_custom(
+            DISTRIBUTOR,
+            0,
+            DELIVERY_TIME,
+            Some(penalty_schedule.clone()),
+            None,
+        )
+        .succeed(0);
+    assert_eq!(
+        supply_chain
+            .state()
+            .item_info(0)
+            .0
+            .unwrap()
+            .penalty_schedule,
+        penalty_schedule
+    );
+
+    supply_chain
+        .approve_by_producer(PRODUCER, 0, true)
+        .succeed((0, true));
+    supply_chain.ship_by_producer(PRODUCER, 0).succeed(0);
+
+    // The delivery is late by a half of the delivery time.
+    system.spend_blocks(DELIVERY_TIME_IN_BLOCKS * 3 / 2);
+    supply_chain
+        .receive_by_distributor(DISTRIBUTOR, 0)
+        .succeed(0);
+    fungible_token.balance(PRODUCER).contains(ITEM_PRICE / 4);
+    fungible_token
+        .balance(DISTRIBUTOR)
+        .contains(ITEM_PRICE - ITEM_PRICE / 4);
+}
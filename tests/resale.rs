@@ -0,0 +1,124 @@
+use utils::{prelude::*, FungibleToken, NonFungibleToken};
+
+pub mod utils;
+
+const SECOND_CONSUMER: u64 = 12;
+const RESALE_PRICE: u128 = ITEM_PRICE * 2;
+const ROYALTY: u128 = RESALE_PRICE * RESALE_ROYALTY as u128 / MAX_BASIS_POINTS as u128;
+
+#[test]
+fn resale() {
+    let system = utils::initialize_system();
+
+    let mut non_fungible_token = NonFungibleToken::initialize(&system);
+    let mut fungible_token = FungibleToken::initialize(&system);
+    let mut supply_chain = SupplyChain::initialize(
+        &system,
+        fungible_token.actor_id(),
+        non_fungible_token.actor_id(),
+    );
+
+    non_fungible_token.add_minter(supply_chain.actor_id());
+
+    for (from, amount) in [
+        (DISTRIBUTOR, ITEM_PRICE),
+        (RETAILER, ITEM_PRICE),
+        (CONSUMER, ITEM_PRICE),
+        (SECOND_CONSUMER, RESALE_PRICE),
+    ] {
+        fungible_token.mint(from, amount);
+        fungible_token.approve(from, supply_chain.actor_id(), amount);
+    }
+
+    supply_chain.produce(PRODUCER).succeed(0);
+    non_fungible_token.approve(PRODUCER, supply_chain.actor_id(), 0);
+    supply_chain
+        .put_up_for_sale_by_producer(PRODUCER, 0, ITEM_PRICE)
+        .succeed(0);
+    supply_chain
+        .purchase_by_distributor(DISTRIBUTOR, 0, DELIVERY_TIME)
+        .succeed(0);
+    supply_chain
+        .approve_by_producer(PRODUCER, 0, true)
+        .succeed((0, true));
+    supply_chain.ship_by_producer(PRODUCER, 0).succeed(0);
+    supply_chain
+        .receive_by_distributor(DISTRIBUTOR, 0)
+        .succeed(0);
+    supply_chain.process(DISTRIBUTOR, 0).succeed(0);
+    supply_chain.package(DISTRIBUTOR, 0).succeed(0);
+    non_fungible_token.approve(DISTRIBUTOR, supply_chain.actor_id(), 0);
+    supply_chain
+        .put_up_for_sale_by_distributor(DISTRIBUTOR, 0, ITEM_PRICE)
+        .succeed(0);
+    supply_chain
+        .purchase_by_retailer(RETAILER, 0, DELIVERY_TIME)
+        .succeed(0);
+    supply_chain
+        .approve_by_distributor(DISTRIBUTOR, 0, true)
+        .succeed((0, true));
+    supply_chain.ship_by_distributor(DISTRIBUTOR, 0).succeed(0);
+    supply_chain.receive_by_retailer(RETAILER, 0).succeed(0);
+    non_fungible_token.approve(RETAILER, supply_chain.actor_id(), 0);
+    supply_chain
+        .put_up_for_sale_by_retailer(RETAILER, 0, ITEM_PRICE)
+        .succeed(0);
+    supply_chain.purchase_by_consumer(CONSUMER, 0).succeed(0);
+
+    supply_chain
+        .put_up_for_sale_by_consumer(SECOND_CONSUMER, 0, RESALE_PRICE)
+        .failed(Error::AccessRestricted);
+
+    non_fungible_token.approve(CONSUMER, supply_chain.actor_id(), 0);
+    supply_chain
+        .put_up_for_sale_by_consumer(CONSUMER, 0, ITEM_PRICE)
+        .succeed(0);
+    non_fungible_token
+        .meta_state()
+        .owner_id(0)
+        .eq(supply_chain.actor_id());
+    supply_chain
+        .update_price_by_consumer(CONSUMER, 0, RESALE_PRICE)
+        .succeed(0);
+    supply_chain.state().item_price(0).eq(Some(RESALE_PRICE));
+
+    supply_chain
+        .withdraw_from_sale_by_consumer(CONSUMER, 0)
+        .succeed(0);
+    non_fungible_token
+        .meta_state()
+        .owner_id(0)
+        .eq(CONSUMER.into());
+    supply_chain
+        .purchase_by_consumer(SECOND_CONSUMER, 0)
+        .failed(Error::UnexpectedItemState);
+
+    non_fungible_token.approve(CONSUMER, supply_chain.actor_id(), 0);
+    supply_chain
+        .put_up_for_sale_by_consumer(CONSUMER, 0, RESALE_PRICE)
+        .succeed(0);
+
+    // The producer receives a royalty from the resale.
+    supply_chain
+        .purchase_by_consumer(SECOND_CONSUMER, 0)
+        .succeed(0);
+    fungible_token
+        .balance(CONSUMER)
+        .contains(RESALE_PRICE - ROYALTY);
+    fungible_token
+        .balance(PRODUCER)
+        .contains(ITEM_PRICE + ROYALTY);
+    fungible_token.balance(SECOND_CONSUMER).contains(0);
+    non_fungible_token
+        .meta_state()
+        .owner_id(0)
+        .eq(SECOND_CONSUMER.into());
+    assert_eq!(
+        supply_chain.state().item_info(0).0.unwrap().consumer,
+        SECOND_CONSUMER.into()
+    );
+
+    supply_chain
+        .put_up_for_sale_by_consumer(CONSUMER, 0, RESALE_PRICE)
+        .failed(Error::AccessRestricted);
+}
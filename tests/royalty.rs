@@ -0,0 +1,89 @@
+use utils::{prelude::*, FungibleToken, NonFungibleToken};
+
+pub mod utils;
+
+const ROYALTY: u16 = 1000;
+const ROYALTY_AMOUNT: u128 = ITEM_PRICE * ROYALTY as u128 / MAX_BASIS_POINTS as u128;
+
+#[test]
+fn royalty() {
+    let system = utils::initialize_system();
+
+    let mut non_fungible_token = NonFungibleToken::initialize(&system);
+    let mut fungible_token = FungibleToken::initialize(&system);
+    let mut supply_chain = SupplyChain::initialize(
+        &system,
+        fungible_token.actor_id(),
+        non_fungible_token.actor_id(),
+    );
+
+    non_fungible_token.add_minter(supply_chain.actor_id());
+
+    for from in [DISTRIBUTOR, RETAILER, CONSUMER] {
+        fungible_token.mint(from, ITEM_PRICE);
+        fungible_token.approve(from, supply_chain.actor_id(), ITEM_PRICE);
+    }
+
+    supply_chain
+        .produce_with_royalty(PRODUCER, MAX_BASIS_POINTS + 1)
+        .failed(Error::InvalidBasisPoints);
+    supply_chain
+        .produce_with_royalty(PRODUCER, ROYALTY)
+        .succeed(0);
+    assert_eq!(
+        supply_chain.state().item_info(0).0.unwrap().royalty,
+        ROYALTY
+    );
+
+    // The producer doesn't pay a royalty to itself.
+    non_fungible_token.approve(PRODUCER, supply_chain.actor_id(), 0);
+    supply_chain
+        .put_up_for_sale_by_producer(PRODUCER, 0, ITEM_PRICE)
+        .succeed(0);
+    supply_chain
+        .purchase_by_distributor(DISTRIBUTOR, 0, DELIVERY_TIME)
+        .succeed(0);
+    supply_chain
+        .approve_by_producer(PRODUCER, 0, true)
+        .succeed((0, true));
+    supply_chain.ship_by_producer(PRODUCER, 0).succeed(0);
+    supply_chain
+        .receive_by_distributor(DISTRIBUTOR, 0)
+        .succeed(0);
+    fungible_token.balance(PRODUCER).contains(ITEM_PRICE);
+
+    supply_chain.process(DISTRIBUTOR, 0).succeed(0);
+    supply_chain.package(DISTRIBUTOR, 0).succeed(0);
+    non_fungible_token.approve(DISTRIBUTOR, supply_chain.actor_id(), 0);
+    supply_chain
+        .put_up_for_sale_by_distributor(DISTRIBUTOR, 0, ITEM_PRICE)
+        .succeed(0);
+    supply_chain
+        .purchase_by_retailer(RETAILER, 0, DELIVERY_TIME)
+        .succeed(0);
+    supply_chain
+        .approve_by_distributor(DISTRIBUTOR, 0, true)
+        .succeed((0, true));
+    supply_chain.ship_by_distributor(DISTRIBUTOR, 0).succeed(0);
+    supply_chain.receive_by_retailer(RETAILER, 0).succeed(0);
+    fungible_token
+        .balance(DISTRIBUTOR)
+        .contains(ITEM_PRICE - ROYALTY_AMOUNT);
+    fungible_token
+        .balance(PRODUCER)
+        .contains(ITEM_PRICE + ROYALTY_AMOUNT);
+    fungible_token.balance(supply_chain.actor_id()).contains(0);
+
+    non_fungible_token.approve(RETAILER, supply_chain.actor_id(), 0);
+    supply_chain
+        .put_up_for_sale_by_retailer(RETAILER, 0, ITEM_PRICE)
+        .succeed(0);
+    supply_chain.purchase_by_consumer(CONSUMER, 0).succeed(0);
+    fungible_token
+        .balance(RETAILER)
+        .contains(ITEM_PRICE - ROYALTY_AMOUNT);
+    fungible_token
+        .balance(PRODUCER)
+        .contains(ITEM_PRICE + ROYALTY_AMOUNT * 2);
+    fungible_token.balance(CONSUMER).contains(0);
+}
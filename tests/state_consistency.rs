@@ -49,6 +49,15 @@ async fn state_consistency() -> Result<()> {
 
                 fungible_token: ft_actor_id.into(),
                 non_fungible_token: nft_actor_id.into(),
+                payment_mode: Default::default(),
+                currencies: vec![],
+
+                penalty_schedule: Default::default(),
+                resale_royalty: Default::default(),
+                platform_fee: None,
+                transaction_ttl: None,
+
+                arbiter: ALICE.into(),
             },
         )
         .await?;
@@ -75,6 +84,7 @@ async fn state_consistency() -> Result<()> {
     let approve = true;
     let mut payload = Action::new(InnerAction::Producer(ProducerAction::Produce {
         token_metadata: TokenMetadata::default(),
+        royalty: 0,
     }));
 
     assert!(
@@ -125,7 +135,7 @@ async fn state_consistency() -> Result<()> {
         client
             .send_message_for_sc(supply_chain_actor_id, payload.to_retry())
             .await?,
-        Ok(Event {
+        Ok(Event::Item {
             item_id,
             item_state: ItemState {
                 state: ItemEventState::Produced,
@@ -157,6 +167,7 @@ async fn state_consistency() -> Result<()> {
     payload = Action::new(InnerAction::Producer(ProducerAction::PutUpForSale {
         item_id,
         price,
+        currency: None,
     }));
 
     println!(
@@ -174,7 +185,7 @@ async fn state_consistency() -> Result<()> {
         client
             .send_message_for_sc(supply_chain_actor_id, payload.to_retry())
             .await?,
-        Ok(Event {
+        Ok(Event::Item {
             item_id,
             item_state: ItemState {
                 state: ItemEventState::ForSale,
@@ -188,6 +199,8 @@ async fn state_consistency() -> Result<()> {
     payload = Action::new(InnerAction::Distributor(DistributorAction::Purchase {
         item_id,
         delivery_time,
+        penalty_schedule: None,
+        approval_time: None,
     }));
 
     println!(
@@ -205,7 +218,7 @@ async fn state_consistency() -> Result<()> {
         client
             .send_message_for_sc(supply_chain_actor_id, payload.to_retry())
             .await?,
-        Ok(Event {
+        Ok(Event::Item {
             item_id,
             item_state: ItemState {
                 state: ItemEventState::Purchased,
@@ -225,7 +238,7 @@ async fn state_consistency() -> Result<()> {
         client
             .send_message_for_sc(supply_chain_actor_id, payload.clone())
             .await?,
-        Ok(Event {
+        Ok(Event::Item {
             item_id,
             item_state: ItemState {
                 state: ItemEventState::Approved,
@@ -243,7 +256,7 @@ async fn state_consistency() -> Result<()> {
                 Action::new(InnerAction::Producer(ProducerAction::Ship(item_id)))
             )
             .await?,
-        Ok(Event {
+        Ok(Event::Item {
             item_id,
             item_state: ItemState {
                 state: ItemEventState::Shipped,
@@ -273,7 +286,7 @@ async fn state_consistency() -> Result<()> {
         client
             .send_message_for_sc(supply_chain_actor_id, payload.to_retry())
             .await?,
-        Ok(Event {
+        Ok(Event::Item {
             item_id,
             item_state: ItemState {
                 state: ItemEventState::Received,
@@ -293,7 +306,7 @@ async fn state_consistency() -> Result<()> {
                 )))
             )
             .await?,
-        Ok(Event {
+        Ok(Event::Item {
             item_id,
             item_state: ItemState {
                 state: ItemEventState::Processed,
@@ -313,7 +326,7 @@ async fn state_consistency() -> Result<()> {
                 )))
             )
             .await?,
-        Ok(Event {
+        Ok(Event::Item {
             item_id,
             item_state: ItemState {
                 state: ItemEventState::Packaged,
@@ -345,6 +358,7 @@ async fn state_consistency() -> Result<()> {
     payload = Action::new(InnerAction::Distributor(DistributorAction::PutUpForSale {
         item_id,
         price,
+        currency: None,
     }));
 
     println!(
@@ -362,7 +376,7 @@ async fn state_consistency() -> Result<()> {
         client
             .send_message_for_sc(supply_chain_actor_id, payload.to_retry())
             .await?,
-        Ok(Event {
+        Ok(Event::Item {
             item_id,
             item_state: ItemState {
                 state: ItemEventState::ForSale,
@@ -376,6 +390,8 @@ async fn state_consistency() -> Result<()> {
     payload = Action::new(InnerAction::Retailer(RetailerAction::Purchase {
         item_id,
         delivery_time,
+        penalty_schedule: None,
+        approval_time: None,
     }));
 
     println!(
@@ -393,7 +409,7 @@ async fn state_consistency() -> Result<()> {
         client
             .send_message_for_sc(supply_chain_actor_id, payload.to_retry())
             .await?,
-        Ok(Event {
+        Ok(Event::Item {
             item_id,
             item_state: ItemState {
                 state: ItemEventState::Purchased,
@@ -413,7 +429,7 @@ async fn state_consistency() -> Result<()> {
         client
             .send_message_for_sc(supply_chain_actor_id, payload.clone())
             .await?,
-        Ok(Event {
+        Ok(Event::Item {
             item_id,
             item_state: ItemState {
                 state: ItemEventState::Approved,
@@ -431,7 +447,7 @@ async fn state_consistency() -> Result<()> {
                 Action::new(InnerAction::Distributor(DistributorAction::Ship(item_id)))
             )
             .await?,
-        Ok(Event {
+        Ok(Event::Item {
             item_id,
             item_state: ItemState {
                 state: ItemEventState::Shipped,
@@ -459,7 +475,7 @@ async fn state_consistency() -> Result<()> {
         client
             .send_message_for_sc(supply_chain_actor_id, payload.to_retry())
             .await?,
-        Ok(Event {
+        Ok(Event::Item {
             item_id,
             item_state: ItemState {
                 state: ItemEventState::Received,
@@ -491,6 +507,7 @@ async fn state_consistency() -> Result<()> {
     payload = Action::new(InnerAction::Retailer(RetailerAction::PutUpForSale {
         item_id,
         price,
+        currency: None,
     }));
 
     println!(
@@ -508,7 +525,7 @@ async fn state_consistency() -> Result<()> {
         client
             .send_message_for_sc(supply_chain_actor_id, payload.to_retry())
             .await?,
-        Ok(Event {
+        Ok(Event::Item {
             item_id,
             item_state: ItemState {
                 state: ItemEventState::ForSale,
@@ -536,7 +553,7 @@ async fn state_consistency() -> Result<()> {
         client
             .send_message_for_sc(supply_chain_actor_id, payload.to_retry())
             .await?,
-        Ok(Event {
+        Ok(Event::Item {
             item_id,
             item_state: ItemState {
                 state: ItemEventState::Purchased,
@@ -0,0 +1,98 @@
+use utils::{prelude::*, FungibleToken, NonFungibleToken};
+
+pub mod utils;
+
+const TTL: u64 = 10000;
+const TTL_IN_BLOCKS: u32 = (TTL / 1000) as _;
+
+fn purchase(item_id: u128) -> Action {
+    Action::new(InnerAction::Distributor(DistributorAction::Purchase {
+        item_id: item_id.into(),
+        delivery_time: DELIVERY_TIME,
+        penalty_schedule: None,
+        approval_time: None,
+    }))
+}
+
+#[test]
+fn tx_ttl() {
+    let system = utils::initialize_system();
+
+    let mut non_fungible_token = NonFungibleToken::initialize(&system);
+    let mut fungible_token = FungibleToken::initialize(&system);
+    let mut supply_chain = SupplyChain::initialize(
+        &system,
+        fungible_token.actor_id(),
+        non_fungible_token.actor_id(),
+    );
+
+    non_fungible_token.add_minter(supply_chain.actor_id());
+    fungible_token.approve(DISTRIBUTOR, supply_chain.actor_id(), ITEM_PRICE);
+
+    supply_chain
+        .set_transaction_ttl(PRODUCER, Some(TTL))
+        .failed(Error::AccessRestricted);
+    supply_chain
+        .set_transaction_ttl(FOREIGN_USER, Some(TTL))
+        .succeed(Some(TTL));
+    supply_chain.state().transaction_ttl().eq(Some(TTL));
+
+    for item_id in [0, 1] {
+        supply_chain.produce(PRODUCER).succeed(item_id);
+        non_fungible_token.approve(PRODUCER, supply_chain.actor_id(), item_id);
+        supply_chain
+            .put_up_for_sale_by_producer(PRODUCER, item_id, ITEM_PRICE)
+            .succeed(item_id);
+        supply_chain
+            .send_action(DISTRIBUTOR, purchase(item_id))
+            .failed(Error::FTTransferFailed);
+    }
+
+    // A sender can purge its own cached action.
+    supply_chain
+        .send_action(DISTRIBUTOR, purchase(1).to_purge())
+        .succeed(Event::TransactionPurged(CachedAction::Purchase(1.into())));
+    supply_chain
+        .state()
+        .cached_actions(DISTRIBUTOR)
+        .eq(vec![CachedAction::Purchase(0.into())]);
+    supply_chain
+        .send_action(DISTRIBUTOR, purchase(1).to_purge())
+        .failed(Error::TxCacheError(
+            TransactionCacheError::TransactionNotFound,
+        ));
+    supply_chain
+        .send_action(FOREIGN_USER, purchase(0).to_purge())
+        .failed(Error::TxCacheError(
+            TransactionCacheError::TransactionNotFound,
+        ));
+
+    // An expired action can't be retried anymore.
+    system.spend_blocks(TTL_IN_BLOCKS + 1);
+    supply_chain.state().cached_actions(DISTRIBUTOR).eq(vec![]);
+    supply_chain
+        .send_action(DISTRIBUTOR, purchase(0).to_retry())
+        .failed(Error::TxCacheError(
+            TransactionCacheError::TransactionNotFound,
+        ));
+
+    // Without a TTL, cached actions never expire.
+    supply_chain
+        .set_transaction_ttl(FOREIGN_USER, None)
+        .succeed(None);
+    supply_chain
+        .send_action(DISTRIBUTOR, purchase(1))
+        .failed(Error::FTTransferFailed);
+    system.spend_blocks(TTL_IN_BLOCKS + 1);
+    fungible_token.mint(DISTRIBUTOR, ITEM_PRICE);
+    supply_chain
+        .send_action(DISTRIBUTOR, purchase(1).to_retry())
+        .succeed(Event::Item {
+            item_id: 1.into(),
+            item_state: ItemState {
+                state: ItemEventState::Purchased,
+                by: Role::Distributor,
+            },
+        });
+    supply_chain.state().cached_actions(DISTRIBUTOR).eq(vec![]);
+}
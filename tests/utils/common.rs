@@ -79,6 +79,11 @@ impl<T, R: Encode, E: Encode> RunResult<T, R, E> {
         assert_contains(&self.result, payload);
     }
 
+    pub fn is_sent_to(&self, destination: u64, payload: impl Encode) -> bool {
+        self.result
+            .contains(&Log::builder().dest(destination).payload(payload))
+    }
+
     #[track_caller]
     pub fn failed(self, error: E) {
         self.assert_contains(Err::<R, E>(error));
@@ -19,6 +19,8 @@ pub const FOREIGN_USER: u64 = 1029384756123;
 pub const PRODUCER: u64 = 5;
 pub const DISTRIBUTOR: u64 = 7;
 pub const RETAILER: u64 = 9;
+pub const ARBITER: u64 = 3;
+pub const RESALE_ROYALTY: u16 = 500;
 
 type SupplyChainRunResult<T> = RunResult<T, Event, Error>;
 
@@ -45,6 +47,15 @@ impl<'a> SupplyChain<'a> {
 
                 fungible_token,
                 non_fungible_token,
+                payment_mode: Default::default(),
+                currencies: vec![],
+
+                penalty_schedule: Default::default(),
+                resale_royalty: RESALE_ROYALTY,
+                platform_fee: None,
+                transaction_ttl: None,
+
+                arbiter: ARBITER.into(),
             },
         )
         .succeed()
@@ -86,14 +97,19 @@ impl<'a> SupplyChain<'a> {
     }
 
     pub fn produce(&mut self, from: u64) -> SupplyChainRunResult<u128> {
+        self.produce_with_royalty(from, 0)
+    }
+
+    pub fn produce_with_royalty(&mut self, from: u64, royalty: u16) -> SupplyChainRunResult<u128> {
         RunResult::new(
             self.0.send(
                 from,
                 Action::new(InnerAction::Producer(ProducerAction::Produce {
                     token_metadata: Default::default(),
+                    royalty,
                 })),
             ),
-            |item_id| Event {
+            |item_id| Event::Item {
                 item_id: item_id.into(),
                 item_state: ItemState {
                     state: ItemEventState::Produced,
@@ -103,11 +119,40 @@ impl<'a> SupplyChain<'a> {
         )
     }
 
+    pub fn produce_batch(&mut self, from: u64, size: usize) -> SupplyChainRunResult<Vec<u128>> {
+        RunResult::new(
+            self.0.send(
+                from,
+                Action::new(InnerAction::Producer(ProducerAction::ProduceBatch {
+                    items: vec![Default::default(); size],
+                    royalty: 0,
+                })),
+            ),
+            |item_ids| Event::Items {
+                item_ids: item_ids.into_iter().map(Into::into).collect(),
+                item_state: ItemState {
+                    state: ItemEventState::Produced,
+                    by: Role::Producer,
+                },
+            },
+        )
+    }
+
     pub fn put_up_for_sale_by_producer(
         &mut self,
         from: u64,
         item_id: u128,
         price: u128,
+    ) -> SupplyChainRunResult<u128> {
+        self.put_up_for_sale_by_producer_with_currency(from, item_id, price, None)
+    }
+
+    pub fn put_up_for_sale_by_producer_with_currency(
+        &mut self,
+        from: u64,
+        item_id: u128,
+        price: u128,
+        currency: Option<ActorId>,
     ) -> SupplyChainRunResult<u128> {
         RunResult::new(
             self.0.send(
@@ -115,9 +160,56 @@ impl<'a> SupplyChain<'a> {
                 Action::new(InnerAction::Producer(ProducerAction::PutUpForSale {
                     item_id: item_id.into(),
                     price,
+                    currency,
+                })),
+            ),
+            |item_id| Event::Item {
+                item_id: item_id.into(),
+                item_state: ItemState {
+                    state: ItemEventState::ForSale,
+                    by: Role::Producer,
+                },
+            },
+        )
+    }
+
+    pub fn withdraw_from_sale_by_producer(
+        &mut self,
+        from: u64,
+        item_id: u128,
+    ) -> SupplyChainRunResult<u128> {
+        RunResult::new(
+            self.0.send(
+                from,
+                Action::new(InnerAction::Producer(ProducerAction::WithdrawFromSale(
+                    item_id.into(),
+                ))),
+            ),
+            |item_id| Event::Item {
+                item_id: item_id.into(),
+                item_state: ItemState {
+                    state: ItemEventState::Produced,
+                    by: Role::Producer,
+                },
+            },
+        )
+    }
+
+    pub fn update_price_by_producer(
+        &mut self,
+        from: u64,
+        item_id: u128,
+        price: u128,
+    ) -> SupplyChainRunResult<u128> {
+        RunResult::new(
+            self.0.send(
+                from,
+                Action::new(InnerAction::Producer(ProducerAction::UpdatePrice {
+                    item_id: item_id.into(),
+                    price,
                 })),
             ),
-            |item_id| Event {
+            |item_id| Event::Item {
                 item_id: item_id.into(),
                 item_state: ItemState {
                     state: ItemEventState::ForSale,
@@ -132,6 +224,17 @@ impl<'a> SupplyChain<'a> {
         from: u64,
         item_id: u128,
         delivery_time: u64,
+    ) -> SupplyChainRunResult<u128> {
+        self.purchase_by_distributor_custom(from, item_id, delivery_time, None, None)
+    }
+
+    pub fn purchase_by_distributor_custom(
+        &mut self,
+        from: u64,
+        item_id: u128,
+        delivery_time: u64,
+        penalty_schedule: Option<PenaltySchedule>,
+        approval_time: Option<u64>,
     ) -> SupplyChainRunResult<u128> {
         RunResult::new(
             self.0.send(
@@ -139,9 +242,39 @@ impl<'a> SupplyChain<'a> {
                 Action::new(InnerAction::Distributor(DistributorAction::Purchase {
                     item_id: item_id.into(),
                     delivery_time,
+                    penalty_schedule,
+                    approval_time,
+                })),
+            ),
+            |item_id| Event::Item {
+                item_id: item_id.into(),
+                item_state: ItemState {
+                    state: ItemEventState::Purchased,
+                    by: Role::Distributor,
+                },
+            },
+        )
+    }
+
+    pub fn purchase_by_distributor_with_value(
+        &mut self,
+        from: u64,
+        item_id: u128,
+        delivery_time: u64,
+        value: u128,
+    ) -> SupplyChainRunResult<u128> {
+        RunResult::new(
+            self.0.send_with_value(
+                from,
+                Action::new(InnerAction::Distributor(DistributorAction::Purchase {
+                    item_id: item_id.into(),
+                    delivery_time,
+                    penalty_schedule: None,
+                    approval_time: None,
                 })),
+                value,
             ),
-            |item_id| Event {
+            |item_id| Event::Item {
                 item_id: item_id.into(),
                 item_state: ItemState {
                     state: ItemEventState::Purchased,
@@ -151,6 +284,28 @@ impl<'a> SupplyChain<'a> {
         )
     }
 
+    pub fn cancel_purchase_by_distributor(
+        &mut self,
+        from: u64,
+        item_id: u128,
+    ) -> SupplyChainRunResult<u128> {
+        RunResult::new(
+            self.0.send(
+                from,
+                Action::new(InnerAction::Distributor(DistributorAction::CancelPurchase(
+                    item_id.into(),
+                ))),
+            ),
+            |item_id| Event::Item {
+                item_id: item_id.into(),
+                item_state: ItemState {
+                    state: ItemEventState::ForSale,
+                    by: Role::Producer,
+                },
+            },
+        )
+    }
+
     pub fn approve_by_producer(
         &mut self,
         from: u64,
@@ -165,7 +320,7 @@ impl<'a> SupplyChain<'a> {
                     approve,
                 })),
             ),
-            |(item_id, approved)| Event {
+            |(item_id, approved)| Event::Item {
                 item_id: item_id.into(),
                 item_state: ItemState {
                     state: bool_to_event(approved),
@@ -181,7 +336,7 @@ impl<'a> SupplyChain<'a> {
                 from,
                 Action::new(InnerAction::Producer(ProducerAction::Ship(item_id.into()))),
             ),
-            |item_id| Event {
+            |item_id| Event::Item {
                 item_id: item_id.into(),
                 item_state: ItemState {
                     state: ItemEventState::Shipped,
@@ -203,7 +358,7 @@ impl<'a> SupplyChain<'a> {
                     item_id.into(),
                 ))),
             ),
-            |item_id| Event {
+            |item_id| Event::Item {
                 item_id: item_id.into(),
                 item_state: ItemState {
                     state: ItemEventState::Received,
@@ -213,6 +368,30 @@ impl<'a> SupplyChain<'a> {
         )
     }
 
+    pub fn dispute_by_distributor(
+        &mut self,
+        from: u64,
+        item_id: u128,
+        reason: &str,
+    ) -> SupplyChainRunResult<u128> {
+        RunResult::new(
+            self.0.send(
+                from,
+                Action::new(InnerAction::Distributor(DistributorAction::Dispute {
+                    item_id: item_id.into(),
+                    reason: reason.into(),
+                })),
+            ),
+            |item_id| Event::Item {
+                item_id: item_id.into(),
+                item_state: ItemState {
+                    state: ItemEventState::Disputed,
+                    by: Role::Distributor,
+                },
+            },
+        )
+    }
+
     pub fn process(&mut self, from: u64, item_id: u128) -> SupplyChainRunResult<u128> {
         RunResult::new(
             self.0.send(
@@ -221,7 +400,7 @@ impl<'a> SupplyChain<'a> {
                     item_id.into(),
                 ))),
             ),
-            |item_id| Event {
+            |item_id| Event::Item {
                 item_id: item_id.into(),
                 item_state: ItemState {
                     state: ItemEventState::Processed,
@@ -239,7 +418,7 @@ impl<'a> SupplyChain<'a> {
                     item_id.into(),
                 ))),
             ),
-            |item_id| Event {
+            |item_id| Event::Item {
                 item_id: item_id.into(),
                 item_state: ItemState {
                     state: ItemEventState::Packaged,
@@ -261,9 +440,56 @@ impl<'a> SupplyChain<'a> {
                 Action::new(InnerAction::Distributor(DistributorAction::PutUpForSale {
                     item_id: item_id.into(),
                     price,
+                    currency: None,
+                })),
+            ),
+            |item_id| Event::Item {
+                item_id: item_id.into(),
+                item_state: ItemState {
+                    state: ItemEventState::ForSale,
+                    by: Role::Distributor,
+                },
+            },
+        )
+    }
+
+    pub fn withdraw_from_sale_by_distributor(
+        &mut self,
+        from: u64,
+        item_id: u128,
+    ) -> SupplyChainRunResult<u128> {
+        RunResult::new(
+            self.0.send(
+                from,
+                Action::new(InnerAction::Distributor(
+                    DistributorAction::WithdrawFromSale(item_id.into()),
+                )),
+            ),
+            |item_id| Event::Item {
+                item_id: item_id.into(),
+                item_state: ItemState {
+                    state: ItemEventState::Packaged,
+                    by: Role::Distributor,
+                },
+            },
+        )
+    }
+
+    pub fn update_price_by_distributor(
+        &mut self,
+        from: u64,
+        item_id: u128,
+        price: u128,
+    ) -> SupplyChainRunResult<u128> {
+        RunResult::new(
+            self.0.send(
+                from,
+                Action::new(InnerAction::Distributor(DistributorAction::UpdatePrice {
+                    item_id: item_id.into(),
+                    price,
                 })),
             ),
-            |item_id| Event {
+            |item_id| Event::Item {
                 item_id: item_id.into(),
                 item_state: ItemState {
                     state: ItemEventState::ForSale,
@@ -278,6 +504,17 @@ impl<'a> SupplyChain<'a> {
         from: u64,
         item_id: u128,
         delivery_time: u64,
+    ) -> SupplyChainRunResult<u128> {
+        self.purchase_by_retailer_custom(from, item_id, delivery_time, None, None)
+    }
+
+    pub fn purchase_by_retailer_custom(
+        &mut self,
+        from: u64,
+        item_id: u128,
+        delivery_time: u64,
+        penalty_schedule: Option<PenaltySchedule>,
+        approval_time: Option<u64>,
     ) -> SupplyChainRunResult<u128> {
         RunResult::new(
             self.0.send(
@@ -285,9 +522,11 @@ impl<'a> SupplyChain<'a> {
                 Action::new(InnerAction::Retailer(RetailerAction::Purchase {
                     item_id: item_id.into(),
                     delivery_time,
+                    penalty_schedule,
+                    approval_time,
                 })),
             ),
-            |item_id| Event {
+            |item_id| Event::Item {
                 item_id: item_id.into(),
                 item_state: ItemState {
                     state: ItemEventState::Purchased,
@@ -297,6 +536,28 @@ impl<'a> SupplyChain<'a> {
         )
     }
 
+    pub fn cancel_purchase_by_retailer(
+        &mut self,
+        from: u64,
+        item_id: u128,
+    ) -> SupplyChainRunResult<u128> {
+        RunResult::new(
+            self.0.send(
+                from,
+                Action::new(InnerAction::Retailer(RetailerAction::CancelPurchase(
+                    item_id.into(),
+                ))),
+            ),
+            |item_id| Event::Item {
+                item_id: item_id.into(),
+                item_state: ItemState {
+                    state: ItemEventState::ForSale,
+                    by: Role::Distributor,
+                },
+            },
+        )
+    }
+
     pub fn approve_by_distributor(
         &mut self,
         from: u64,
@@ -311,7 +572,7 @@ impl<'a> SupplyChain<'a> {
                     approve,
                 })),
             ),
-            |(item_id, approved)| Event {
+            |(item_id, approved)| Event::Item {
                 item_id: item_id.into(),
                 item_state: ItemState {
                     state: bool_to_event(approved),
@@ -329,7 +590,7 @@ impl<'a> SupplyChain<'a> {
                     item_id.into(),
                 ))),
             ),
-            |item_id| Event {
+            |item_id| Event::Item {
                 item_id: item_id.into(),
                 item_state: ItemState {
                     state: ItemEventState::Shipped,
@@ -347,7 +608,7 @@ impl<'a> SupplyChain<'a> {
                     item_id.into(),
                 ))),
             ),
-            |item_id| Event {
+            |item_id| Event::Item {
                 item_id: item_id.into(),
                 item_state: ItemState {
                     state: ItemEventState::Received,
@@ -357,6 +618,30 @@ impl<'a> SupplyChain<'a> {
         )
     }
 
+    pub fn dispute_by_retailer(
+        &mut self,
+        from: u64,
+        item_id: u128,
+        reason: &str,
+    ) -> SupplyChainRunResult<u128> {
+        RunResult::new(
+            self.0.send(
+                from,
+                Action::new(InnerAction::Retailer(RetailerAction::Dispute {
+                    item_id: item_id.into(),
+                    reason: reason.into(),
+                })),
+            ),
+            |item_id| Event::Item {
+                item_id: item_id.into(),
+                item_state: ItemState {
+                    state: ItemEventState::Disputed,
+                    by: Role::Retailer,
+                },
+            },
+        )
+    }
+
     pub fn put_up_for_sale_by_retailer(
         &mut self,
         from: u64,
@@ -369,9 +654,56 @@ impl<'a> SupplyChain<'a> {
                 Action::new(InnerAction::Retailer(RetailerAction::PutUpForSale {
                     item_id: item_id.into(),
                     price,
+                    currency: None,
+                })),
+            ),
+            |item_id| Event::Item {
+                item_id: item_id.into(),
+                item_state: ItemState {
+                    state: ItemEventState::ForSale,
+                    by: Role::Retailer,
+                },
+            },
+        )
+    }
+
+    pub fn withdraw_from_sale_by_retailer(
+        &mut self,
+        from: u64,
+        item_id: u128,
+    ) -> SupplyChainRunResult<u128> {
+        RunResult::new(
+            self.0.send(
+                from,
+                Action::new(InnerAction::Retailer(RetailerAction::WithdrawFromSale(
+                    item_id.into(),
+                ))),
+            ),
+            |item_id| Event::Item {
+                item_id: item_id.into(),
+                item_state: ItemState {
+                    state: ItemEventState::Received,
+                    by: Role::Retailer,
+                },
+            },
+        )
+    }
+
+    pub fn update_price_by_retailer(
+        &mut self,
+        from: u64,
+        item_id: u128,
+        price: u128,
+    ) -> SupplyChainRunResult<u128> {
+        RunResult::new(
+            self.0.send(
+                from,
+                Action::new(InnerAction::Retailer(RetailerAction::UpdatePrice {
+                    item_id: item_id.into(),
+                    price,
                 })),
             ),
-            |item_id| Event {
+            |item_id| Event::Item {
                 item_id: item_id.into(),
                 item_state: ItemState {
                     state: ItemEventState::ForSale,
@@ -389,7 +721,7 @@ impl<'a> SupplyChain<'a> {
                     item_id.into(),
                 ))),
             ),
-            |item_id| Event {
+            |item_id| Event::Item {
                 item_id: item_id.into(),
                 item_state: ItemState {
                     state: ItemEventState::Purchased,
@@ -398,12 +730,392 @@ impl<'a> SupplyChain<'a> {
             },
         )
     }
-}
 
-pub struct SupplyChainState<'a>(&'a InnerProgram<'a>);
-
-impl SupplyChainState<'_> {
-    fn query_state_common<A: Encode, T: Decode>(
+    pub fn purchase_by_consumer_with_value(
+        &mut self,
+        from: u64,
+        item_id: u128,
+        value: u128,
+    ) -> SupplyChainRunResult<u128> {
+        RunResult::new(
+            self.0.send_with_value(
+                from,
+                Action::new(InnerAction::Consumer(ConsumerAction::Purchase(
+                    item_id.into(),
+                ))),
+                value,
+            ),
+            |item_id| Event::Item {
+                item_id: item_id.into(),
+                item_state: ItemState {
+                    state: ItemEventState::Purchased,
+                    by: Role::Consumer,
+                },
+            },
+        )
+    }
+
+    pub fn put_up_for_sale_by_consumer(
+        &mut self,
+        from: u64,
+        item_id: u128,
+        price: u128,
+    ) -> SupplyChainRunResult<u128> {
+        RunResult::new(
+            self.0.send(
+                from,
+                Action::new(InnerAction::Consumer(ConsumerAction::PutUpForSale {
+                    item_id: item_id.into(),
+                    price,
+                    currency: None,
+                })),
+            ),
+            |item_id| Event::Item {
+                item_id: item_id.into(),
+                item_state: ItemState {
+                    state: ItemEventState::ForSale,
+                    by: Role::Consumer,
+                },
+            },
+        )
+    }
+
+    pub fn withdraw_from_sale_by_consumer(
+        &mut self,
+        from: u64,
+        item_id: u128,
+    ) -> SupplyChainRunResult<u128> {
+        RunResult::new(
+            self.0.send(
+                from,
+                Action::new(InnerAction::Consumer(ConsumerAction::WithdrawFromSale(
+                    item_id.into(),
+                ))),
+            ),
+            |item_id| Event::Item {
+                item_id: item_id.into(),
+                item_state: ItemState {
+                    state: ItemEventState::Purchased,
+                    by: Role::Consumer,
+                },
+            },
+        )
+    }
+
+    pub fn update_price_by_consumer(
+        &mut self,
+        from: u64,
+        item_id: u128,
+        price: u128,
+    ) -> SupplyChainRunResult<u128> {
+        RunResult::new(
+            self.0.send(
+                from,
+                Action::new(InnerAction::Consumer(ConsumerAction::UpdatePrice {
+                    item_id: item_id.into(),
+                    price,
+                })),
+            ),
+            |item_id| Event::Item {
+                item_id: item_id.into(),
+                item_state: ItemState {
+                    state: ItemEventState::ForSale,
+                    by: Role::Consumer,
+                },
+            },
+        )
+    }
+
+    pub fn fail_delivery(
+        &mut self,
+        from: u64,
+        item_id: u128,
+    ) -> SupplyChainRunResult<(u128, Role)> {
+        RunResult::new(
+            self.0.send(
+                from,
+                Action::new(InnerAction::Deadline(DeadlineAction::FailDelivery(
+                    item_id.into(),
+                ))),
+            ),
+            |(item_id, by)| Event::Item {
+                item_id: item_id.into(),
+                item_state: ItemState {
+                    state: ItemEventState::DeliveryFailed,
+                    by,
+                },
+            },
+        )
+    }
+
+    pub fn expire_purchase(
+        &mut self,
+        from: u64,
+        item_id: u128,
+    ) -> SupplyChainRunResult<(u128, Role)> {
+        RunResult::new(
+            self.0.send(
+                from,
+                Action::new(InnerAction::Deadline(DeadlineAction::ExpirePurchase(
+                    item_id.into(),
+                ))),
+            ),
+            |(item_id, by)| Event::Item {
+                item_id: item_id.into(),
+                item_state: ItemState {
+                    state: ItemEventState::ForSale,
+                    by,
+                },
+            },
+        )
+    }
+
+    pub fn resolve(
+        &mut self,
+        from: u64,
+        item_id: u128,
+        seller_share: u16,
+    ) -> SupplyChainRunResult<(u128, ItemState)> {
+        RunResult::new(
+            self.0.send(
+                from,
+                Action::new(InnerAction::Arbiter(ArbiterAction::Resolve {
+                    item_id: item_id.into(),
+                    seller_share,
+                })),
+            ),
+            |(item_id, item_state)| Event::Item {
+                item_id: item_id.into(),
+                item_state,
+            },
+        )
+    }
+
+    pub fn add_participant(
+        &mut self,
+        from: u64,
+        role: Role,
+        participant: u64,
+    ) -> SupplyChainRunResult<(Role, u64)> {
+        RunResult::new(
+            self.0.send(
+                from,
+                Action::new(InnerAction::Admin(AdminAction::AddParticipant {
+                    role,
+                    participant: participant.into(),
+                })),
+            ),
+            |(role, participant)| Event::ParticipantAdded {
+                role,
+                participant: participant.into(),
+            },
+        )
+    }
+
+    pub fn remove_participant(
+        &mut self,
+        from: u64,
+        role: Role,
+        participant: u64,
+    ) -> SupplyChainRunResult<(Role, u64)> {
+        RunResult::new(
+            self.0.send(
+                from,
+                Action::new(InnerAction::Admin(AdminAction::RemoveParticipant {
+                    role,
+                    participant: participant.into(),
+                })),
+            ),
+            |(role, participant)| Event::ParticipantRemoved {
+                role,
+                participant: participant.into(),
+            },
+        )
+    }
+
+    pub fn subscribe(
+        &mut self,
+        from: u64,
+        filter: SubscriptionFilter,
+    ) -> SupplyChainRunResult<(u64, SubscriptionFilter)> {
+        RunResult::new(
+            self.0.send(
+                from,
+                Action::new(InnerAction::Subscriber(SubscriberAction::Subscribe(filter))),
+            ),
+            |(subscriber, filter)| Event::Subscribed {
+                subscriber: subscriber.into(),
+                filter,
+            },
+        )
+    }
+
+    pub fn unsubscribe(&mut self, from: u64) -> SupplyChainRunResult<u64> {
+        RunResult::new(
+            self.0.send(
+                from,
+                Action::new(InnerAction::Subscriber(SubscriberAction::Unsubscribe)),
+            ),
+            |subscriber| Event::Unsubscribed(subscriber.into()),
+        )
+    }
+
+    pub fn fund_recall_pool(
+        &mut self,
+        from: u64,
+        amount: u128,
+    ) -> SupplyChainRunResult<(u64, u128)> {
+        RunResult::new(
+            self.0.send(
+                from,
+                Action::new(InnerAction::Producer(ProducerAction::FundRecallPool(
+                    amount,
+                ))),
+            ),
+            |(producer, amount)| Event::RecallPoolFunded {
+                producer: producer.into(),
+                amount,
+            },
+        )
+    }
+
+    pub fn recall(
+        &mut self,
+        from: u64,
+        item_ids: &[u128],
+        reason: &str,
+    ) -> SupplyChainRunResult<(Vec<u128>, Vec<u128>)> {
+        RunResult::new(
+            self.0.send(
+                from,
+                Action::new(InnerAction::Producer(ProducerAction::Recall {
+                    item_ids: item_ids.iter().copied().map(Into::into).collect(),
+                    reason: reason.into(),
+                })),
+            ),
+            |(item_ids, refunded_item_ids)| Event::Recalled {
+                item_ids: item_ids.into_iter().map(Into::into).collect(),
+                refunded_item_ids: refunded_item_ids.into_iter().map(Into::into).collect(),
+            },
+        )
+    }
+
+    pub fn lot_action(
+        &mut self,
+        from: u64,
+        action: LotAction,
+    ) -> SupplyChainRunResult<(u64, Vec<u128>, ItemState)> {
+        RunResult::new(
+            self.0.send(from, Action::new(InnerAction::Lot(action))),
+            |(lot_id, item_ids, item_state)| Event::Lot {
+                lot_id,
+                item_ids: item_ids.into_iter().map(Into::into).collect(),
+                item_state,
+            },
+        )
+    }
+
+    pub fn split_lot(&mut self, from: u64, lot_id: u64) -> SupplyChainRunResult<(u64, Vec<u128>)> {
+        RunResult::new(
+            self.0.send(
+                from,
+                Action::new(InnerAction::Lot(LotAction::Split(lot_id))),
+            ),
+            |(lot_id, item_ids)| Event::LotSplit {
+                lot_id,
+                item_ids: item_ids.into_iter().map(Into::into).collect(),
+            },
+        )
+    }
+
+    pub fn offer_action(&mut self, from: u64, action: OfferAction) -> SupplyChainRunResult<Event> {
+        RunResult::new(
+            self.0.send(from, Action::new(InnerAction::Offer(action))),
+            |event| event,
+        )
+    }
+
+    pub fn auction_action(
+        &mut self,
+        from: u64,
+        action: AuctionAction,
+    ) -> SupplyChainRunResult<Event> {
+        RunResult::new(
+            self.0.send(from, Action::new(InnerAction::Auction(action))),
+            |event| event,
+        )
+    }
+
+    pub fn settle_auction(
+        &mut self,
+        from: u64,
+        item_id: u128,
+    ) -> SupplyChainRunResult<(u128, ItemState)> {
+        RunResult::new(
+            self.0.send(
+                from,
+                Action::new(InnerAction::Deadline(DeadlineAction::SettleAuction(
+                    item_id.into(),
+                ))),
+            ),
+            |(item_id, item_state)| Event::Item {
+                item_id: item_id.into(),
+                item_state,
+            },
+        )
+    }
+
+    pub fn send_action(&mut self, from: u64, action: Action) -> SupplyChainRunResult<Event> {
+        RunResult::new(self.0.send(from, action), |event| event)
+    }
+
+    pub fn set_platform_fee(
+        &mut self,
+        from: u64,
+        platform_fee: Option<PlatformFee>,
+    ) -> SupplyChainRunResult<Option<PlatformFee>> {
+        RunResult::new(
+            self.0.send(
+                from,
+                Action::new(InnerAction::Admin(AdminAction::SetPlatformFee(
+                    platform_fee,
+                ))),
+            ),
+            Event::PlatformFeeChanged,
+        )
+    }
+
+    pub fn set_transaction_ttl(
+        &mut self,
+        from: u64,
+        transaction_ttl: Option<u64>,
+    ) -> SupplyChainRunResult<Option<u64>> {
+        RunResult::new(
+            self.0.send(
+                from,
+                Action::new(InnerAction::Admin(AdminAction::SetTransactionTtl(
+                    transaction_ttl,
+                ))),
+            ),
+            Event::TransactionTtlChanged,
+        )
+    }
+
+    pub fn change_admin(&mut self, from: u64, admin: u64) -> SupplyChainRunResult<u64> {
+        RunResult::new(
+            self.0.send(
+                from,
+                Action::new(InnerAction::Admin(AdminAction::ChangeAdmin(admin.into()))),
+            ),
+            |admin| Event::AdminChanged(admin.into()),
+        )
+    }
+}
+
+pub struct SupplyChainState<'a>(&'a InnerProgram<'a>);
+
+impl SupplyChainState<'_> {
+    fn query_state_common<A: Encode, T: Decode>(
         self,
         fn_index: usize,
         argument: Option<A>,
@@ -431,6 +1143,10 @@ impl SupplyChainState<'_> {
         StateReply(self.item_info(item_id).0.map(|item_info| item_info.price))
     }
 
+    pub fn item_state(self, item_id: u128) -> StateReply<Option<ItemState>> {
+        StateReply(self.item_info(item_id).0.map(|item_info| item_info.state))
+    }
+
     pub fn item_info(self, item_id: u128) -> StateReply<Option<ItemInfo>> {
         self.query_state_with_argument(1, ItemId::from(item_id))
     }
@@ -453,6 +1169,64 @@ impl SupplyChainState<'_> {
         self.query_state(6)
     }
 
+    pub fn penalty_schedule(self) -> StateReply<PenaltySchedule> {
+        self.query_state(8)
+    }
+
+    pub fn item_history(self, item_id: u128) -> StateReply<Option<Vec<HistoryRecord>>> {
+        self.query_state_with_argument(9, ItemId::from(item_id))
+    }
+
+    pub fn items_page(self, filter: ItemFilter, offset: u32, limit: u32) -> StateReply<ItemsPage> {
+        self.query_state_with_argument(10, (filter, offset, limit))
+    }
+
+    pub fn subscribers(self) -> StateReply<HashMap<ActorId, SubscriptionFilter>> {
+        let result: StateReply<Vec<_>> = self.query_state(11);
+
+        result.into()
+    }
+
+    pub fn lot_info(self, lot_id: u64) -> StateReply<Option<LotInfo>> {
+        self.query_state_with_argument(12, lot_id)
+    }
+
+    pub fn recalled_items(self) -> StateReply<Vec<(ItemId, String)>> {
+        self.query_state(13)
+    }
+
+    pub fn platform_fee(self) -> StateReply<Option<PlatformFee>> {
+        self.query_state(14)
+    }
+
+    pub fn collected_fees(self) -> StateReply<HashMap<ActorId, u128>> {
+        let result: StateReply<Vec<_>> = self.query_state(15);
+
+        result.into()
+    }
+
+    pub fn currencies(self) -> StateReply<Vec<ActorId>> {
+        self.query_state(16)
+    }
+
+    pub fn offers(self, item_id: u128) -> StateReply<HashMap<ActorId, Offer>> {
+        let result: StateReply<Vec<_>> = self.query_state_with_argument(17, ItemId::from(item_id));
+
+        result.into()
+    }
+
+    pub fn auction_info(self, item_id: u128) -> StateReply<Option<Auction>> {
+        self.query_state_with_argument(18, ItemId::from(item_id))
+    }
+
+    pub fn cached_actions(self, actor_id: u64) -> StateReply<Vec<CachedAction>> {
+        self.query_state_with_argument(19, ActorId::from(actor_id))
+    }
+
+    pub fn transaction_ttl(self) -> StateReply<Option<u64>> {
+        self.query_state(20)
+    }
+
     pub fn roles(self, actor_id: u64) -> StateReply<HashSet<Role>> {
         let result: StateReply<Vec<_>> = self.query_state_with_argument(3, ActorId::from(actor_id));
 
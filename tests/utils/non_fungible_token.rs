@@ -24,6 +24,10 @@ impl TransactionalProgram for NonFungibleToken<'_> {
 
 impl<'a> NonFungibleToken<'a> {
     pub fn initialize(system: &'a System) -> Self {
+        Self::initialize_with_max_mint_count(system, None)
+    }
+
+    pub fn initialize_with_max_mint_count(system: &'a System, max_mint_count: Option<u32>) -> Self {
         let program = InnerProgram::from_file(system, NFT_BINARY);
 
         assert!(!program
@@ -33,8 +37,8 @@ impl<'a> NonFungibleToken<'a> {
                     royalties: Default::default(),
                     collection: Default::default(),
                     constraints: Constraints {
+                        max_mint_count,
                         authorized_minters: vec![FOREIGN_USER.into()],
-                        ..Default::default()
                     },
                 },
             )
@@ -1,4 +1,7 @@
-pub use super::{common::Program, SupplyChain, DISTRIBUTOR, FOREIGN_USER, PRODUCER, RETAILER};
+pub use super::{
+    common::Program, SupplyChain, ARBITER, DISTRIBUTOR, FOREIGN_USER, PRODUCER, RESALE_ROYALTY,
+    RETAILER,
+};
 pub use gstd::{prelude::*, ActorId};
 pub use supply_chain_io::*;
 
@@ -0,0 +1,111 @@
+use gtest::System;
+use utils::{prelude::*, NonFungibleToken};
+
+pub mod utils;
+
+fn balance(system: &System, supply_chain: &SupplyChain) -> u128 {
+    system.balance_of(supply_chain.inner_program().id())
+}
+
+#[test]
+fn value_payment() {
+    let system = utils::initialize_system();
+
+    let mut non_fungible_token = NonFungibleToken::initialize(&system);
+    let mut supply_chain = SupplyChain::initialize_custom_with_existential_deposit(
+        &system,
+        Initialize {
+            producers: vec![PRODUCER.into()],
+            distributors: vec![DISTRIBUTOR.into()],
+            retailers: vec![RETAILER.into()],
+
+            fungible_token: ActorId::zero(),
+            non_fungible_token: non_fungible_token.actor_id(),
+            payment_mode: PaymentMode::Value,
+            currencies: vec![],
+
+            penalty_schedule: Default::default(),
+            resale_royalty: Default::default(),
+            platform_fee: None,
+            transaction_ttl: None,
+
+            arbiter: ARBITER.into(),
+        },
+    )
+    .succeed();
+
+    non_fungible_token.add_minter(supply_chain.actor_id());
+    system.mint_to(DISTRIBUTOR, ITEM_PRICE * 2);
+
+    let initial_balance = balance(&system, &supply_chain);
+
+    supply_chain.produce(PRODUCER).succeed(0);
+    non_fungible_token.approve(PRODUCER, supply_chain.actor_id(), 0);
+    supply_chain
+        .put_up_for_sale_by_producer(PRODUCER, 0, ITEM_PRICE)
+        .succeed(0);
+
+    // The attached value must be equal to the item price, otherwise it's
+    // refunded.
+    supply_chain
+        .purchase_by_distributor_with_value(DISTRIBUTOR, 0, DELIVERY_TIME, ITEM_PRICE - 1)
+        .failed(Error::UnexpectedValue);
+    assert_eq!(balance(&system, &supply_chain), initial_balance);
+    supply_chain
+        .purchase_by_distributor_with_value(DISTRIBUTOR, 0, DELIVERY_TIME, ITEM_PRICE)
+        .succeed(0);
+    assert_eq!(
+        balance(&system, &supply_chain),
+        initial_balance + ITEM_PRICE
+    );
+
+    supply_chain
+        .approve_by_producer(PRODUCER, 0, true)
+        .succeed((0, true));
+    supply_chain.ship_by_producer(PRODUCER, 0).succeed(0);
+    supply_chain
+        .receive_by_distributor(DISTRIBUTOR, 0)
+        .succeed(0);
+    assert_eq!(balance(&system, &supply_chain), initial_balance);
+
+    // Only NFT transfers of a failed action are compensated by an abort, since
+    // value transfers are sent only after an action succeeds.
+    supply_chain.produce_batch(PRODUCER, 2).succeed(vec![1, 2]);
+    supply_chain
+        .lot_action(PRODUCER, LotAction::Create(vec![1.into(), 2.into()]))
+        .succeed((
+            0,
+            vec![1, 2],
+            ItemState {
+                state: ItemEventState::Produced,
+                by: Role::Producer,
+            },
+        ));
+    non_fungible_token.approve(PRODUCER, supply_chain.actor_id(), 1);
+
+    let put_up_for_sale = Action::new(InnerAction::Lot(LotAction::PutUpForSale {
+        lot_id: 0,
+        price: ITEM_PRICE,
+        currency: None,
+    }));
+
+    // The second NFT of the lot isn't approved, so the action fails after the
+    // first one is transferred.
+    let _ = supply_chain.send_action(PRODUCER, put_up_for_sale.clone());
+    supply_chain
+        .state()
+        .cached_actions(PRODUCER)
+        .eq(vec![CachedAction::Lot(0)]);
+    non_fungible_token
+        .meta_state()
+        .owner_id(1)
+        .eq(supply_chain.actor_id());
+    supply_chain
+        .send_action(PRODUCER, put_up_for_sale.to_abort())
+        .succeed(Event::TransactionAborted(CachedAction::Lot(0)));
+    non_fungible_token
+        .meta_state()
+        .owner_id(1)
+        .eq(PRODUCER.into());
+    assert_eq!(balance(&system, &supply_chain), initial_balance);
+}
@@ -0,0 +1,138 @@
+use utils::{prelude::*, FungibleToken, NonFungibleToken};
+
+pub mod utils;
+
+const NEW_ITEM_PRICE: u128 = ITEM_PRICE / 2;
+
+#[test]
+fn withdraw_from_sale_and_update_price() {
+    let system = utils::initialize_system();
+
+    let mut non_fungible_token = NonFungibleToken::initialize(&system);
+    let mut fungible_token = FungibleToken::initialize(&system);
+    let mut supply_chain = SupplyChain::initialize(
+        &system,
+        fungible_token.actor_id(),
+        non_fungible_token.actor_id(),
+    );
+
+    non_fungible_token.add_minter(supply_chain.actor_id());
+
+    for from in [DISTRIBUTOR, RETAILER] {
+        fungible_token.mint(from, NEW_ITEM_PRICE);
+        fungible_token.approve(from, supply_chain.actor_id(), NEW_ITEM_PRICE);
+    }
+
+    supply_chain.produce(PRODUCER).succeed(0);
+
+    supply_chain
+        .withdraw_from_sale_by_producer(PRODUCER, 0)
+        .failed(Error::UnexpectedItemState);
+    supply_chain
+        .update_price_by_producer(PRODUCER, 0, NEW_ITEM_PRICE)
+        .failed(Error::UnexpectedItemState);
+
+    non_fungible_token.approve(PRODUCER, supply_chain.actor_id(), 0);
+    supply_chain
+        .put_up_for_sale_by_producer(PRODUCER, 0, ITEM_PRICE)
+        .succeed(0);
+    supply_chain
+        .withdraw_from_sale_by_producer(FOREIGN_USER, 0)
+        .failed(Error::AccessRestricted);
+    supply_chain
+        .withdraw_from_sale_by_producer(PRODUCER, 0)
+        .succeed(0);
+    non_fungible_token
+        .meta_state()
+        .owner_id(0)
+        .eq(PRODUCER.into());
+
+    non_fungible_token.approve(PRODUCER, supply_chain.actor_id(), 0);
+    supply_chain
+        .put_up_for_sale_by_producer(PRODUCER, 0, ITEM_PRICE)
+        .succeed(0);
+    supply_chain
+        .update_price_by_producer(FOREIGN_USER, 0, NEW_ITEM_PRICE)
+        .failed(Error::AccessRestricted);
+    supply_chain
+        .update_price_by_producer(PRODUCER, 0, NEW_ITEM_PRICE)
+        .succeed(0);
+    supply_chain.state().item_price(0).eq(Some(NEW_ITEM_PRICE));
+    non_fungible_token
+        .meta_state()
+        .owner_id(0)
+        .eq(supply_chain.actor_id());
+
+    supply_chain
+        .purchase_by_distributor(DISTRIBUTOR, 0, DELIVERY_TIME)
+        .succeed(0);
+    // A purchased item can't be withdrawn from sale or repriced.
+    supply_chain
+        .withdraw_from_sale_by_producer(PRODUCER, 0)
+        .failed(Error::UnexpectedItemState);
+    supply_chain
+        .update_price_by_producer(PRODUCER, 0, ITEM_PRICE)
+        .failed(Error::UnexpectedItemState);
+    fungible_token
+        .balance(supply_chain.actor_id())
+        .contains(NEW_ITEM_PRICE);
+
+    supply_chain
+        .approve_by_producer(PRODUCER, 0, true)
+        .succeed((0, true));
+    supply_chain.ship_by_producer(PRODUCER, 0).succeed(0);
+    supply_chain
+        .receive_by_distributor(DISTRIBUTOR, 0)
+        .succeed(0);
+    supply_chain.process(DISTRIBUTOR, 0).succeed(0);
+    supply_chain.package(DISTRIBUTOR, 0).succeed(0);
+
+    non_fungible_token.approve(DISTRIBUTOR, supply_chain.actor_id(), 0);
+    supply_chain
+        .put_up_for_sale_by_distributor(DISTRIBUTOR, 0, ITEM_PRICE)
+        .succeed(0);
+    supply_chain
+        .withdraw_from_sale_by_distributor(DISTRIBUTOR, 0)
+        .succeed(0);
+    non_fungible_token
+        .meta_state()
+        .owner_id(0)
+        .eq(DISTRIBUTOR.into());
+
+    non_fungible_token.approve(DISTRIBUTOR, supply_chain.actor_id(), 0);
+    supply_chain
+        .put_up_for_sale_by_distributor(DISTRIBUTOR, 0, ITEM_PRICE)
+        .succeed(0);
+    supply_chain
+        .update_price_by_distributor(DISTRIBUTOR, 0, NEW_ITEM_PRICE)
+        .succeed(0);
+    supply_chain.state().item_price(0).eq(Some(NEW_ITEM_PRICE));
+
+    supply_chain
+        .purchase_by_retailer(RETAILER, 0, DELIVERY_TIME)
+        .succeed(0);
+    supply_chain
+        .approve_by_distributor(DISTRIBUTOR, 0, true)
+        .succeed((0, true));
+    supply_chain.ship_by_distributor(DISTRIBUTOR, 0).succeed(0);
+    supply_chain.receive_by_retailer(RETAILER, 0).succeed(0);
+
+    non_fungible_token.approve(RETAILER, supply_chain.actor_id(), 0);
+    supply_chain
+        .put_up_for_sale_by_retailer(RETAILER, 0, ITEM_PRICE)
+        .succeed(0);
+    supply_chain
+        .update_price_by_retailer(RETAILER, 0, NEW_ITEM_PRICE)
+        .succeed(0);
+    supply_chain.state().item_price(0).eq(Some(NEW_ITEM_PRICE));
+    supply_chain
+        .withdraw_from_sale_by_retailer(RETAILER, 0)
+        .succeed(0);
+    non_fungible_token
+        .meta_state()
+        .owner_id(0)
+        .eq(RETAILER.into());
+    supply_chain
+        .purchase_by_consumer(CONSUMER, 0)
+        .failed(Error::UnexpectedItemState);
+}